@@ -88,9 +88,7 @@ botan_ffi_functions! {
 
     #[cfg(botan_ffi_20260303)]
     pub fn botan_ec_group_unregister(oid: botan_asn1_oid_t) -> c_int;
-}
 
-botan_ffi_functions! {
     #[cfg(botan_ffi_20260506)]
     pub fn botan_ec_scalar_destroy(ec_scalar: botan_ec_scalar_t) -> c_int;
 
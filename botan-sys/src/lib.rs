@@ -30,7 +30,11 @@ mod macros;
 mod loader;
 
 #[cfg(feature = "dynamic-loading")]
-pub use loader::{LoadError, last_load_error, load_library, loaded_library_name};
+pub use loader::{
+    FfiLevelSymbols, FfiSymbolReport, LoadError, LoadReport, LoaderConfig, RejectedCandidate,
+    botan_release_for_ffi_level, ffi_symbol_report, last_load_error, load_library,
+    loaded_library_name,
+};
 
 mod block;
 mod cipher;
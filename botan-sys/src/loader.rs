@@ -7,7 +7,7 @@
 //! explicitly), and each function is resolved lazily on first use.
 
 use std::boxed::Box;
-use std::ffi::OsStr;
+use std::ffi::{OsStr, OsString};
 use std::fmt;
 use std::format;
 use std::string::{String, ToString};
@@ -15,9 +15,26 @@ use std::sync::{Mutex, OnceLock};
 use std::vec::Vec;
 
 use libloading::Library;
+use sha2::{Digest, Sha256};
 
 /// The oldest FFI API version this crate supports (Botan 2.13)
-const MINIMUM_FFI_VERSION: u32 = 20191214;
+pub(crate) const MINIMUM_FFI_VERSION: u32 = 20191214;
+
+/// The FFI API versions this crate knows of, and the Botan release which
+/// introduced each
+const FFI_LEVEL_RELEASES: [(u32, &str); 11] = [
+    (20191214, "2.13"),
+    (20210220, "2.18"),
+    (20230403, "3.0"),
+    (20230711, "3.1"),
+    (20231009, "3.2"),
+    (20240408, "3.4"),
+    (20250506, "3.8"),
+    (20250829, "3.10"),
+    (20260303, "3.11"),
+    (20260506, "3.12"),
+    (20260811, "3.13"),
+];
 
 /// The highest Botan 3 ABI revision (`libbotan-3.so.N`) that the default
 /// search will try. New releases are quarterly, and likely the release
@@ -35,6 +52,33 @@ pub enum LoadError {
     LoadFailed(String),
     /// A library was loaded, but it is not a usable version of Botan
     UnusableLibrary(String),
+    /// A library was loaded, but its version is outside the range allowed
+    /// by the [`LoaderConfig`]
+    VersionNotAllowed {
+        /// The name or path of the library
+        library: String,
+        /// The version of the library, as `(major, minor, patch)`
+        version: (u32, u32, u32),
+    },
+    /// The contents of the file do not match the SHA-256 digest pinned by
+    /// the [`LoaderConfig`], so it was not loaded
+    DigestMismatch {
+        /// The path of the library
+        library: String,
+        /// The SHA-256 digest of the file's actual contents
+        digest: [u8; 32],
+    },
+    /// None of the candidates of a [`LoaderConfig`] could be loaded
+    NoUsableCandidate(Vec<RejectedCandidate>),
+}
+
+/// A candidate library which [`LoaderConfig::load`] did not use, and why
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RejectedCandidate {
+    /// The candidate name or path, as provided
+    pub candidate: String,
+    /// The reason it was rejected
+    pub reason: LoadError,
 }
 
 impl fmt::Display for LoadError {
@@ -43,6 +87,31 @@ impl fmt::Display for LoadError {
             Self::AlreadyLoaded => write!(f, "A Botan library has already been loaded"),
             Self::LoadFailed(msg) => write!(f, "Unable to load the Botan library: {msg}"),
             Self::UnusableLibrary(msg) => write!(f, "The loaded library is not usable: {msg}"),
+            Self::VersionNotAllowed {
+                library,
+                version: (major, minor, patch),
+            } => write!(
+                f,
+                "{library} is Botan {major}.{minor}.{patch}, which is outside the allowed versions"
+            ),
+            Self::DigestMismatch { library, digest } => {
+                write!(
+                    f,
+                    "{library} does not match the pinned SHA-256 digest (it has "
+                )?;
+                for b in digest {
+                    write!(f, "{b:02x}")?;
+                }
+                write!(f, ")")
+            }
+            Self::NoUsableCandidate(rejected) => {
+                write!(f, "None of the candidate Botan libraries is usable")?;
+                for (i, r) in rejected.iter().enumerate() {
+                    let sep = if i == 0 { ": " } else { "; " };
+                    write!(f, "{sep}{}: {}", r.candidate, r.reason)?;
+                }
+                Ok(())
+            }
         }
     }
 }
@@ -99,6 +168,7 @@ impl<F: Copy + 'static> Symbol<F> {
 struct Loaded {
     lib: Library,
     name: String,
+    version: (u32, u32, u32),
 }
 
 enum State {
@@ -106,6 +176,9 @@ enum State {
     Unattempted,
     /// The default search was attempted and failed
     AutoFailed(&'static str),
+    /// Loading with a `LoaderConfig` failed; the default search is not
+    /// attempted afterwards, as it would ignore the configured constraints
+    ConfigFailed(&'static str),
 }
 
 /// The loaded library. Once set it is never cleared or dropped, so function
@@ -134,7 +207,7 @@ fn library() -> Result<&'static Library, &'static str> {
     }
 
     match *state {
-        State::AutoFailed(msg) => Err(msg),
+        State::AutoFailed(msg) | State::ConfigFailed(msg) => Err(msg),
         State::Unattempted => match default_search() {
             Ok(loaded) => {
                 let _ = LIBRARY.set(loaded);
@@ -191,6 +264,15 @@ unsafe fn open_library(name: &OsStr) -> Result<Library, libloading::Error> {
 
 /// Attempt to load the named library and verify that it is a usable Botan
 fn try_load(name: &OsStr) -> Result<Loaded, LoadError> {
+    try_load_checked(name, |_| Ok(()))
+}
+
+/// Attempt to load the named library, verify that it is a usable Botan, and
+/// apply an additional check to its `(major, minor, patch)` version
+fn try_load_checked(
+    name: &OsStr,
+    check_version: impl FnOnce((u32, u32, u32)) -> Result<(), LoadError>,
+) -> Result<Loaded, LoadError> {
     // SAFETY: Botan's initialization code is benign
     let lib = unsafe { open_library(name) }.map_err(|e| LoadError::LoadFailed(e.to_string()))?;
 
@@ -217,9 +299,30 @@ fn try_load(name: &OsStr) -> Result<Loaded, LoadError> {
         )));
     }
 
+    // SAFETY: these symbols have had this signature since the FFI was introduced
+    let release = unsafe {
+        let major = lib.get::<unsafe extern "C" fn() -> u32>(b"botan_version_major\0");
+        let minor = lib.get::<unsafe extern "C" fn() -> u32>(b"botan_version_minor\0");
+        let patch = lib.get::<unsafe extern "C" fn() -> u32>(b"botan_version_patch\0");
+        match (major, minor, patch) {
+            (Ok(major), Ok(minor), Ok(patch)) => (major(), minor(), patch()),
+            _ => {
+                return Err(LoadError::UnusableLibrary(format!(
+                    "{} does not export the version functions",
+                    name.to_string_lossy()
+                )));
+            }
+        }
+    };
+
+    // A rejected library is unloaded when it is dropped here; nothing was
+    // resolved from it yet
+    check_version(release)?;
+
     Ok(Loaded {
         lib,
         name: name.to_string_lossy().into_owned(),
+        version: release,
     })
 }
 
@@ -308,6 +411,158 @@ pub fn load_library<P: AsRef<OsStr>>(path: P) -> Result<(), LoadError> {
     Ok(())
 }
 
+/// Options controlling which Botan shared library is loaded
+///
+/// [`load_library`] accepts whatever library is found under the given name.
+/// A `LoaderConfig` instead tries a list of candidates in order, and only
+/// accepts one which is within a range of Botan versions and, optionally,
+/// whose file contents match a SHA-256 digest. The result of
+/// [`LoaderConfig::load`] records which library was chosen and why each
+/// earlier candidate was rejected.
+///
+/// The version can only be checked after the library has been opened (and
+/// its initialization code has run); the digest is checked before. The
+/// digest is computed over the file immediately before it is opened, so it
+/// does not protect against an attacker able to replace the file in between;
+/// the file should be in a directory only trusted users can write to.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct LoaderConfig {
+    candidates: Vec<OsString>,
+    min_version: Option<(u32, u32, u32)>,
+    max_version: Option<(u32, u32, u32)>,
+    sha256: Option<[u8; 32]>,
+}
+
+/// The library chosen by [`LoaderConfig::load`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LoadReport {
+    /// The candidate name or path which was loaded
+    pub library: String,
+    /// The version of the library, as `(major, minor, patch)`
+    pub version: (u32, u32, u32),
+    /// The candidates tried before it, and why each was rejected
+    pub rejected: Vec<RejectedCandidate>,
+}
+
+impl LoaderConfig {
+    /// Create a configuration with no constraints
+    ///
+    /// Unless candidates are added, the names used by the default search are
+    /// tried.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add a candidate name or path; candidates are tried in the order added
+    ///
+    /// As with [`load_library`], the name is passed to `dlopen` (or
+    /// `LoadLibrary`), so it may be a bare filename which is searched for in
+    /// the usual locations. If a SHA-256 digest is pinned, each candidate must
+    /// be a path instead.
+    pub fn with_candidate<P: AsRef<OsStr>>(mut self, path: P) -> Self {
+        self.candidates.push(path.as_ref().to_os_string());
+        self
+    }
+
+    /// Reject libraries older than the given Botan version
+    pub fn with_min_version(mut self, major: u32, minor: u32, patch: u32) -> Self {
+        self.min_version = Some((major, minor, patch));
+        self
+    }
+
+    /// Reject libraries newer than the given Botan version
+    pub fn with_max_version(mut self, major: u32, minor: u32, patch: u32) -> Self {
+        self.max_version = Some((major, minor, patch));
+        self
+    }
+
+    /// Reject libraries whose file contents do not have this SHA-256 digest
+    pub fn with_sha256_pin(mut self, digest: [u8; 32]) -> Self {
+        self.sha256 = Some(digest);
+        self
+    }
+
+    /// Load the first acceptable candidate
+    ///
+    /// Fails with `LoadError::AlreadyLoaded` if a library was already loaded,
+    /// or with `LoadError::NoUsableCandidate` listing every candidate and the
+    /// reason it was rejected.
+    ///
+    /// After a failure the library is no longer loaded implicitly by the
+    /// first FFI call, which instead fails as if no library could be found;
+    /// a library can still be loaded explicitly, with [`load_library`] or
+    /// another `LoaderConfig`.
+    pub fn load(&self) -> Result<LoadReport, LoadError> {
+        let mut state = lock_state();
+
+        if LIBRARY.get().is_some() {
+            return Err(LoadError::AlreadyLoaded);
+        }
+
+        let candidates = if self.candidates.is_empty() {
+            candidate_names().into_iter().map(OsString::from).collect()
+        } else {
+            self.candidates.clone()
+        };
+
+        let mut rejected = Vec::new();
+
+        for candidate in &candidates {
+            match self.try_candidate(candidate) {
+                Ok(loaded) => {
+                    let report = LoadReport {
+                        library: loaded.name.clone(),
+                        version: loaded.version,
+                        rejected,
+                    };
+                    let _ = LIBRARY.set(loaded);
+                    return Ok(report);
+                }
+                Err(reason) => rejected.push(RejectedCandidate {
+                    candidate: candidate.to_string_lossy().into_owned(),
+                    reason,
+                }),
+            }
+        }
+
+        let err = LoadError::NoUsableCandidate(rejected);
+        *state = State::ConfigFailed(Box::leak(err.to_string().into_boxed_str()));
+        Err(err)
+    }
+
+    fn try_candidate(&self, candidate: &OsStr) -> Result<Loaded, LoadError> {
+        let library = candidate.to_string_lossy().into_owned();
+
+        if let Some(pin) = self.sha256 {
+            // A bare name is resolved by dlopen, so which file it would load
+            // cannot be known in order to check it
+            let path = std::path::Path::new(candidate);
+            if path.parent().is_none_or(|p| p.as_os_str().is_empty()) {
+                return Err(LoadError::LoadFailed(format!(
+                    "{library} is not a path, so cannot be checked against the pinned SHA-256 digest"
+                )));
+            }
+
+            let contents = std::fs::read(path)
+                .map_err(|e| LoadError::LoadFailed(format!("unable to read {library}: {e}")))?;
+            let digest: [u8; 32] = Sha256::digest(&contents).into();
+            if digest != pin {
+                return Err(LoadError::DigestMismatch { library, digest });
+            }
+        }
+
+        try_load_checked(candidate, |version| {
+            let too_old = self.min_version.is_some_and(|min| version < min);
+            let too_new = self.max_version.is_some_and(|max| version > max);
+            if too_old || too_new {
+                Err(LoadError::VersionNotAllowed { library, version })
+            } else {
+                Ok(())
+            }
+        })
+    }
+}
+
 /// Return the name or path of the Botan shared library which was loaded
 ///
 /// Returns `None` if no library has been loaded yet
@@ -324,7 +579,165 @@ pub fn last_load_error() -> Option<&'static str> {
         return None;
     }
     match *lock_state() {
-        State::AutoFailed(msg) => Some(msg),
+        State::AutoFailed(msg) | State::ConfigFailed(msg) => Some(msg),
         State::Unattempted => None,
     }
 }
+
+/// An FFI function declared by this crate
+pub(crate) struct DeclaredSymbol {
+    pub(crate) name: &'static str,
+    /// The FFI API version which introduced the function
+    pub(crate) level: u32,
+}
+
+/// Return the FFI API version named by a `botan_ffi_YYYYMMDD` cfg
+pub(crate) const fn ffi_level_of_cfg(cfg: &str) -> u32 {
+    let digits = cfg.as_bytes();
+    let mut level = 0;
+    let mut i = digits.len() - 8;
+    while i < digits.len() {
+        level = level * 10 + (digits[i] - b'0') as u32;
+        i += 1;
+    }
+    level
+}
+
+/// The functions declared by every module of the crate
+const DECLARED_SYMBOLS: &[&[DeclaredSymbol]] = &[
+    crate::block::FFI_SYMBOLS,
+    crate::cipher::FFI_SYMBOLS,
+    crate::ec_group::FFI_SYMBOLS,
+    crate::errors::FFI_SYMBOLS,
+    crate::fpe::FFI_SYMBOLS,
+    crate::hash::FFI_SYMBOLS,
+    crate::kdf::FFI_SYMBOLS,
+    crate::keywrap::FFI_SYMBOLS,
+    crate::mac::FFI_SYMBOLS,
+    crate::mp::FFI_SYMBOLS,
+    crate::oid::FFI_SYMBOLS,
+    crate::otp::FFI_SYMBOLS,
+    crate::passhash::FFI_SYMBOLS,
+    crate::pk_ops::FFI_SYMBOLS,
+    crate::pubkey::FFI_SYMBOLS,
+    crate::rng::FFI_SYMBOLS,
+    crate::spake2p::FFI_SYMBOLS,
+    crate::srp6::FFI_SYMBOLS,
+    crate::tpm2::FFI_SYMBOLS,
+    crate::utils::FFI_SYMBOLS,
+    crate::version::FFI_SYMBOLS,
+    crate::x509::FFI_SYMBOLS,
+    crate::xof::FFI_SYMBOLS,
+    crate::zfec::FFI_SYMBOLS,
+];
+
+/// Return the earliest Botan release providing the given FFI API version,
+/// for example `"3.8"` for 20250506
+///
+/// Returns `None` if the version is newer than any this crate knows of.
+pub fn botan_release_for_ffi_level(level: u32) -> Option<&'static str> {
+    FFI_LEVEL_RELEASES
+        .iter()
+        .find(|(ffi, _)| *ffi >= level)
+        .map(|(_, release)| *release)
+}
+
+/// The functions introduced by one FFI API version, as found in the loaded
+/// library
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FfiLevelSymbols {
+    /// The FFI API version, as a YYYYMMDD value
+    pub level: u32,
+    /// The functions which the library exports
+    pub available: Vec<&'static str>,
+    /// The functions which the library does not export
+    pub missing: Vec<&'static str>,
+}
+
+impl FfiLevelSymbols {
+    /// Return the Botan release which introduced this FFI API version
+    pub fn botan_release(&self) -> Option<&'static str> {
+        botan_release_for_ffi_level(self.level)
+    }
+}
+
+/// The result of resolving every FFI function declared by this crate from
+/// the loaded library
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FfiSymbolReport {
+    /// The name or path of the loaded library
+    pub library: String,
+    /// The FFI API version reported by the library
+    pub api_version: u32,
+    /// The declared functions grouped by the FFI API version which
+    /// introduced them, oldest first. Functions available in every supported
+    /// version are listed under the oldest.
+    pub levels: Vec<FfiLevelSymbols>,
+}
+
+impl FfiSymbolReport {
+    /// Return every declared function which the library does not export
+    pub fn missing(&self) -> impl Iterator<Item = &'static str> + '_ {
+        self.levels
+            .iter()
+            .flat_map(|level| level.missing.iter().copied())
+    }
+
+    /// Return the newest FFI API version for which the library exports
+    /// every declared function of that and all earlier versions
+    ///
+    /// Returns `None` if some function of the oldest version is missing.
+    pub fn complete_level(&self) -> Option<u32> {
+        self.levels
+            .iter()
+            .take_while(|level| level.missing.is_empty())
+            .last()
+            .map(|level| level.level)
+    }
+}
+
+/// Load the library if necessary, and resolve every FFI function declared by
+/// this crate from it
+///
+/// Functions are otherwise resolved on first use, so that a missing function
+/// is only noticed when it is called; this allows checking up front.
+pub fn ffi_symbol_report() -> Result<FfiSymbolReport, LoadError> {
+    let lib = library().map_err(|msg| LoadError::LoadFailed(msg.to_string()))?;
+    let library = loaded_library_name().unwrap_or_default().to_string();
+
+    // SAFETY: the library is loaded, so this resolves and calls the function
+    let api_version = unsafe { crate::botan_ffi_api_version() };
+
+    let mut levels: Vec<FfiLevelSymbols> = Vec::new();
+    for symbol in DECLARED_SYMBOLS.iter().copied().flatten() {
+        // SAFETY: the symbol is only checked for presence, and never called
+        let found = unsafe { lib.get::<unsafe extern "C" fn()>(symbol.name.as_bytes()) }.is_ok();
+
+        let idx = match levels.binary_search_by_key(&symbol.level, |l| l.level) {
+            Ok(idx) => idx,
+            Err(idx) => {
+                levels.insert(
+                    idx,
+                    FfiLevelSymbols {
+                        level: symbol.level,
+                        available: Vec::new(),
+                        missing: Vec::new(),
+                    },
+                );
+                idx
+            }
+        };
+
+        if found {
+            levels[idx].available.push(symbol.name);
+        } else {
+            levels[idx].missing.push(symbol.name);
+        }
+    }
+
+    Ok(FfiSymbolReport {
+        library,
+        api_version,
+        levels,
+    })
+}
@@ -53,6 +53,18 @@ macro_rules! botan_ffi_functions {
                 pub fn $name ( $($arg : $ty),* ) $( -> $ret )? ;
             }
         )*
+
+        // Dynamic loading: the functions declared by this module, for
+        // `ffi_symbol_report`. Each module may only invoke this macro once.
+        #[cfg(feature = "dynamic-loading")]
+        pub(crate) const FFI_SYMBOLS: &[$crate::loader::DeclaredSymbol] = &[
+            $(
+                $crate::loader::DeclaredSymbol {
+                    name: stringify!($name),
+                    level: __botan_ffi_level!( $($cfg)? ),
+                },
+            )*
+        ];
     };
 }
 
@@ -121,6 +133,15 @@ macro_rules! __botan_ffi_stub {
     };
 }
 
+macro_rules! __botan_ffi_level {
+    () => {
+        $crate::loader::MINIMUM_FFI_VERSION
+    };
+    ($cfg:ident) => {
+        $crate::loader::ffi_level_of_cfg(stringify!($cfg))
+    };
+}
+
 macro_rules! __botan_unavailable_value {
     () => {
         $crate::BOTAN_FFI_ERROR_FUNCTION_NOT_AVAILABLE
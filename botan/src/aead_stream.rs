@@ -0,0 +1,272 @@
+use crate::utils::*;
+
+use crate::{Cipher, CipherAlgorithmIdentifier, CipherDirection};
+
+use crate::segmented::{SEGMENT_NONCE_SUFFIX_LEN, SegmentedAead};
+
+#[cfg(feature = "std")]
+use std::io;
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+#[non_exhaustive]
+/// How segments of an online AEAD stream are bound together
+pub enum AeadStreamMode {
+    /// The STREAM construction of Hoang, Reyhanitabar, Rogaway and Vizár
+    ///
+    /// Each segment is encrypted under a nonce made of a fixed prefix, the
+    /// segment number, and a flag marking the final segment.
+    Stream,
+}
+
+fn segmented_aead<A: CipherAlgorithmIdentifier>(
+    algo: A,
+    direction: CipherDirection,
+    mode: AeadStreamMode,
+    key: &[u8],
+    nonce: &[u8],
+    ad: &[u8],
+    segment_size: usize,
+) -> Result<SegmentedAead> {
+    let AeadStreamMode::Stream = mode;
+    let mut segments = SegmentedAead::new(Cipher::new(algo, direction)?, nonce, ad, segment_size)?;
+    segments.set_key(key)?;
+    Ok(segments)
+}
+
+/// Online authenticated encryption of a stream of unbounded length
+///
+/// The plaintext is split into segments of a fixed size, each of which is
+/// encrypted and authenticated separately. The final segment is marked, so
+/// that truncation of the stream is detected by the decryptor.
+///
+/// The nonce provided must be unique for each stream encrypted with a
+/// given key; it is 5 bytes shorter than the cipher's default nonce
+/// length, see [`AeadStreamEncryptor::nonce_length`].
+///
+/// # Examples
+///
+/// ```
+/// let mut rng = botan::RandomNumberGenerator::new_system().unwrap();
+/// let key = rng.read(32).unwrap();
+/// let algo = "ChaCha20Poly1305";
+/// let nonce = rng.read(botan::AeadStreamEncryptor::nonce_length(algo).unwrap()).unwrap();
+///
+/// let mut enc = botan::AeadStreamEncryptor::new(algo, botan::AeadStreamMode::Stream, &key, &nonce, b"ad", 1024).unwrap();
+/// let mut ctext = enc.update(&[1; 5000]).unwrap();
+/// ctext.extend(enc.finish().unwrap());
+///
+/// let mut dec = botan::AeadStreamDecryptor::new(algo, botan::AeadStreamMode::Stream, &key, &nonce, b"ad", 1024).unwrap();
+/// let mut ptext = dec.update(&ctext).unwrap();
+/// ptext.extend(dec.finish().unwrap());
+/// assert_eq!(ptext, vec![1; 5000]);
+/// ```
+pub struct AeadStreamEncryptor {
+    segments: SegmentedAead,
+}
+
+impl AeadStreamEncryptor {
+    /// Return the length of the nonce used with the given AEAD
+    pub fn nonce_length<A: CipherAlgorithmIdentifier>(algo: A) -> Result<usize> {
+        let cipher = Cipher::new(algo, CipherDirection::Encrypt)?;
+        cipher
+            .default_nonce_length()
+            .checked_sub(SEGMENT_NONCE_SUFFIX_LEN)
+            .ok_or_else(|| Error::bad_parameter("Cipher nonce too short for online encryption"))
+    }
+
+    /// Create a new stream encryptor
+    ///
+    /// The associated data is authenticated with every segment
+    pub fn new<A: CipherAlgorithmIdentifier>(
+        algo: A,
+        mode: AeadStreamMode,
+        key: &[u8],
+        nonce: &[u8],
+        ad: &[u8],
+        segment_size: usize,
+    ) -> Result<Self> {
+        Ok(Self {
+            segments: segmented_aead(
+                algo,
+                CipherDirection::Encrypt,
+                mode,
+                key,
+                nonce,
+                ad,
+                segment_size,
+            )?,
+        })
+    }
+
+    /// Encrypt more input, returning any complete segments
+    ///
+    /// A segment is only output once it is known not to be the final segment.
+    pub fn update(&mut self, input: &[u8]) -> Result<Vec<u8>> {
+        self.segments.update(input)
+    }
+
+    /// Encrypt the final segment
+    pub fn finish(self) -> Result<Vec<u8>> {
+        self.segments.finish()
+    }
+}
+
+/// Online authenticated decryption of a stream of unbounded length
+///
+/// Plaintext is only returned once the segment containing it has been
+/// authenticated. Modification, reordering, or truncation of the
+/// segments is detected.
+pub struct AeadStreamDecryptor {
+    segments: SegmentedAead,
+}
+
+impl AeadStreamDecryptor {
+    /// Create a new stream decryptor
+    pub fn new<A: CipherAlgorithmIdentifier>(
+        algo: A,
+        mode: AeadStreamMode,
+        key: &[u8],
+        nonce: &[u8],
+        ad: &[u8],
+        segment_size: usize,
+    ) -> Result<Self> {
+        Ok(Self {
+            segments: segmented_aead(
+                algo,
+                CipherDirection::Decrypt,
+                mode,
+                key,
+                nonce,
+                ad,
+                segment_size,
+            )?,
+        })
+    }
+
+    /// Decrypt more input, returning the plaintext of any authenticated segments
+    pub fn update(&mut self, input: &[u8]) -> Result<Vec<u8>> {
+        self.segments.update(input)
+    }
+
+    /// Decrypt the final segment, verifying that the stream is complete
+    pub fn finish(self) -> Result<Vec<u8>> {
+        self.segments.finish()
+    }
+}
+
+#[cfg(feature = "std")]
+/// An [`io::Write`] adapter which encrypts everything written to it
+///
+/// [`AeadStreamWriter::finish`] must be called once all data has been
+/// written; otherwise the final segment is never written, and decryption
+/// will fail since the stream appears truncated.
+pub struct AeadStreamWriter<W: io::Write> {
+    inner: W,
+    enc: Option<AeadStreamEncryptor>,
+}
+
+#[cfg(feature = "std")]
+impl<W: io::Write> AeadStreamWriter<W> {
+    /// Create a writer which encrypts into `inner`
+    pub fn new(inner: W, enc: AeadStreamEncryptor) -> Self {
+        Self {
+            inner,
+            enc: Some(enc),
+        }
+    }
+
+    fn encryptor(&mut self) -> io::Result<&mut AeadStreamEncryptor> {
+        self.enc
+            .as_mut()
+            .ok_or_else(|| io::Error::other("Stream already finished"))
+    }
+
+    /// Write the final segment, returning the underlying writer
+    pub fn finish(mut self) -> io::Result<W> {
+        let enc = self
+            .enc
+            .take()
+            .ok_or_else(|| io::Error::other("Stream already finished"))?;
+        let last = enc.finish().map_err(io::Error::other)?;
+        self.inner.write_all(&last)?;
+        self.inner.flush()?;
+        Ok(self.inner)
+    }
+}
+
+#[cfg(feature = "std")]
+impl<W: io::Write> io::Write for AeadStreamWriter<W> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        let out = self.encryptor()?.update(buf).map_err(io::Error::other)?;
+        self.inner.write_all(&out)?;
+        Ok(buf.len())
+    }
+
+    /// Flush the underlying writer
+    ///
+    /// Any partial segment remains buffered until more data is written or
+    /// the stream is finished.
+    fn flush(&mut self) -> io::Result<()> {
+        self.inner.flush()
+    }
+}
+
+#[cfg(feature = "std")]
+/// An [`io::Read`] adapter which decrypts and authenticates a stream
+///
+/// Only authenticated plaintext is returned. If the stream is modified or
+/// truncated, reading fails with an error wrapping a [`crate::Error`]. At
+/// most two encrypted segments are buffered at any time.
+pub struct AeadStreamReader<R: io::Read> {
+    inner: R,
+    dec: Option<AeadStreamDecryptor>,
+    input: Vec<u8>,
+    plaintext: Vec<u8>,
+    pos: usize,
+}
+
+#[cfg(feature = "std")]
+impl<R: io::Read> AeadStreamReader<R> {
+    /// Create a reader which decrypts from `inner`
+    pub fn new(inner: R, dec: AeadStreamDecryptor) -> Self {
+        let input = vec![0; dec.segments.input_segment_len()];
+        Self {
+            inner,
+            dec: Some(dec),
+            input,
+            plaintext: Vec::new(),
+            pos: 0,
+        }
+    }
+
+    /// Return the underlying reader
+    pub fn into_inner(self) -> R {
+        self.inner
+    }
+}
+
+#[cfg(feature = "std")]
+impl<R: io::Read> io::Read for AeadStreamReader<R> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        while self.pos == self.plaintext.len() {
+            let Some(dec) = self.dec.as_mut() else {
+                return Ok(0);
+            };
+
+            let n = self.inner.read(&mut self.input)?;
+
+            self.plaintext = if n == 0 {
+                let dec = self.dec.take().expect("checked above");
+                dec.finish().map_err(io::Error::other)?
+            } else {
+                dec.update(&self.input[..n]).map_err(io::Error::other)?
+            };
+            self.pos = 0;
+        }
+
+        let n = buf.len().min(self.plaintext.len() - self.pos);
+        buf[..n].copy_from_slice(&self.plaintext[self.pos..self.pos + n]);
+        self.pos += n;
+        Ok(n)
+    }
+}
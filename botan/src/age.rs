@@ -0,0 +1,867 @@
+use crate::utils::*;
+
+use core::fmt;
+use core::str::FromStr;
+
+use crate::{
+    Cipher, CipherAlgorithm, CipherDirection, HashAlgorithm, KdfAlgorithm, MacAlgorithm,
+    MsgAuthCode, Privkey, RandomNumberGenerator, base64_decode, base64_encode, const_time_compare,
+    kdf, scrypt,
+};
+
+const AGE_VERSION_LINE: &str = "age-encryption.org/v1";
+const AGE_ARMOR_BEGIN: &str = "-----BEGIN AGE ENCRYPTED FILE-----";
+const AGE_ARMOR_END: &str = "-----END AGE ENCRYPTED FILE-----";
+
+const AGE_X25519_LABEL: &[u8] = b"age-encryption.org/v1/X25519";
+const AGE_SCRYPT_LABEL: &[u8] = b"age-encryption.org/v1/scrypt";
+
+const AGE_IDENTITY_HRP: &str = "age-secret-key-";
+const AGE_RECIPIENT_HRP: &str = "age";
+
+const AGE_FILE_KEY_LEN: usize = 16;
+const AGE_PAYLOAD_NONCE_LEN: usize = 16;
+const AGE_CHUNK_SIZE: usize = 64 * 1024;
+const AGE_TAG_LEN: usize = 16;
+const AGE_COLUMNS: usize = 64;
+
+/// The scrypt work factor (log2 of N) used by [`AgeRecipient::scrypt`]
+pub const AGE_DEFAULT_SCRYPT_WORK_FACTOR: u8 = 18;
+
+/// The largest scrypt work factor accepted by [`AgeIdentity::scrypt`]
+pub const AGE_DEFAULT_MAX_SCRYPT_WORK_FACTOR: u8 = 22;
+
+fn header_error(msg: &str) -> Error {
+    Error::with_message(
+        ErrorType::InvalidInput,
+        format!("Invalid age header: {msg}"),
+    )
+}
+
+fn payload_error(msg: &str) -> Error {
+    Error::with_message(
+        ErrorType::InvalidInput,
+        format!("Invalid age payload: {msg}"),
+    )
+}
+
+fn armor_error(msg: &str) -> Error {
+    Error::with_message(ErrorType::InvalidInput, format!("Invalid age armor: {msg}"))
+}
+
+fn is_base64_char(c: u8) -> bool {
+    c.is_ascii_alphanumeric() || c == b'+' || c == b'/'
+}
+
+/// Base64 encode without padding, as used in age headers
+fn b64_encode_unpadded(data: &[u8]) -> Result<String> {
+    if data.is_empty() {
+        return Ok(String::new());
+    }
+    Ok(base64_encode(data)?.trim_end_matches('=').to_owned())
+}
+
+/// Strict decoding of unpadded base64, rejecting any non-canonical encoding
+fn b64_decode_unpadded(encoded: &[u8]) -> Result<Vec<u8>> {
+    if encoded.is_empty() {
+        return Ok(Vec::new());
+    }
+    if !encoded.iter().all(|&c| is_base64_char(c)) || encoded.len() % 4 == 1 {
+        return Err(header_error("bad base64 encoding"));
+    }
+
+    // Checked above to only contain ASCII
+    let encoded = core::str::from_utf8(encoded).map_err(Error::conversion_error)?;
+    let mut padded = encoded.to_owned();
+    while padded.len() % 4 != 0 {
+        padded.push('=');
+    }
+
+    let decoded = base64_decode(&padded)?;
+    if b64_encode_unpadded(&decoded)? != encoded {
+        return Err(header_error("non-canonical base64 encoding"));
+    }
+    Ok(decoded)
+}
+
+const BECH32_CHARSET: &[u8; 32] = b"qpzry9x8gf2tvdw0s3jn54khce6mua7l";
+
+fn bech32_polymod(values: impl Iterator<Item = u8>) -> u32 {
+    const GEN: [u32; 5] = [0x3b6a57b2, 0x26508e6d, 0x1ea119fa, 0x3d4233dd, 0x2a1462b3];
+    let mut chk: u32 = 1;
+    for v in values {
+        let top = chk >> 25;
+        chk = ((chk & 0x1ffffff) << 5) ^ u32::from(v);
+        for (i, g) in GEN.iter().enumerate() {
+            if (top >> i) & 1 == 1 {
+                chk ^= g;
+            }
+        }
+    }
+    chk
+}
+
+fn bech32_hrp_expand(hrp: &str) -> Vec<u8> {
+    let mut out: Vec<u8> = hrp.bytes().map(|b| b >> 5).collect();
+    out.push(0);
+    out.extend(hrp.bytes().map(|b| b & 31));
+    out
+}
+
+fn convert_bits(data: &[u8], from: u32, to: u32, pad: bool) -> Option<Vec<u8>> {
+    let mut acc: u32 = 0;
+    let mut bits: u32 = 0;
+    let mut out = Vec::new();
+    let maxv = (1u32 << to) - 1;
+    for &v in data {
+        acc = (acc << from) | u32::from(v);
+        bits += from;
+        while bits >= to {
+            bits -= to;
+            out.push(((acc >> bits) & maxv) as u8);
+        }
+    }
+    if pad {
+        if bits > 0 {
+            out.push(((acc << (to - bits)) & maxv) as u8);
+        }
+    } else if bits >= from || ((acc << (to - bits)) & maxv) != 0 {
+        return None;
+    }
+    Some(out)
+}
+
+/// Bech32 encoding (BIP 173), without the 90 character length limit
+fn bech32_encode(hrp: &str, data: &[u8]) -> String {
+    let data = convert_bits(data, 8, 5, true).expect("padding is always possible");
+    let mut values = bech32_hrp_expand(hrp);
+    values.extend_from_slice(&data);
+    values.extend_from_slice(&[0; 6]);
+    let checksum = bech32_polymod(values.into_iter()) ^ 1;
+
+    let mut out = format!("{hrp}1");
+    for d in data {
+        out.push(BECH32_CHARSET[d as usize] as char);
+    }
+    for i in 0..6 {
+        out.push(BECH32_CHARSET[((checksum >> (5 * (5 - i))) & 31) as usize] as char);
+    }
+    out
+}
+
+fn bech32_decode(encoded: &str, expected_hrp: &str) -> Result<Vec<u8>> {
+    let invalid =
+        || Error::with_message(ErrorType::InvalidInput, "Invalid Bech32 string".to_owned());
+
+    if encoded.bytes().any(|b| b.is_ascii_lowercase())
+        && encoded.bytes().any(|b| b.is_ascii_uppercase())
+    {
+        return Err(invalid());
+    }
+    let encoded = encoded.to_ascii_lowercase();
+
+    let sep = encoded.rfind('1').ok_or_else(invalid)?;
+    let (hrp, data) = (&encoded[..sep], &encoded[sep + 1..]);
+    if hrp != expected_hrp || data.len() < 6 {
+        return Err(invalid());
+    }
+
+    let values = data
+        .bytes()
+        .map(|c| BECH32_CHARSET.iter().position(|&x| x == c).map(|p| p as u8))
+        .collect::<Option<Vec<u8>>>()
+        .ok_or_else(invalid)?;
+
+    let expanded = bech32_hrp_expand(hrp);
+    if bech32_polymod(expanded.into_iter().chain(values.iter().copied())) != 1 {
+        return Err(invalid());
+    }
+
+    convert_bits(&values[..values.len() - 6], 5, 8, false).ok_or_else(invalid)
+}
+
+fn hkdf_sha256(ikm: &[u8], salt: &[u8], info: &[u8]) -> Result<Vec<u8>> {
+    kdf(
+        KdfAlgorithm::Hkdf(HashAlgorithm::Sha256),
+        32,
+        ikm,
+        salt,
+        info,
+    )
+}
+
+/// ChaCha20Poly1305 with an all-zero nonce, as used to wrap file keys
+fn aead_seal(key: &[u8], plaintext: &[u8]) -> Result<Vec<u8>> {
+    let mut cipher = Cipher::new(CipherAlgorithm::ChaCha20Poly1305, CipherDirection::Encrypt)?;
+    cipher.set_key(key)?;
+    cipher.process(&[0u8; 12], plaintext)
+}
+
+/// Unwrap a file key; returns None if authentication fails
+fn aead_open(key: &[u8], ciphertext: &[u8]) -> Result<Option<Vec<u8>>> {
+    let mut cipher = Cipher::new(CipherAlgorithm::ChaCha20Poly1305, CipherDirection::Decrypt)?;
+    cipher.set_key(key)?;
+    Ok(cipher.process(&[0u8; 12], ciphertext).ok())
+}
+
+/// An X25519 public key that age files can be encrypted to
+///
+/// The string encoding is Bech32 with the prefix `age1`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct AgeX25519Recipient {
+    public_key: Vec<u8>,
+}
+
+impl AgeX25519Recipient {
+    /// Create a recipient from a raw X25519 public key
+    pub fn from_bytes(public_key: &[u8]) -> Result<Self> {
+        if public_key.len() != 32 {
+            return Err(Error::bad_parameter("Invalid X25519 public key length"));
+        }
+        Ok(Self {
+            public_key: public_key.to_vec(),
+        })
+    }
+
+    /// Return the raw X25519 public key
+    #[must_use]
+    pub fn public_key(&self) -> &[u8] {
+        &self.public_key
+    }
+}
+
+impl FromStr for AgeX25519Recipient {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        if s.bytes().any(|b| b.is_ascii_uppercase()) {
+            return Err(Error::with_message(
+                ErrorType::InvalidInput,
+                "age recipients must be lowercase".to_owned(),
+            ));
+        }
+        Self::from_bytes(&bech32_decode(s, AGE_RECIPIENT_HRP)?)
+    }
+}
+
+impl fmt::Display for AgeX25519Recipient {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(&bech32_encode(AGE_RECIPIENT_HRP, &self.public_key))
+    }
+}
+
+/// An X25519 private key that can decrypt age files
+///
+/// The string encoding is upper case Bech32 with the prefix
+/// `AGE-SECRET-KEY-1`.
+///
+/// # Examples
+///
+/// ```
+/// let mut rng = botan::RandomNumberGenerator::new_system().unwrap();
+/// let identity = botan::AgeX25519Identity::generate(&mut rng).unwrap();
+/// let encoded = identity.encode();
+/// assert!(encoded.starts_with("AGE-SECRET-KEY-1"));
+/// let decoded: botan::AgeX25519Identity = encoded.parse().unwrap();
+/// assert_eq!(decoded.recipient(), identity.recipient());
+/// ```
+#[derive(Clone)]
+pub struct AgeX25519Identity {
+    secret: Vec<u8>,
+    recipient: AgeX25519Recipient,
+}
+
+impl fmt::Debug for AgeX25519Identity {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("AgeX25519Identity")
+            .field("recipient", &self.recipient)
+            .finish_non_exhaustive()
+    }
+}
+
+impl AgeX25519Identity {
+    /// Generate a new random identity
+    pub fn generate(rng: &mut RandomNumberGenerator) -> Result<Self> {
+        Self::from_bytes(&rng.read(32)?)
+    }
+
+    /// Create an identity from a raw X25519 private key
+    pub fn from_bytes(secret: &[u8]) -> Result<Self> {
+        let key = Privkey::load_x25519(secret)?;
+        let recipient = AgeX25519Recipient::from_bytes(&key.key_agreement_key()?)?;
+        Ok(Self {
+            secret: secret.to_vec(),
+            recipient,
+        })
+    }
+
+    /// Return the recipient corresponding to this identity
+    #[must_use]
+    pub fn recipient(&self) -> AgeX25519Recipient {
+        self.recipient.clone()
+    }
+
+    /// Return the `AGE-SECRET-KEY-1...` encoding of this identity
+    #[must_use]
+    pub fn encode(&self) -> String {
+        bech32_encode(AGE_IDENTITY_HRP, &self.secret).to_ascii_uppercase()
+    }
+
+    fn unwrap_stanza(&self, stanza: &AgeStanza) -> Result<Option<Vec<u8>>> {
+        if stanza.tag != "X25519" {
+            return Ok(None);
+        }
+        if stanza.args.len() != 1 {
+            return Err(header_error("malformed X25519 stanza"));
+        }
+        let share = b64_decode_unpadded(stanza.args[0].as_bytes())?;
+        if share.len() != 32 {
+            return Err(header_error("invalid X25519 share length"));
+        }
+        if stanza.body.len() != AGE_FILE_KEY_LEN + AGE_TAG_LEN {
+            return Err(header_error("invalid X25519 stanza body length"));
+        }
+
+        let key = Privkey::load_x25519(&self.secret)?;
+        let shared = key.agree(&share, 0, &[], KdfAlgorithm::Raw)?;
+        if shared.iter().all(|&b| b == 0) {
+            return Err(header_error("X25519 share is a low order point"));
+        }
+
+        let mut salt = share;
+        salt.extend_from_slice(&self.recipient.public_key);
+        let wrap_key = hkdf_sha256(&shared, &salt, AGE_X25519_LABEL)?;
+
+        aead_open(&wrap_key, &stanza.body)
+    }
+}
+
+impl FromStr for AgeX25519Identity {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        if s.bytes().any(|b| b.is_ascii_lowercase()) {
+            return Err(Error::with_message(
+                ErrorType::InvalidInput,
+                "age identities must be uppercase".to_owned(),
+            ));
+        }
+        Self::from_bytes(&bech32_decode(s, AGE_IDENTITY_HRP)?)
+    }
+}
+
+/// A recipient that an age file is encrypted to
+#[derive(Clone)]
+pub enum AgeRecipient {
+    /// An X25519 public key
+    X25519(AgeX25519Recipient),
+    /// A passphrase; this must be the only recipient of a file
+    Scrypt {
+        /// The passphrase
+        passphrase: String,
+        /// The scrypt work factor, as log2 of the N parameter
+        work_factor: u8,
+    },
+}
+
+impl fmt::Debug for AgeRecipient {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::X25519(recipient) => f.debug_tuple("X25519").field(recipient).finish(),
+            Self::Scrypt { work_factor, .. } => f
+                .debug_struct("Scrypt")
+                .field("work_factor", work_factor)
+                .finish_non_exhaustive(),
+        }
+    }
+}
+
+impl AgeRecipient {
+    /// Create a passphrase recipient with the default work factor
+    #[must_use]
+    pub fn scrypt(passphrase: &str) -> Self {
+        Self::Scrypt {
+            passphrase: passphrase.to_owned(),
+            work_factor: AGE_DEFAULT_SCRYPT_WORK_FACTOR,
+        }
+    }
+
+    fn wrap(&self, file_key: &[u8], rng: &mut RandomNumberGenerator) -> Result<AgeStanza> {
+        match self {
+            Self::X25519(recipient) => {
+                let ephemeral = Privkey::load_x25519(&rng.read(32)?)?;
+                let share = ephemeral.key_agreement_key()?;
+                let shared = ephemeral.agree(&recipient.public_key, 0, &[], KdfAlgorithm::Raw)?;
+                if shared.iter().all(|&b| b == 0) {
+                    return Err(Error::bad_parameter(
+                        "X25519 recipient is a low order point",
+                    ));
+                }
+
+                let mut salt = share.clone();
+                salt.extend_from_slice(&recipient.public_key);
+                let wrap_key = hkdf_sha256(&shared, &salt, AGE_X25519_LABEL)?;
+
+                Ok(AgeStanza {
+                    tag: "X25519".to_owned(),
+                    args: vec![b64_encode_unpadded(&share)?],
+                    body: aead_seal(&wrap_key, file_key)?,
+                })
+            }
+            Self::Scrypt {
+                passphrase,
+                work_factor,
+            } => {
+                if *work_factor == 0 || *work_factor >= 64 {
+                    return Err(Error::bad_parameter("Invalid scrypt work factor"));
+                }
+                let salt = rng.read(16)?;
+                let wrap_key = age_scrypt(passphrase, &salt, *work_factor)?;
+
+                Ok(AgeStanza {
+                    tag: "scrypt".to_owned(),
+                    args: vec![b64_encode_unpadded(&salt)?, work_factor.to_string()],
+                    body: aead_seal(&wrap_key, file_key)?,
+                })
+            }
+        }
+    }
+}
+
+impl From<AgeX25519Recipient> for AgeRecipient {
+    fn from(recipient: AgeX25519Recipient) -> Self {
+        Self::X25519(recipient)
+    }
+}
+
+/// A key which may be able to decrypt an age file
+#[derive(Clone)]
+pub enum AgeIdentity {
+    /// An X25519 private key
+    X25519(AgeX25519Identity),
+    /// A passphrase
+    Scrypt {
+        /// The passphrase
+        passphrase: String,
+        /// Files requiring a larger scrypt work factor are rejected
+        max_work_factor: u8,
+    },
+}
+
+impl fmt::Debug for AgeIdentity {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::X25519(identity) => f.debug_tuple("X25519").field(identity).finish(),
+            Self::Scrypt {
+                max_work_factor, ..
+            } => f
+                .debug_struct("Scrypt")
+                .field("max_work_factor", max_work_factor)
+                .finish_non_exhaustive(),
+        }
+    }
+}
+
+impl AgeIdentity {
+    /// Create a passphrase identity with the default maximum work factor
+    #[must_use]
+    pub fn scrypt(passphrase: &str) -> Self {
+        Self::Scrypt {
+            passphrase: passphrase.to_owned(),
+            max_work_factor: AGE_DEFAULT_MAX_SCRYPT_WORK_FACTOR,
+        }
+    }
+
+    fn unwrap_stanza(&self, stanza: &AgeStanza) -> Result<Option<Vec<u8>>> {
+        match self {
+            Self::X25519(identity) => identity.unwrap_stanza(stanza),
+            Self::Scrypt {
+                passphrase,
+                max_work_factor,
+            } => {
+                if stanza.tag != "scrypt" {
+                    return Ok(None);
+                }
+                if stanza.args.len() != 2 {
+                    return Err(header_error("malformed scrypt stanza"));
+                }
+                let salt = b64_decode_unpadded(stanza.args[0].as_bytes())?;
+                if salt.len() != 16 {
+                    return Err(header_error("invalid scrypt salt length"));
+                }
+
+                let work_factor = &stanza.args[1];
+                if !work_factor.bytes().all(|b| b.is_ascii_digit()) || work_factor.starts_with('0')
+                {
+                    return Err(header_error("invalid scrypt work factor"));
+                }
+                let work_factor = work_factor
+                    .parse::<u8>()
+                    .map_err(|_| header_error("invalid scrypt work factor"))?;
+                if work_factor > *max_work_factor || work_factor >= 64 {
+                    return Err(header_error("scrypt work factor too large"));
+                }
+
+                if stanza.body.len() != AGE_FILE_KEY_LEN + AGE_TAG_LEN {
+                    return Err(header_error("invalid scrypt stanza body length"));
+                }
+
+                let wrap_key = age_scrypt(passphrase, &salt, work_factor)?;
+                aead_open(&wrap_key, &stanza.body)
+            }
+        }
+    }
+}
+
+impl From<AgeX25519Identity> for AgeIdentity {
+    fn from(identity: AgeX25519Identity) -> Self {
+        Self::X25519(identity)
+    }
+}
+
+fn age_scrypt(passphrase: &str, salt: &[u8], work_factor: u8) -> Result<Vec<u8>> {
+    let mut full_salt = AGE_SCRYPT_LABEL.to_vec();
+    full_salt.extend_from_slice(salt);
+    scrypt(32, passphrase, &full_salt, 1 << work_factor, 8, 1)
+}
+
+struct AgeStanza {
+    tag: String,
+    args: Vec<String>,
+    body: Vec<u8>,
+}
+
+impl AgeStanza {
+    fn write(&self, out: &mut String) -> Result<()> {
+        out.push_str("-> ");
+        out.push_str(&self.tag);
+        for arg in &self.args {
+            out.push(' ');
+            out.push_str(arg);
+        }
+        out.push('\n');
+
+        let body = b64_encode_unpadded(&self.body)?;
+        let mut lines = body.as_bytes().chunks(AGE_COLUMNS);
+        for line in lines.by_ref() {
+            // Checked to be ASCII by construction
+            out.push_str(core::str::from_utf8(line).map_err(Error::conversion_error)?);
+            out.push('\n');
+        }
+        // The body always ends with a short (possibly empty) line
+        if body.len() % AGE_COLUMNS == 0 {
+            out.push('\n');
+        }
+        Ok(())
+    }
+}
+
+struct AgeHeader<'a> {
+    stanzas: Vec<AgeStanza>,
+    /// The header up to and including the "---" of the MAC line
+    mac_input: &'a [u8],
+    mac: Vec<u8>,
+    payload: &'a [u8],
+}
+
+fn parse_header(input: &[u8]) -> Result<AgeHeader<'_>> {
+    let mut pos = 0;
+
+    let next_line = |pos: &mut usize| -> Result<(usize, &[u8])> {
+        let start = *pos;
+        let len = input[start..]
+            .iter()
+            .position(|&b| b == b'\n')
+            .ok_or_else(|| header_error("truncated header"))?;
+        *pos = start + len + 1;
+        Ok((start, &input[start..start + len]))
+    };
+
+    let (_, version) = next_line(&mut pos)?;
+    if version != AGE_VERSION_LINE.as_bytes() {
+        return Err(header_error("unsupported version"));
+    }
+
+    let mut stanzas = Vec::new();
+    let mut line = next_line(&mut pos)?;
+
+    loop {
+        let (start, text) = line;
+
+        if let Some(mac) = text.strip_prefix(b"---") {
+            let mac = mac
+                .strip_prefix(b" ")
+                .ok_or_else(|| header_error("malformed MAC line"))?;
+            let mac = b64_decode_unpadded(mac)?;
+            if mac.len() != 32 {
+                return Err(header_error("invalid MAC length"));
+            }
+            if stanzas.is_empty() {
+                return Err(header_error("no recipient stanzas"));
+            }
+            return Ok(AgeHeader {
+                stanzas,
+                mac_input: &input[..start + 3],
+                mac,
+                payload: &input[pos..],
+            });
+        }
+
+        let args = text
+            .strip_prefix(b"-> ")
+            .ok_or_else(|| header_error("malformed stanza"))?;
+
+        let mut args = args
+            .split(|&b| b == b' ')
+            .map(|arg| {
+                if arg.is_empty() || !arg.iter().all(|&b| (0x21..=0x7e).contains(&b)) {
+                    return Err(header_error("malformed stanza argument"));
+                }
+                // Checked to be ASCII
+                Ok(String::from_utf8_lossy(arg).into_owned())
+            })
+            .collect::<Result<Vec<String>>>()?;
+        let tag = args.remove(0);
+
+        let mut body = Vec::new();
+        loop {
+            let (_, body_line) = next_line(&mut pos)?;
+            if body_line.len() > AGE_COLUMNS {
+                return Err(header_error("stanza body line too long"));
+            }
+            body.extend_from_slice(body_line);
+            if body_line.len() < AGE_COLUMNS {
+                break;
+            }
+        }
+
+        stanzas.push(AgeStanza {
+            tag,
+            args,
+            body: b64_decode_unpadded(&body)?,
+        });
+
+        line = next_line(&mut pos)?;
+    }
+}
+
+fn header_mac(file_key: &[u8], mac_input: &[u8]) -> Result<Vec<u8>> {
+    let mac_key = hkdf_sha256(file_key, &[], b"header")?;
+    let mut mac = MsgAuthCode::new(MacAlgorithm::Hmac(HashAlgorithm::Sha256))?;
+    mac.set_key(&mac_key)?;
+    mac.update(mac_input)?;
+    mac.finish()
+}
+
+fn stream_nonce(counter: u64, last: bool) -> [u8; 12] {
+    let mut nonce = [0u8; 12];
+    nonce[3..11].copy_from_slice(&counter.to_be_bytes());
+    nonce[11] = u8::from(last);
+    nonce
+}
+
+/// Encrypt a message in the age v1 format
+///
+/// A random file key is wrapped to each of the recipients. A passphrase
+/// recipient cannot be combined with any other recipient.
+///
+/// # Examples
+///
+/// ```
+/// let mut rng = botan::RandomNumberGenerator::new_system().unwrap();
+/// let identity = botan::AgeX25519Identity::generate(&mut rng).unwrap();
+/// let recipients = [identity.recipient().into()];
+///
+/// let ctext = botan::age_encrypt(&recipients, b"backup", &mut rng).unwrap();
+/// let ptext = botan::age_decrypt(&[identity.into()], &ctext).unwrap();
+/// assert_eq!(ptext, b"backup");
+/// ```
+pub fn age_encrypt(
+    recipients: &[AgeRecipient],
+    plaintext: &[u8],
+    rng: &mut RandomNumberGenerator,
+) -> Result<Vec<u8>> {
+    if recipients.is_empty() {
+        return Err(Error::bad_parameter("age encryption requires a recipient"));
+    }
+    if recipients.len() > 1
+        && recipients
+            .iter()
+            .any(|r| matches!(r, AgeRecipient::Scrypt { .. }))
+    {
+        return Err(Error::bad_parameter(
+            "age passphrase recipients cannot be combined with other recipients",
+        ));
+    }
+
+    let file_key = rng.read(AGE_FILE_KEY_LEN)?;
+
+    let mut header = format!("{AGE_VERSION_LINE}\n");
+    for recipient in recipients {
+        recipient.wrap(&file_key, rng)?.write(&mut header)?;
+    }
+    header.push_str("---");
+    let mac = header_mac(&file_key, header.as_bytes())?;
+    header.push(' ');
+    header.push_str(&b64_encode_unpadded(&mac)?);
+    header.push('\n');
+
+    let nonce = rng.read(AGE_PAYLOAD_NONCE_LEN)?;
+    let payload_key = hkdf_sha256(&file_key, &nonce, b"payload")?;
+
+    let mut cipher = Cipher::new(CipherAlgorithm::ChaCha20Poly1305, CipherDirection::Encrypt)?;
+    cipher.set_key(&payload_key)?;
+
+    let mut out = header.into_bytes();
+    out.extend_from_slice(&nonce);
+
+    let chunks = plaintext.len().div_ceil(AGE_CHUNK_SIZE).max(1);
+    for i in 0..chunks {
+        let chunk = &plaintext[i * AGE_CHUNK_SIZE..plaintext.len().min((i + 1) * AGE_CHUNK_SIZE)];
+        let nonce = stream_nonce(i as u64, i + 1 == chunks);
+        out.extend_from_slice(&cipher.process(&nonce, chunk)?);
+    }
+
+    Ok(out)
+}
+
+/// Decrypt an age v1 file, which may be armored
+///
+/// Each identity is tried against each recipient stanza in the header. If
+/// no identity matches, an error of type
+/// [`ErrorType::NoValueAvailable`](crate::ErrorType::NoValueAvailable) is
+/// returned. If the header or payload fails authentication, an error of
+/// type [`ErrorType::BadAuthCode`](crate::ErrorType::BadAuthCode) is
+/// returned. Malformed input produces an error of type
+/// [`ErrorType::InvalidInput`](crate::ErrorType::InvalidInput).
+pub fn age_decrypt(identities: &[AgeIdentity], input: &[u8]) -> Result<Vec<u8>> {
+    let first = input.iter().position(|b| !b.is_ascii_whitespace());
+    if first.is_some_and(|i| input[i..].starts_with(b"-----")) {
+        let armored = core::str::from_utf8(input).map_err(|_| armor_error("not valid UTF-8"))?;
+        return age_decrypt_binary(identities, &age_dearmor(armored)?);
+    }
+    age_decrypt_binary(identities, input)
+}
+
+fn age_decrypt_binary(identities: &[AgeIdentity], input: &[u8]) -> Result<Vec<u8>> {
+    let header = parse_header(input)?;
+
+    if header.stanzas.len() > 1 && header.stanzas.iter().any(|s| s.tag == "scrypt") {
+        return Err(header_error("scrypt stanza must be the only stanza"));
+    }
+
+    let mut file_key = None;
+    'search: for identity in identities {
+        for stanza in &header.stanzas {
+            if let Some(key) = identity.unwrap_stanza(stanza)? {
+                file_key = Some(key);
+                break 'search;
+            }
+        }
+    }
+
+    let file_key = file_key.ok_or_else(|| {
+        Error::with_message(
+            ErrorType::NoValueAvailable,
+            "No identity matched any age recipient".to_owned(),
+        )
+    })?;
+
+    if file_key.len() != AGE_FILE_KEY_LEN {
+        return Err(header_error("invalid file key length"));
+    }
+
+    let mac = header_mac(&file_key, header.mac_input)?;
+    if !const_time_compare(&mac, &header.mac) {
+        return Err(Error::with_message(
+            ErrorType::BadAuthCode,
+            "age header MAC is invalid".to_owned(),
+        ));
+    }
+
+    if header.payload.len() < AGE_PAYLOAD_NONCE_LEN {
+        return Err(payload_error("missing nonce"));
+    }
+    let (nonce, body) = header.payload.split_at(AGE_PAYLOAD_NONCE_LEN);
+    if body.is_empty() {
+        return Err(payload_error("no chunks"));
+    }
+
+    let payload_key = hkdf_sha256(&file_key, nonce, b"payload")?;
+    let mut cipher = Cipher::new(CipherAlgorithm::ChaCha20Poly1305, CipherDirection::Decrypt)?;
+    cipher.set_key(&payload_key)?;
+
+    let chunks = body.len().div_ceil(AGE_CHUNK_SIZE + AGE_TAG_LEN);
+    let mut out = Vec::with_capacity(body.len());
+
+    for (i, chunk) in body.chunks(AGE_CHUNK_SIZE + AGE_TAG_LEN).enumerate() {
+        let last = i + 1 == chunks;
+        if chunk.len() < AGE_TAG_LEN || (last && i > 0 && chunk.len() == AGE_TAG_LEN) {
+            return Err(payload_error("truncated or empty chunk"));
+        }
+        let ptext = cipher
+            .process(&stream_nonce(i as u64, last), chunk)
+            .map_err(|_| {
+                Error::with_message(
+                    ErrorType::BadAuthCode,
+                    "age payload authentication failed".to_owned(),
+                )
+            })?;
+        out.extend_from_slice(&ptext);
+    }
+
+    Ok(out)
+}
+
+/// Armor a binary age file using the strict PEM-like age encoding
+pub fn age_armor(input: &[u8]) -> Result<String> {
+    let encoded = base64_encode(input)?;
+    let mut out = format!("{AGE_ARMOR_BEGIN}\n");
+    for line in encoded.as_bytes().chunks(AGE_COLUMNS) {
+        out.push_str(core::str::from_utf8(line).map_err(Error::conversion_error)?);
+        out.push('\n');
+    }
+    out.push_str(AGE_ARMOR_END);
+    out.push('\n');
+    Ok(out)
+}
+
+/// Remove the armor from an age file
+///
+/// Whitespace is allowed around the armor, and lines may end with CRLF,
+/// but the encoding is otherwise required to be exactly what
+/// [`age_armor`] produces.
+pub fn age_dearmor(armored: &str) -> Result<Vec<u8>> {
+    let armored = armored.trim_matches(|c| matches!(c, ' ' | '\t' | '\r' | '\n'));
+
+    let lines: Vec<&str> = armored
+        .split('\n')
+        .map(|line| line.strip_suffix('\r').unwrap_or(line))
+        .collect();
+
+    if lines.len() < 3 || lines[0] != AGE_ARMOR_BEGIN || lines[lines.len() - 1] != AGE_ARMOR_END {
+        return Err(armor_error("missing begin or end line"));
+    }
+
+    let body = &lines[1..lines.len() - 1];
+    let mut encoded = String::new();
+    for (i, line) in body.iter().enumerate() {
+        let last = i + 1 == body.len();
+        if line.len() > AGE_COLUMNS || line.is_empty() || (!last && line.len() != AGE_COLUMNS) {
+            return Err(armor_error("bad line length"));
+        }
+        if !line.bytes().all(|b| is_base64_char(b) || b == b'=') {
+            return Err(armor_error("invalid character"));
+        }
+        encoded.push_str(line);
+    }
+
+    if encoded.len() % 4 != 0 {
+        return Err(armor_error("missing padding"));
+    }
+    let decoded = base64_decode(&encoded).map_err(|_| armor_error("bad base64 encoding"))?;
+    if base64_encode(&decoded)? != encoded {
+        return Err(armor_error("non-canonical base64 encoding"));
+    }
+    Ok(decoded)
+}
@@ -793,6 +793,9 @@ macro_rules! define_keygen_params_enum {
         }
 
         impl $name {
+            /// Every parameter set, in declaration order.
+            pub(crate) const ALL: &'static [Self] = &[$(Self::$variant),+];
+
             /// Return the Botan interface string for this parameter set.
             #[must_use]
             pub fn botan_name(&self) -> String {
@@ -802,6 +805,29 @@ macro_rules! define_keygen_params_enum {
             }
         }
 
+        /// Parse the Botan name of a parameter set; an unknown name is an error.
+        impl core::str::FromStr for $name {
+            type Err = crate::Error;
+
+            fn from_str(name: &str) -> crate::Result<Self> {
+                match name {
+                    $($botan_name => Ok(Self::$variant),)+
+                    _ => Err(crate::Error::with_message(
+                        crate::ErrorType::BadParameter,
+                        format!("Unknown {} '{name}'", stringify!($name)),
+                    )),
+                }
+            }
+        }
+
+        impl TryFrom<&str> for $name {
+            type Error = crate::Error;
+
+            fn try_from(name: &str) -> crate::Result<Self> {
+                name.parse()
+            }
+        }
+
         impl KeyGenParamsIdentifier for $name {
             fn botan_name(&self) -> String {
                 $name::botan_name(self)
@@ -1300,6 +1326,33 @@ impl SignatureParamsIdentifier for Option<SignatureParams> {
     }
 }
 
+/// A signature algorithm, combining a public key algorithm with the
+/// parameters it signs under, as identified by the AlgorithmIdentifier of
+/// an X.509 certificate or CRL.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SignatureAlgorithm {
+    /// The public key algorithm.
+    pub key_algorithm: PublicKeyAlgorithm,
+    /// The signature parameters, `None` for schemes that take none.
+    pub params: Option<SignatureParams>,
+}
+
+impl SignatureAlgorithm {
+    /// Return the Botan name for this signature algorithm, for example
+    /// `ECDSA/SHA-256`.
+    #[must_use]
+    pub fn botan_name(&self) -> String {
+        match &self.params {
+            Some(params) => format!(
+                "{}/{}",
+                self.key_algorithm.botan_name(),
+                params.botan_name()
+            ),
+            None => self.key_algorithm.botan_name(),
+        }
+    }
+}
+
 /// Random number generator types accepted by Botan.
 #[derive(Clone, Debug, PartialEq, Eq)]
 pub enum RngType {
@@ -1341,3 +1394,56 @@ impl RngTypeIdentifier for RngType {
         RngType::botan_name(self)
     }
 }
+
+// With the serde feature, every identifier type is serialized as its Botan
+// name, and deserialized by parsing that name.
+#[cfg(feature = "serde")]
+macro_rules! impl_serde_via_botan_name {
+    ($($name:ident),+ $(,)?) => {
+        $(
+            impl serde::Serialize for $name {
+                fn serialize<S: serde::Serializer>(
+                    &self,
+                    serializer: S,
+                ) -> core::result::Result<S::Ok, S::Error> {
+                    serializer.serialize_str(&self.botan_name())
+                }
+            }
+
+            impl<'de> serde::Deserialize<'de> for $name {
+                fn deserialize<D: serde::Deserializer<'de>>(
+                    deserializer: D,
+                ) -> core::result::Result<Self, D::Error> {
+                    let name = String::deserialize(deserializer)?;
+                    name.parse().map_err(serde::de::Error::custom)
+                }
+            }
+        )+
+    };
+}
+
+#[cfg(feature = "serde")]
+impl_serde_via_botan_name!(
+    HashAlgorithm,
+    BlockCipherAlgorithm,
+    CipherPadding,
+    StreamCipherAlgorithm,
+    CipherAlgorithm,
+    MacAlgorithm,
+    KdfAlgorithm,
+    PasswordHashAlgorithm,
+    Pkcs8Kdf,
+    PublicKeyAlgorithm,
+    EcGroupId,
+    DlGroup,
+    MlKemParams,
+    MlDsaParams,
+    SlhDsaParams,
+    FrodoKemParams,
+    ClassicMcElieceParams,
+    XmssParams,
+    EncryptionParams,
+    SignatureParams,
+    SignatureAlgorithm,
+    RngType,
+);
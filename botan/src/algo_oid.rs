@@ -0,0 +1,579 @@
+use crate::utils::*;
+
+use crate::{
+    EcGroup, EcGroupId, HashAlgorithm, MlDsaParams, MlKemParams, OID, PublicKeyAlgorithm,
+    SignatureAlgorithm, SignatureParams, SlhDsaParams,
+};
+
+// Each table maps a dotted OID to the name Botan registers it under, and to
+// the corresponding enum value. The names must match Botan's OID registry.
+
+const HASH_OIDS: &[(&str, &str, HashAlgorithm)] = &[
+    ("1.2.840.113549.2.5", "MD5", HashAlgorithm::Md5),
+    ("1.3.36.3.2.1", "RIPEMD-160", HashAlgorithm::Ripemd160),
+    ("1.3.14.3.2.26", "SHA-1", HashAlgorithm::Sha1),
+    ("2.16.840.1.101.3.4.2.4", "SHA-224", HashAlgorithm::Sha224),
+    ("2.16.840.1.101.3.4.2.1", "SHA-256", HashAlgorithm::Sha256),
+    ("2.16.840.1.101.3.4.2.2", "SHA-384", HashAlgorithm::Sha384),
+    ("2.16.840.1.101.3.4.2.3", "SHA-512", HashAlgorithm::Sha512),
+    (
+        "2.16.840.1.101.3.4.2.6",
+        "SHA-512-256",
+        HashAlgorithm::Sha512_256,
+    ),
+    (
+        "2.16.840.1.101.3.4.2.7",
+        "SHA-3(224)",
+        HashAlgorithm::Sha3(224),
+    ),
+    (
+        "2.16.840.1.101.3.4.2.8",
+        "SHA-3(256)",
+        HashAlgorithm::Sha3(256),
+    ),
+    (
+        "2.16.840.1.101.3.4.2.9",
+        "SHA-3(384)",
+        HashAlgorithm::Sha3(384),
+    ),
+    (
+        "2.16.840.1.101.3.4.2.10",
+        "SHA-3(512)",
+        HashAlgorithm::Sha3(512),
+    ),
+    // RFC 8692 fixes the output length of the SHAKE identifiers
+    (
+        "2.16.840.1.101.3.4.2.11",
+        "SHAKE-128(256)",
+        HashAlgorithm::Shake128(256),
+    ),
+    (
+        "2.16.840.1.101.3.4.2.12",
+        "SHAKE-256(512)",
+        HashAlgorithm::Shake256(512),
+    ),
+    (
+        "1.3.6.1.4.1.1722.12.2.1.8",
+        "BLAKE2b(256)",
+        HashAlgorithm::Blake2b(256),
+    ),
+    (
+        "1.3.6.1.4.1.1722.12.2.1.12",
+        "BLAKE2b(384)",
+        HashAlgorithm::Blake2b(384),
+    ),
+    (
+        "1.3.6.1.4.1.1722.12.2.1.16",
+        "BLAKE2b(512)",
+        HashAlgorithm::Blake2b(512),
+    ),
+    (
+        "1.3.6.1.4.1.1722.12.2.2.8",
+        "BLAKE2s(256)",
+        HashAlgorithm::Blake2s(256),
+    ),
+    ("1.2.156.10197.1.401", "SM3", HashAlgorithm::Sm3),
+    ("1.0.10118.3.0.55", "Whirlpool", HashAlgorithm::Whirlpool),
+];
+
+const PUBLIC_KEY_OIDS: &[(&str, &str, PublicKeyAlgorithm)] = &[
+    ("1.2.840.113549.1.1.1", "RSA", PublicKeyAlgorithm::Rsa),
+    ("1.2.840.10045.2.1", "ECDSA", PublicKeyAlgorithm::Ecdsa),
+    ("1.3.132.1.12", "ECDH", PublicKeyAlgorithm::Ecdh),
+    ("1.2.840.10046.2.1", "DH", PublicKeyAlgorithm::Dh),
+    ("1.3.36.3.3.2.5.2.1", "ECGDSA", PublicKeyAlgorithm::Ecgdsa),
+    ("1.0.14888.3.0.5", "ECKCDSA", PublicKeyAlgorithm::Eckcdsa),
+    ("1.2.156.10197.1.301.1", "SM2", PublicKeyAlgorithm::Sm2),
+    ("1.3.101.110", "X25519", PublicKeyAlgorithm::X25519),
+    ("1.3.101.111", "X448", PublicKeyAlgorithm::X448),
+    ("1.3.101.112", "Ed25519", PublicKeyAlgorithm::Ed25519),
+    ("1.3.101.113", "Ed448", PublicKeyAlgorithm::Ed448),
+    (
+        "1.2.840.113549.1.9.16.3.17",
+        "HSS-LMS",
+        PublicKeyAlgorithm::HssLms,
+    ),
+];
+
+const ML_KEM_OIDS: &[(&str, &str, MlKemParams)] = &[
+    (
+        "2.16.840.1.101.3.4.4.1",
+        "ML-KEM-512",
+        MlKemParams::MlKem512,
+    ),
+    (
+        "2.16.840.1.101.3.4.4.2",
+        "ML-KEM-768",
+        MlKemParams::MlKem768,
+    ),
+    (
+        "2.16.840.1.101.3.4.4.3",
+        "ML-KEM-1024",
+        MlKemParams::MlKem1024,
+    ),
+];
+
+const ML_DSA_OIDS: &[(&str, &str, MlDsaParams)] = &[
+    (
+        "2.16.840.1.101.3.4.3.17",
+        "ML-DSA-4x4",
+        MlDsaParams::MlDsa4x4,
+    ),
+    (
+        "2.16.840.1.101.3.4.3.18",
+        "ML-DSA-6x5",
+        MlDsaParams::MlDsa6x5,
+    ),
+    (
+        "2.16.840.1.101.3.4.3.19",
+        "ML-DSA-8x7",
+        MlDsaParams::MlDsa8x7,
+    ),
+];
+
+const SLH_DSA_OIDS: &[(&str, &str, SlhDsaParams)] = &[
+    (
+        "2.16.840.1.101.3.4.3.20",
+        "SLH-DSA-SHA2-128s",
+        SlhDsaParams::Sha2_128s,
+    ),
+    (
+        "2.16.840.1.101.3.4.3.21",
+        "SLH-DSA-SHA2-128f",
+        SlhDsaParams::Sha2_128f,
+    ),
+    (
+        "2.16.840.1.101.3.4.3.22",
+        "SLH-DSA-SHA2-192s",
+        SlhDsaParams::Sha2_192s,
+    ),
+    (
+        "2.16.840.1.101.3.4.3.23",
+        "SLH-DSA-SHA2-192f",
+        SlhDsaParams::Sha2_192f,
+    ),
+    (
+        "2.16.840.1.101.3.4.3.24",
+        "SLH-DSA-SHA2-256s",
+        SlhDsaParams::Sha2_256s,
+    ),
+    (
+        "2.16.840.1.101.3.4.3.25",
+        "SLH-DSA-SHA2-256f",
+        SlhDsaParams::Sha2_256f,
+    ),
+    (
+        "2.16.840.1.101.3.4.3.26",
+        "SLH-DSA-SHAKE-128s",
+        SlhDsaParams::Shake128s,
+    ),
+    (
+        "2.16.840.1.101.3.4.3.27",
+        "SLH-DSA-SHAKE-128f",
+        SlhDsaParams::Shake128f,
+    ),
+    (
+        "2.16.840.1.101.3.4.3.28",
+        "SLH-DSA-SHAKE-192s",
+        SlhDsaParams::Shake192s,
+    ),
+    (
+        "2.16.840.1.101.3.4.3.29",
+        "SLH-DSA-SHAKE-192f",
+        SlhDsaParams::Shake192f,
+    ),
+    (
+        "2.16.840.1.101.3.4.3.30",
+        "SLH-DSA-SHAKE-256s",
+        SlhDsaParams::Shake256s,
+    ),
+    (
+        "2.16.840.1.101.3.4.3.31",
+        "SLH-DSA-SHAKE-256f",
+        SlhDsaParams::Shake256f,
+    ),
+];
+
+const EC_GROUP_OIDS: &[(&str, &str, EcGroupId)] = &[
+    ("1.2.840.10045.3.1.1", "secp192r1", EcGroupId::Secp192r1),
+    ("1.3.132.0.33", "secp224r1", EcGroupId::Secp224r1),
+    ("1.2.840.10045.3.1.7", "secp256r1", EcGroupId::Secp256r1),
+    ("1.3.132.0.34", "secp384r1", EcGroupId::Secp384r1),
+    ("1.3.132.0.35", "secp521r1", EcGroupId::Secp521r1),
+    ("1.3.132.0.10", "secp256k1", EcGroupId::Secp256k1),
+    (
+        "1.3.36.3.3.2.8.1.1.7",
+        "brainpool256r1",
+        EcGroupId::Brainpool256r1,
+    ),
+    (
+        "1.3.36.3.3.2.8.1.1.11",
+        "brainpool384r1",
+        EcGroupId::Brainpool384r1,
+    ),
+    (
+        "1.3.36.3.3.2.8.1.1.13",
+        "brainpool512r1",
+        EcGroupId::Brainpool512r1,
+    ),
+    ("1.2.250.1.223.101.256.1", "frp256v1", EcGroupId::Frp256v1),
+    ("1.2.156.10197.1.301", "sm2p256v1", EcGroupId::Sm2p256v1),
+];
+
+macro_rules! sig_algo {
+    ($key:ident) => {
+        SignatureAlgorithm {
+            key_algorithm: PublicKeyAlgorithm::$key,
+            params: None,
+        }
+    };
+    ($key:ident, $params:ident, $hash:expr) => {
+        SignatureAlgorithm {
+            key_algorithm: PublicKeyAlgorithm::$key,
+            params: Some(SignatureParams::$params($hash)),
+        }
+    };
+}
+
+const SIGNATURE_OIDS: &[(&str, &str, SignatureAlgorithm)] = &[
+    (
+        "1.2.840.113549.1.1.5",
+        "RSA/PKCS1v15(SHA-1)",
+        sig_algo!(Rsa, RsaPkcs1v15, HashAlgorithm::Sha1),
+    ),
+    (
+        "1.2.840.113549.1.1.14",
+        "RSA/PKCS1v15(SHA-224)",
+        sig_algo!(Rsa, RsaPkcs1v15, HashAlgorithm::Sha224),
+    ),
+    (
+        "1.2.840.113549.1.1.11",
+        "RSA/PKCS1v15(SHA-256)",
+        sig_algo!(Rsa, RsaPkcs1v15, HashAlgorithm::Sha256),
+    ),
+    (
+        "1.2.840.113549.1.1.12",
+        "RSA/PKCS1v15(SHA-384)",
+        sig_algo!(Rsa, RsaPkcs1v15, HashAlgorithm::Sha384),
+    ),
+    (
+        "1.2.840.113549.1.1.13",
+        "RSA/PKCS1v15(SHA-512)",
+        sig_algo!(Rsa, RsaPkcs1v15, HashAlgorithm::Sha512),
+    ),
+    (
+        "2.16.840.1.101.3.4.3.13",
+        "RSA/PKCS1v15(SHA-3(224))",
+        sig_algo!(Rsa, RsaPkcs1v15, HashAlgorithm::Sha3(224)),
+    ),
+    (
+        "2.16.840.1.101.3.4.3.14",
+        "RSA/PKCS1v15(SHA-3(256))",
+        sig_algo!(Rsa, RsaPkcs1v15, HashAlgorithm::Sha3(256)),
+    ),
+    (
+        "2.16.840.1.101.3.4.3.15",
+        "RSA/PKCS1v15(SHA-3(384))",
+        sig_algo!(Rsa, RsaPkcs1v15, HashAlgorithm::Sha3(384)),
+    ),
+    (
+        "2.16.840.1.101.3.4.3.16",
+        "RSA/PKCS1v15(SHA-3(512))",
+        sig_algo!(Rsa, RsaPkcs1v15, HashAlgorithm::Sha3(512)),
+    ),
+    (
+        "1.2.840.10045.4.1",
+        "ECDSA/SHA-1",
+        sig_algo!(Ecdsa, Hash, HashAlgorithm::Sha1),
+    ),
+    (
+        "1.2.840.10045.4.3.1",
+        "ECDSA/SHA-224",
+        sig_algo!(Ecdsa, Hash, HashAlgorithm::Sha224),
+    ),
+    (
+        "1.2.840.10045.4.3.2",
+        "ECDSA/SHA-256",
+        sig_algo!(Ecdsa, Hash, HashAlgorithm::Sha256),
+    ),
+    (
+        "1.2.840.10045.4.3.3",
+        "ECDSA/SHA-384",
+        sig_algo!(Ecdsa, Hash, HashAlgorithm::Sha384),
+    ),
+    (
+        "1.2.840.10045.4.3.4",
+        "ECDSA/SHA-512",
+        sig_algo!(Ecdsa, Hash, HashAlgorithm::Sha512),
+    ),
+    (
+        "2.16.840.1.101.3.4.3.9",
+        "ECDSA/SHA-3(224)",
+        sig_algo!(Ecdsa, Hash, HashAlgorithm::Sha3(224)),
+    ),
+    (
+        "2.16.840.1.101.3.4.3.10",
+        "ECDSA/SHA-3(256)",
+        sig_algo!(Ecdsa, Hash, HashAlgorithm::Sha3(256)),
+    ),
+    (
+        "2.16.840.1.101.3.4.3.11",
+        "ECDSA/SHA-3(384)",
+        sig_algo!(Ecdsa, Hash, HashAlgorithm::Sha3(384)),
+    ),
+    (
+        "2.16.840.1.101.3.4.3.12",
+        "ECDSA/SHA-3(512)",
+        sig_algo!(Ecdsa, Hash, HashAlgorithm::Sha3(512)),
+    ),
+    (
+        "1.3.36.3.3.2.5.4.1",
+        "ECGDSA/RIPEMD-160",
+        sig_algo!(Ecgdsa, Hash, HashAlgorithm::Ripemd160),
+    ),
+    (
+        "1.3.36.3.3.2.5.4.2",
+        "ECGDSA/SHA-1",
+        sig_algo!(Ecgdsa, Hash, HashAlgorithm::Sha1),
+    ),
+    (
+        "1.3.36.3.3.2.5.4.3",
+        "ECGDSA/SHA-224",
+        sig_algo!(Ecgdsa, Hash, HashAlgorithm::Sha224),
+    ),
+    (
+        "1.3.36.3.3.2.5.4.4",
+        "ECGDSA/SHA-256",
+        sig_algo!(Ecgdsa, Hash, HashAlgorithm::Sha256),
+    ),
+    (
+        "1.3.36.3.3.2.5.4.5",
+        "ECGDSA/SHA-384",
+        sig_algo!(Ecgdsa, Hash, HashAlgorithm::Sha384),
+    ),
+    (
+        "1.3.36.3.3.2.5.4.6",
+        "ECGDSA/SHA-512",
+        sig_algo!(Ecgdsa, Hash, HashAlgorithm::Sha512),
+    ),
+    ("1.3.101.112", "Ed25519", sig_algo!(Ed25519)),
+    ("1.3.101.113", "Ed448", sig_algo!(Ed448)),
+    ("2.16.840.1.101.3.4.3.17", "ML-DSA-4x4", sig_algo!(MlDsa)),
+    ("2.16.840.1.101.3.4.3.18", "ML-DSA-6x5", sig_algo!(MlDsa)),
+    ("2.16.840.1.101.3.4.3.19", "ML-DSA-8x7", sig_algo!(MlDsa)),
+];
+
+/// Return the static table of object identifiers which this crate maps to
+/// algorithm identifiers, as pairs of the dotted decimal OID and the name
+/// Botan registers it under.
+///
+/// This covers hash functions, public key algorithms and parameter sets,
+/// elliptic curve groups, and signature algorithms. The table is built into
+/// this crate rather than read from the library, and Botan's FFI offers no way
+/// to enumerate its own OID registry; Botan knows further OIDs, which can be
+/// resolved using [`OID::from_str`] and [`OID::as_name`].
+///
+/// # Examples
+///
+/// ```
+/// let oids = botan::mapped_oids();
+/// assert!(oids.contains(&("2.16.840.1.101.3.4.2.1", "SHA-256")));
+/// ```
+#[must_use]
+pub fn mapped_oids() -> Vec<(&'static str, &'static str)> {
+    fn names<T>(
+        table: &'static [(&'static str, &'static str, T)],
+    ) -> impl Iterator<Item = (&'static str, &'static str)> {
+        table.iter().map(|(oid, name, _)| (*oid, *name))
+    }
+
+    let mut oids = Vec::new();
+    oids.extend(names(HASH_OIDS));
+    oids.extend(names(PUBLIC_KEY_OIDS));
+    oids.extend(names(ML_KEM_OIDS));
+    oids.extend(names(ML_DSA_OIDS));
+    oids.extend(names(SLH_DSA_OIDS));
+    oids.extend(names(EC_GROUP_OIDS));
+    // Ed25519, Ed448 and ML-DSA share their key and signature algorithm OIDs
+    oids.extend(names(SIGNATURE_OIDS).filter(|(oid, _)| {
+        !PUBLIC_KEY_OIDS.iter().any(|e| e.0 == *oid) && !ML_DSA_OIDS.iter().any(|e| e.0 == *oid)
+    }));
+    oids
+}
+
+/// Return the OID of value, if exactly one entry of the table matches it
+fn oid_of<T: PartialEq>(
+    table: &[(&'static str, &'static str, T)],
+    value: &T,
+) -> Option<&'static str> {
+    let mut matches = table.iter().filter(|e| e.2 == *value);
+    match (matches.next(), matches.next()) {
+        (Some(e), None) => Some(e.0),
+        _ => None,
+    }
+}
+
+fn lookup_oid<T: Clone>(
+    table: &[(&'static str, &'static str, T)],
+    oid: &OID,
+    what: &str,
+) -> Result<T> {
+    let dotted = oid.as_string()?;
+    match table.iter().find(|e| e.0 == dotted) {
+        Some(e) => Ok(e.2.clone()),
+        None => Err(Error::with_message(
+            ErrorType::NoValueAvailable,
+            format!("No known {what} has the OID {dotted}"),
+        )),
+    }
+}
+
+impl HashAlgorithm {
+    /// Return the object identifier of this hash function
+    ///
+    /// Hash functions not in [`mapped_oids`] are looked up by name in Botan's
+    /// OID registry. This requires Botan 3.8 or later; with older versions an
+    /// error of type [`ErrorType::NotImplemented`](crate::ErrorType::NotImplemented) is returned
+    pub fn oid(&self) -> Result<OID> {
+        match oid_of(HASH_OIDS, self) {
+            Some(oid) => OID::from_str(oid),
+            None => OID::from_str(&self.botan_name()),
+        }
+    }
+
+    /// Return the hash function with the given object identifier
+    ///
+    /// This requires Botan 3.8 or later; with older versions an error of type
+    /// [`ErrorType::NotImplemented`](crate::ErrorType::NotImplemented) is returned
+    pub fn from_oid(oid: &OID) -> Result<Self> {
+        lookup_oid(HASH_OIDS, oid, "hash function")
+    }
+}
+
+impl PublicKeyAlgorithm {
+    /// Return the object identifier of this public key algorithm
+    ///
+    /// ML-KEM, ML-DSA and SLH-DSA have an OID per parameter set rather than
+    /// one for the algorithm; for these use the `oid` function of
+    /// [`MlKemParams`], [`MlDsaParams`], or [`SlhDsaParams`].
+    ///
+    /// This requires Botan 3.8 or later; with older versions an error of type
+    /// [`ErrorType::NotImplemented`](crate::ErrorType::NotImplemented) is returned
+    pub fn oid(&self) -> Result<OID> {
+        match oid_of(PUBLIC_KEY_OIDS, self) {
+            Some(oid) => OID::from_str(oid),
+            None => OID::from_str(&self.botan_name()),
+        }
+    }
+
+    /// Return the public key algorithm with the given object identifier
+    ///
+    /// The OID of an ML-KEM, ML-DSA or SLH-DSA parameter set maps to the
+    /// respective algorithm.
+    ///
+    /// This requires Botan 3.8 or later; with older versions an error of type
+    /// [`ErrorType::NotImplemented`](crate::ErrorType::NotImplemented) is returned
+    pub fn from_oid(oid: &OID) -> Result<Self> {
+        let dotted = oid.as_string()?;
+        if ML_KEM_OIDS.iter().any(|e| e.0 == dotted) {
+            Ok(Self::MlKem)
+        } else if ML_DSA_OIDS.iter().any(|e| e.0 == dotted) {
+            Ok(Self::MlDsa)
+        } else if SLH_DSA_OIDS.iter().any(|e| e.0 == dotted) {
+            Ok(Self::SlhDsa)
+        } else {
+            lookup_oid(PUBLIC_KEY_OIDS, oid, "public key algorithm")
+        }
+    }
+}
+
+macro_rules! impl_param_set_oid {
+    ($name:ident, $table:ident, $what:literal) => {
+        impl $name {
+            /// Return the object identifier of this parameter set
+            ///
+            /// This requires Botan 3.8 or later; with older versions an error of type
+            /// [`ErrorType::NotImplemented`](crate::ErrorType::NotImplemented) is returned
+            pub fn oid(&self) -> Result<OID> {
+                match oid_of($table, self) {
+                    Some(oid) => OID::from_str(oid),
+                    None => OID::from_str(&self.botan_name()),
+                }
+            }
+
+            /// Return the parameter set with the given object identifier
+            ///
+            /// This requires Botan 3.8 or later; with older versions an error of type
+            /// [`ErrorType::NotImplemented`](crate::ErrorType::NotImplemented) is returned
+            pub fn from_oid(oid: &OID) -> Result<Self> {
+                lookup_oid($table, oid, $what)
+            }
+        }
+    };
+}
+
+impl_param_set_oid!(MlKemParams, ML_KEM_OIDS, "ML-KEM parameter set");
+impl_param_set_oid!(MlDsaParams, ML_DSA_OIDS, "ML-DSA parameter set");
+impl_param_set_oid!(SlhDsaParams, SLH_DSA_OIDS, "SLH-DSA parameter set");
+
+impl EcGroupId {
+    /// Return the object identifier of this EC group
+    ///
+    /// Groups not in [`mapped_oids`] are looked up by name in Botan's OID
+    /// registry. This requires Botan 3.8 or later; with older versions an
+    /// error of type [`ErrorType::NotImplemented`](crate::ErrorType::NotImplemented) is returned
+    pub fn oid(&self) -> Result<OID> {
+        match oid_of(EC_GROUP_OIDS, self) {
+            Some(oid) => OID::from_str(oid),
+            None => OID::from_str(&self.botan_name()),
+        }
+    }
+
+    /// Return the EC group with the given object identifier
+    ///
+    /// Groups Botan knows but which are not modeled by this enum are
+    /// returned as [`EcGroupId::Arbitrary`]; an OID which does not name an
+    /// EC group Botan can load is an error.
+    ///
+    /// This requires Botan 3.8 or later; with older versions an error of type
+    /// [`ErrorType::NotImplemented`](crate::ErrorType::NotImplemented) is returned
+    pub fn from_oid(oid: &OID) -> Result<Self> {
+        lookup_oid(EC_GROUP_OIDS, oid, "EC group").or_else(|e| {
+            // The OID registry also names hashes, ciphers, and so on
+            match (oid.as_name(), EcGroup::from_oid(oid)) {
+                (Ok(name), Ok(_)) => Ok(Self::Arbitrary(name)),
+                _ => Err(e),
+            }
+        })
+    }
+}
+
+impl SignatureAlgorithm {
+    /// Return the object identifier of this signature algorithm
+    ///
+    /// An error is returned for ML-DSA, since its OID depends on the
+    /// parameter set of the key; see [`MlDsaParams::oid`].
+    ///
+    /// This requires Botan 3.8 or later; with older versions an error of type
+    /// [`ErrorType::NotImplemented`](crate::ErrorType::NotImplemented) is returned
+    pub fn oid(&self) -> Result<OID> {
+        match oid_of(SIGNATURE_OIDS, self) {
+            Some(oid) => OID::from_str(oid),
+            None => Err(Error::with_message(
+                ErrorType::NoValueAvailable,
+                format!("No unique OID for the signature algorithm {self:?}"),
+            )),
+        }
+    }
+
+    /// Return the signature algorithm with the given object identifier
+    ///
+    /// RSA-PSS is not supported, since its hash function is carried in the
+    /// AlgorithmIdentifier parameters rather than in the OID.
+    ///
+    /// This requires Botan 3.8 or later; with older versions an error of type
+    /// [`ErrorType::NotImplemented`](crate::ErrorType::NotImplemented) is returned
+    pub fn from_oid(oid: &OID) -> Result<Self> {
+        lookup_oid(SIGNATURE_OIDS, oid, "signature algorithm")
+    }
+}
@@ -0,0 +1,610 @@
+use crate::utils::*;
+
+use core::str::FromStr;
+
+use crate::{
+    BlockCipherAlgorithm, CipherAlgorithm, CipherPadding, DlGroup, EcGroupId, EncryptionParams,
+    HashAlgorithm, KdfAlgorithm, MacAlgorithm, PasswordHashAlgorithm, Pkcs8Kdf, PublicKeyAlgorithm,
+    RngType, SignatureAlgorithm, SignatureParams, StreamCipherAlgorithm,
+};
+
+// Parsing of Botan algorithm names back into the enums of algo.rs.
+//
+// Each `parse_known` returns `None` if the name is not one modeled by the
+// enum, in which case `FromStr` falls back to `Arbitrary`. Where two
+// values share a name (for example `HKDF(BLAKE2b(256))` naming either a hash
+// or a MAC), the interpretation Botan itself would pick is returned.
+
+/// Split `spec` at each occurrence of `sep` not nested within parentheses,
+/// or return `None` if the parentheses are unbalanced.
+fn split_top_level(spec: &str, sep: char) -> Option<Vec<&str>> {
+    let mut parts = Vec::new();
+    let mut depth = 0usize;
+    let mut start = 0;
+
+    for (i, c) in spec.char_indices() {
+        match c {
+            '(' => depth += 1,
+            ')' => depth = depth.checked_sub(1)?,
+            c if c == sep && depth == 0 => {
+                parts.push(&spec[start..i]);
+                start = i + c.len_utf8();
+            }
+            _ => {}
+        }
+    }
+
+    if depth != 0 {
+        return None;
+    }
+
+    parts.push(&spec[start..]);
+    Some(parts)
+}
+
+/// Split an algorithm specification such as `HMAC(SHA-256)` into its name
+/// and arguments, or return `None` if it is malformed.
+fn split_algo_spec(spec: &str) -> Option<(&str, Vec<&str>)> {
+    let Some(open) = spec.find('(') else {
+        if spec.contains([')', ',']) {
+            return None;
+        }
+        return Some((spec, Vec::new()));
+    };
+
+    let args = split_top_level(spec[open + 1..].strip_suffix(')')?, ',')?;
+
+    if args.iter().any(|arg| arg.is_empty()) {
+        return None;
+    }
+
+    Some((&spec[..open], args))
+}
+
+fn parse_u32(s: &str) -> Option<u32> {
+    if s.is_empty() || !s.bytes().all(|b| b.is_ascii_digit()) {
+        return None;
+    }
+    s.parse().ok()
+}
+
+fn parse_optional_u32(args: &[&str]) -> Option<Option<u32>> {
+    match args {
+        [] => Some(None),
+        [value] => Some(Some(parse_u32(value)?)),
+        _ => None,
+    }
+}
+
+/// Parse an argument which may name either a MAC or a hash function; as in
+/// Botan, a MAC is preferred, and anything else is taken as a hash for HMAC.
+fn parse_hash_or_mac<T>(
+    arg: &str,
+    hash: impl FnOnce(HashAlgorithm) -> T,
+    mac: impl FnOnce(MacAlgorithm) -> T,
+) -> T {
+    match MacAlgorithm::parse_known(arg) {
+        Some(m) => mac(m),
+        None => hash(HashAlgorithm::from_botan_name_or_arbitrary(arg)),
+    }
+}
+
+macro_rules! impl_from_str_or_arbitrary {
+    ($($(#[$meta:meta])* $name:ident),+ $(,)?) => {
+        $(
+            impl $name {
+                fn from_botan_name_or_arbitrary(name: &str) -> Self {
+                    Self::parse_known(name).unwrap_or_else(|| Self::Arbitrary(name.to_string()))
+                }
+            }
+
+            /// Parse a Botan name, such as one returned by `botan_name`.
+            ///
+            /// A name not modeled by this type is returned as `Arbitrary`,
+            /// so parsing never fails.
+            $(#[$meta])*
+            impl FromStr for $name {
+                type Err = Error;
+
+                fn from_str(name: &str) -> Result<Self> {
+                    Ok(Self::from_botan_name_or_arbitrary(name))
+                }
+            }
+
+            impl TryFrom<&str> for $name {
+                type Error = Error;
+
+                fn try_from(name: &str) -> Result<Self> {
+                    name.parse()
+                }
+            }
+        )+
+    };
+}
+
+impl_from_str_or_arbitrary!(
+    HashAlgorithm,
+    BlockCipherAlgorithm,
+    CipherPadding,
+    StreamCipherAlgorithm,
+    ///
+    /// A `CTR-BE` name parses as [`CipherAlgorithm::CtrBe`] rather than as a
+    /// [`CipherAlgorithm::Stream`]; other stream ciphers parse as `Stream`.
+    /// CCM with only a length field size names the default tag length, so
+    /// it parses back with the tag length explicit.
+    CipherAlgorithm,
+    MacAlgorithm,
+    ///
+    /// As in Botan, an argument naming a MAC (including `BLAKE2b(n)`) selects
+    /// the MAC variant, such as [`KdfAlgorithm::HkdfMac`], and any other
+    /// argument is taken as a hash function for HMAC. SP800-108 names with
+    /// only an output length field size parse back with the counter size
+    /// explicit.
+    KdfAlgorithm,
+    ///
+    /// As for [`KdfAlgorithm`], a PBKDF2 argument naming a MAC selects
+    /// [`PasswordHashAlgorithm::Pbkdf2Mac`].
+    PasswordHashAlgorithm,
+    ///
+    /// A hash function name parses as [`Pkcs8Kdf::Pbkdf2`].
+    Pkcs8Kdf,
+    PublicKeyAlgorithm,
+    EcGroupId,
+    DlGroup,
+    ///
+    /// A hash function name parses as [`EncryptionParams::Sm2`].
+    EncryptionParams,
+    ///
+    /// A hash function name, or `EMSA1(hash)`, parses as
+    /// [`SignatureParams::Hash`], and `user_id,hash` as
+    /// [`SignatureParams::Sm2`]. SM2 parameters without a hash are not
+    /// distinguishable from other names, and parse as `Arbitrary`.
+    SignatureParams,
+    RngType,
+);
+
+impl HashAlgorithm {
+    fn parse_known(name: &str) -> Option<Self> {
+        let (algo, args) = split_algo_spec(name)?;
+
+        let bits = |args: &[&str]| match args {
+            [bits] => parse_u32(bits),
+            _ => None,
+        };
+
+        Some(match (algo, args.as_slice()) {
+            ("Ascon-Hash256", []) => Self::AsconHash256,
+            ("BLAKE2b", args) => Self::Blake2b(bits(args)?),
+            ("BLAKE2s", args) => Self::Blake2s(bits(args)?),
+            ("CRC24", []) => Self::Crc24,
+            ("MD5", []) => Self::Md5,
+            ("RIPEMD-160", []) => Self::Ripemd160,
+            ("SHA-1", []) => Self::Sha1,
+            ("SHA-224", []) => Self::Sha224,
+            ("SHA-256", []) => Self::Sha256,
+            ("SHA-384", []) => Self::Sha384,
+            ("SHA-512", []) => Self::Sha512,
+            ("SHA-512-256", []) => Self::Sha512_256,
+            ("SHA-3", args) => Self::Sha3(bits(args)?),
+            ("SHAKE-128", args) => Self::Shake128(bits(args)?),
+            ("SHAKE-256", args) => Self::Shake256(bits(args)?),
+            ("Skein-512", args) => Self::Skein512(bits(args)?),
+            ("SM3", []) => Self::Sm3,
+            ("Whirlpool", []) => Self::Whirlpool,
+            _ => return None,
+        })
+    }
+}
+
+impl BlockCipherAlgorithm {
+    fn parse_known(name: &str) -> Option<Self> {
+        Some(match name {
+            "AES-128" => Self::Aes128,
+            "AES-192" => Self::Aes192,
+            "AES-256" => Self::Aes256,
+            "ARIA-128" => Self::Aria128,
+            "ARIA-192" => Self::Aria192,
+            "ARIA-256" => Self::Aria256,
+            "Blowfish" => Self::Blowfish,
+            "Camellia-128" => Self::Camellia128,
+            "Camellia-192" => Self::Camellia192,
+            "Camellia-256" => Self::Camellia256,
+            "CAST-128" => Self::Cast128,
+            "DES" => Self::Des,
+            "IDEA" => Self::Idea,
+            "Kuznyechik" => Self::Kuznyechik,
+            "SEED" => Self::Seed,
+            "Serpent" => Self::Serpent,
+            "SHACAL2" => Self::Shacal2,
+            "SM4" => Self::Sm4,
+            "Threefish-512" => Self::Threefish512,
+            "TripleDES" => Self::TripleDes,
+            "Twofish" => Self::Twofish,
+            _ => return None,
+        })
+    }
+}
+
+impl CipherPadding {
+    fn parse_known(name: &str) -> Option<Self> {
+        Some(match name {
+            "X9.23" => Self::AnsiX923,
+            "CTS" => Self::Cts,
+            "ESP" => Self::Esp,
+            "NoPadding" => Self::NoPadding,
+            "OneAndZeros" => Self::OneAndZeros,
+            "PKCS7" => Self::Pkcs7,
+            _ => return None,
+        })
+    }
+}
+
+fn parse_ctr_be(args: &[&str]) -> Option<(BlockCipherAlgorithm, Option<u32>)> {
+    let (cipher, width) = args.split_first()?;
+    Some((
+        BlockCipherAlgorithm::from_botan_name_or_arbitrary(cipher),
+        parse_optional_u32(width)?,
+    ))
+}
+
+impl StreamCipherAlgorithm {
+    fn parse_known(name: &str) -> Option<Self> {
+        let (algo, args) = split_algo_spec(name)?;
+
+        Some(match (algo, args.as_slice()) {
+            ("ChaCha", ["8"]) => Self::ChaCha8,
+            ("ChaCha", ["12"]) => Self::ChaCha12,
+            ("ChaCha", ["20"]) | ("ChaCha20", []) => Self::ChaCha20,
+            ("CTR-BE", args) => {
+                let (cipher, width) = parse_ctr_be(args)?;
+                Self::CtrBe(cipher, width)
+            }
+            ("OFB", [cipher]) => {
+                Self::Ofb(BlockCipherAlgorithm::from_botan_name_or_arbitrary(cipher))
+            }
+            ("RC4", args) => Self::Rc4(parse_optional_u32(args)?),
+            ("Salsa20", []) => Self::Salsa20,
+            _ => return None,
+        })
+    }
+}
+
+impl CipherAlgorithm {
+    fn parse_known(name: &str) -> Option<Self> {
+        match split_top_level(name, '/')?.as_slice() {
+            [spec] => {
+                let (algo, args) = split_algo_spec(spec)?;
+                Some(match (algo, args.as_slice()) {
+                    ("Ascon-AEAD128", []) => Self::AsconAead128,
+                    ("ChaCha20Poly1305", []) => Self::ChaCha20Poly1305,
+                    ("CTR-BE", args) => {
+                        let (cipher, width) = parse_ctr_be(args)?;
+                        Self::CtrBe(cipher, width)
+                    }
+                    _ => Self::Stream(StreamCipherAlgorithm::parse_known(spec)?),
+                })
+            }
+            [cipher, mode] => {
+                let cipher = BlockCipherAlgorithm::from_botan_name_or_arbitrary(cipher);
+                let (mode, args) = split_algo_spec(mode)?;
+                Some(match (mode, args.as_slice()) {
+                    ("CBC", []) => Self::Cbc(cipher, None),
+                    ("CCM", [tag_len, length_field]) => Self::Ccm(
+                        cipher,
+                        Some(parse_u32(tag_len)?),
+                        Some(parse_u32(length_field)?),
+                    ),
+                    ("CCM", args) => Self::Ccm(cipher, parse_optional_u32(args)?, None),
+                    ("CFB", args) => Self::Cfb(cipher, parse_optional_u32(args)?),
+                    ("EAX", args) => Self::Eax(cipher, parse_optional_u32(args)?),
+                    ("GCM", args) => Self::Gcm(cipher, parse_optional_u32(args)?),
+                    ("GCM-SIV", []) => Self::GcmSiv(cipher),
+                    ("OCB", args) => Self::Ocb(cipher, parse_optional_u32(args)?),
+                    ("SIV", []) => Self::Siv(cipher),
+                    ("XTS", []) => Self::Xts(cipher),
+                    _ => return None,
+                })
+            }
+            [cipher, "CBC", padding] => Some(Self::Cbc(
+                BlockCipherAlgorithm::from_botan_name_or_arbitrary(cipher),
+                Some(CipherPadding::from_botan_name_or_arbitrary(padding)),
+            )),
+            _ => None,
+        }
+    }
+}
+
+impl MacAlgorithm {
+    fn parse_known(name: &str) -> Option<Self> {
+        let (algo, args) = split_algo_spec(name)?;
+
+        Some(match (algo, args.as_slice()) {
+            ("BLAKE2b", [bits]) => Self::Blake2b(parse_u32(bits)?),
+            ("CMAC", [cipher]) => {
+                Self::Cmac(BlockCipherAlgorithm::from_botan_name_or_arbitrary(cipher))
+            }
+            ("GMAC", [cipher]) => {
+                Self::Gmac(BlockCipherAlgorithm::from_botan_name_or_arbitrary(cipher))
+            }
+            ("HMAC", [hash]) => Self::Hmac(HashAlgorithm::from_botan_name_or_arbitrary(hash)),
+            ("KMAC-128", [bits]) => Self::Kmac128(parse_u32(bits)?),
+            ("KMAC-256", [bits]) => Self::Kmac256(parse_u32(bits)?),
+            ("Poly1305", []) => Self::Poly1305,
+            _ => return None,
+        })
+    }
+}
+
+type Sp800108Variant = fn(MacAlgorithm, Option<u32>, Option<u32>) -> KdfAlgorithm;
+
+fn parse_sp800_108(args: &[&str], variant: Sp800108Variant) -> Option<KdfAlgorithm> {
+    let (mac, sizes) = args.split_first()?;
+    let mac = MacAlgorithm::from_botan_name_or_arbitrary(mac);
+    Some(match sizes {
+        [] => variant(mac, None, None),
+        [counter_bits] => variant(mac, Some(parse_u32(counter_bits)?), None),
+        [counter_bits, length_bits] => variant(
+            mac,
+            Some(parse_u32(counter_bits)?),
+            Some(parse_u32(length_bits)?),
+        ),
+        _ => return None,
+    })
+}
+
+impl KdfAlgorithm {
+    fn parse_known(name: &str) -> Option<Self> {
+        let (algo, args) = split_algo_spec(name)?;
+
+        Some(match (algo, args.as_slice()) {
+            ("HKDF", [prf]) => parse_hash_or_mac(prf, Self::Hkdf, Self::HkdfMac),
+            ("HKDF-Expand", [prf]) => parse_hash_or_mac(prf, Self::HkdfExpand, Self::HkdfExpandMac),
+            ("HKDF-Extract", [prf]) => {
+                parse_hash_or_mac(prf, Self::HkdfExtract, Self::HkdfExtractMac)
+            }
+            ("KDF1", [hash]) => Self::Kdf1(HashAlgorithm::from_botan_name_or_arbitrary(hash)),
+            ("KDF1-18033", [hash]) => {
+                Self::Kdf1Iso18033(HashAlgorithm::from_botan_name_or_arbitrary(hash))
+            }
+            ("KDF2", [hash]) => Self::Kdf2(HashAlgorithm::from_botan_name_or_arbitrary(hash)),
+            ("Raw", []) => Self::Raw,
+            ("SP800-56A", [prf]) => parse_hash_or_mac(prf, Self::Sp80056aHash, Self::Sp80056aMac),
+            ("SP800-56C", [prf]) => parse_hash_or_mac(prf, Self::Sp80056cHash, Self::Sp80056cMac),
+            ("SP800-108-Counter", args) => parse_sp800_108(args, Self::Sp800108Counter)?,
+            ("SP800-108-Feedback", args) => parse_sp800_108(args, Self::Sp800108Feedback)?,
+            ("SP800-108-Pipeline", args) => parse_sp800_108(args, Self::Sp800108Pipeline)?,
+            ("TLS-12-PRF", [prf]) => parse_hash_or_mac(prf, Self::Tls12Prf, Self::Tls12PrfMac),
+            ("X9.42-PRF", [key_wrap_oid]) => Self::X942Prf(key_wrap_oid.to_string()),
+            _ => return None,
+        })
+    }
+}
+
+impl PasswordHashAlgorithm {
+    fn parse_known(name: &str) -> Option<Self> {
+        let (algo, args) = split_algo_spec(name)?;
+
+        Some(match (algo, args.as_slice()) {
+            ("Argon2d", []) => Self::Argon2d,
+            ("Argon2i", []) => Self::Argon2i,
+            ("Argon2id", []) => Self::Argon2id,
+            ("Bcrypt-PBKDF", []) => Self::BcryptPbkdf,
+            ("OpenPGP-S2K", [hash]) => {
+                Self::OpenPgpS2k(HashAlgorithm::from_botan_name_or_arbitrary(hash))
+            }
+            ("PBKDF2", [prf]) => parse_hash_or_mac(prf, Self::Pbkdf2, Self::Pbkdf2Mac),
+            ("PKCS12-KDF", [hash, id]) => Self::Pkcs12Kdf(
+                HashAlgorithm::from_botan_name_or_arbitrary(hash),
+                parse_u32(id)?,
+            ),
+            ("Scrypt", []) => Self::Scrypt,
+            _ => return None,
+        })
+    }
+}
+
+impl Pkcs8Kdf {
+    fn parse_known(name: &str) -> Option<Self> {
+        match name {
+            "Scrypt" => Some(Self::Scrypt),
+            _ => HashAlgorithm::parse_known(name).map(Self::Pbkdf2),
+        }
+    }
+}
+
+impl PublicKeyAlgorithm {
+    fn parse_known(name: &str) -> Option<Self> {
+        Some(match name {
+            "ClassicMcEliece" => Self::ClassicMcEliece,
+            "DH" => Self::Dh,
+            "ECDH" => Self::Ecdh,
+            "ECDSA" => Self::Ecdsa,
+            "ECGDSA" => Self::Ecgdsa,
+            "ECKCDSA" => Self::Eckcdsa,
+            "Ed25519" => Self::Ed25519,
+            "Ed448" => Self::Ed448,
+            "FrodoKEM" => Self::FrodoKem,
+            "HSS-LMS" => Self::HssLms,
+            "ML-DSA" => Self::MlDsa,
+            "ML-KEM" => Self::MlKem,
+            "RSA" => Self::Rsa,
+            "SLH-DSA" => Self::SlhDsa,
+            "SM2" => Self::Sm2,
+            "X25519" => Self::X25519,
+            "X448" => Self::X448,
+            "XMSS" => Self::Xmss,
+            _ => return None,
+        })
+    }
+}
+
+impl EcGroupId {
+    fn parse_known(name: &str) -> Option<Self> {
+        Some(match name {
+            "brainpool256r1" => Self::Brainpool256r1,
+            "brainpool384r1" => Self::Brainpool384r1,
+            "brainpool512r1" => Self::Brainpool512r1,
+            "frp256v1" => Self::Frp256v1,
+            "numsp512d1" => Self::Numsp512d1,
+            "secp192r1" => Self::Secp192r1,
+            "secp224r1" => Self::Secp224r1,
+            "secp256k1" => Self::Secp256k1,
+            "secp256r1" => Self::Secp256r1,
+            "secp384r1" => Self::Secp384r1,
+            "secp521r1" => Self::Secp521r1,
+            "sm2p256v1" => Self::Sm2p256v1,
+            _ => return None,
+        })
+    }
+}
+
+impl DlGroup {
+    fn parse_known(name: &str) -> Option<Self> {
+        Some(match name {
+            "ffdhe/ietf/2048" => Self::FfdheIetf2048,
+            "ffdhe/ietf/3072" => Self::FfdheIetf3072,
+            "ffdhe/ietf/4096" => Self::FfdheIetf4096,
+            "ffdhe/ietf/6144" => Self::FfdheIetf6144,
+            "ffdhe/ietf/8192" => Self::FfdheIetf8192,
+            "modp/ietf/2048" => Self::ModpIetf2048,
+            "modp/ietf/3072" => Self::ModpIetf3072,
+            "modp/ietf/4096" => Self::ModpIetf4096,
+            "modp/ietf/6144" => Self::ModpIetf6144,
+            "modp/ietf/8192" => Self::ModpIetf8192,
+            _ => return None,
+        })
+    }
+}
+
+impl EncryptionParams {
+    fn parse_known(name: &str) -> Option<Self> {
+        let (algo, args) = split_algo_spec(name)?;
+
+        Some(match (algo, args.as_slice()) {
+            ("Raw", []) => Self::Raw,
+            ("OAEP", [hash, rest @ ..]) => {
+                let (mgf1_hash, label) = match rest {
+                    [] => (None, None),
+                    [mgf1, label @ ..] => {
+                        let (mgf1, mgf1_args) = split_algo_spec(mgf1)?;
+                        let mgf1_hash = match (mgf1, mgf1_args.as_slice()) {
+                            ("MGF1", []) => None,
+                            ("MGF1", [mgf1_hash]) => {
+                                Some(HashAlgorithm::from_botan_name_or_arbitrary(mgf1_hash))
+                            }
+                            _ => return None,
+                        };
+                        let label = match label {
+                            [] => None,
+                            [label] => Some(label.to_string()),
+                            _ => return None,
+                        };
+                        (mgf1_hash, label)
+                    }
+                };
+                Self::RsaOaep {
+                    hash: HashAlgorithm::from_botan_name_or_arbitrary(hash),
+                    mgf1_hash,
+                    label,
+                }
+            }
+            ("PKCS1v15", []) => Self::RsaPkcs1v15,
+            _ => Self::Sm2(HashAlgorithm::parse_known(name)?),
+        })
+    }
+}
+
+fn parse_pss(
+    args: &[&str],
+    variant: fn(HashAlgorithm, Option<u32>) -> SignatureParams,
+) -> Option<SignatureParams> {
+    let (hash, salt_len) = match args {
+        [hash] => (hash, None),
+        [hash, "MGF1", salt_len] => (hash, Some(parse_u32(salt_len)?)),
+        _ => return None,
+    };
+    Some(variant(
+        HashAlgorithm::from_botan_name_or_arbitrary(hash),
+        salt_len,
+    ))
+}
+
+impl SignatureParams {
+    fn parse_known(name: &str) -> Option<Self> {
+        // SM2 parameters are the only ones with a comma outside of parentheses
+        if let [user_id, hash] = split_top_level(name, ',')?.as_slice() {
+            return Some(Self::Sm2 {
+                user_id: user_id.to_string(),
+                hash: Some(HashAlgorithm::from_botan_name_or_arbitrary(hash)),
+            });
+        }
+
+        let (algo, args) = split_algo_spec(name)?;
+
+        Some(match (algo, args.as_slice()) {
+            ("Deterministic", []) => Self::Deterministic,
+            ("Ed25519ph", []) => Self::Ed25519ph,
+            ("Ed448ph", []) => Self::Ed448ph,
+            ("EMSA1", [hash]) => Self::Hash(HashAlgorithm::from_botan_name_or_arbitrary(hash)),
+            ("Randomized", []) => Self::Randomized,
+            ("Raw", []) => Self::Raw(None),
+            ("Raw", [hash]) => Self::Raw(Some(HashAlgorithm::from_botan_name_or_arbitrary(hash))),
+            ("PKCS1v15", ["Raw"]) => Self::RsaPkcs1v15Raw(None),
+            ("PKCS1v15", ["Raw", hash]) => {
+                Self::RsaPkcs1v15Raw(Some(HashAlgorithm::from_botan_name_or_arbitrary(hash)))
+            }
+            ("PKCS1v15", [hash]) => {
+                Self::RsaPkcs1v15(HashAlgorithm::from_botan_name_or_arbitrary(hash))
+            }
+            ("PSS", args) => parse_pss(args, |hash, salt_len| Self::RsaPss { hash, salt_len })?,
+            ("PSS_Raw", args) => {
+                parse_pss(args, |hash, salt_len| Self::RsaPssRaw { hash, salt_len })?
+            }
+            ("X9.31", [hash]) => Self::RsaX931(HashAlgorithm::from_botan_name_or_arbitrary(hash)),
+            _ => Self::Hash(HashAlgorithm::parse_known(name)?),
+        })
+    }
+}
+
+/// Parse a signature algorithm name such as `ECDSA/SHA-256`, as returned by
+/// `botan_name`; this never fails.
+impl FromStr for SignatureAlgorithm {
+    type Err = Error;
+
+    fn from_str(name: &str) -> Result<Self> {
+        let (key_algorithm, params) = match name.split_once('/') {
+            Some((key_algorithm, params)) => (
+                key_algorithm,
+                Some(SignatureParams::from_botan_name_or_arbitrary(params)),
+            ),
+            None => (name, None),
+        };
+
+        Ok(Self {
+            key_algorithm: PublicKeyAlgorithm::from_botan_name_or_arbitrary(key_algorithm),
+            params,
+        })
+    }
+}
+
+impl TryFrom<&str> for SignatureAlgorithm {
+    type Error = Error;
+
+    fn try_from(name: &str) -> Result<Self> {
+        name.parse()
+    }
+}
+
+impl RngType {
+    fn parse_known(name: &str) -> Option<Self> {
+        Some(match name {
+            "esdm-full" => Self::EsdmFull,
+            "esdm-pr" => Self::EsdmPr,
+            "hwrng" => Self::Hwrng,
+            "jitter" => Self::Jitter,
+            "system" => Self::System,
+            "user" => Self::User,
+            _ => return None,
+        })
+    }
+}
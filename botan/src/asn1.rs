@@ -1,3 +1,4 @@
+use crate::mp::MPI;
 use crate::utils::*;
 use botan_sys::*;
 use core::cmp::Ordering;
@@ -90,3 +91,1417 @@ impl Ord for OID {
         self.compare(other).expect("botan_oid_cmp should succeed")
     }
 }
+
+/// Serialized as a dotted decimal string; a registered name is also accepted
+/// when deserializing.
+#[cfg(feature = "serde")]
+impl serde::Serialize for OID {
+    fn serialize<S: serde::Serializer>(
+        &self,
+        serializer: S,
+    ) -> core::result::Result<S::Ok, S::Error> {
+        let dotted = self.as_string().map_err(serde::ser::Error::custom)?;
+        serializer.serialize_str(&dotted)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de> serde::Deserialize<'de> for OID {
+    fn deserialize<D: serde::Deserializer<'de>>(
+        deserializer: D,
+    ) -> core::result::Result<Self, D::Error> {
+        let s = <String as serde::Deserialize>::deserialize(deserializer)?;
+        OID::from_str(&s).map_err(serde::de::Error::custom)
+    }
+}
+
+/// The class of an ASN.1 tag
+#[derive(Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Asn1Class {
+    /// Universal types such as INTEGER and SEQUENCE
+    Universal,
+    /// Application specific tags
+    Application,
+    /// Context specific tags, written as `[n]`
+    ContextSpecific,
+    /// Private tags
+    Private,
+}
+
+impl Asn1Class {
+    fn bits(self) -> u8 {
+        match self {
+            Self::Universal => 0x00,
+            Self::Application => 0x40,
+            Self::ContextSpecific => 0x80,
+            Self::Private => 0xC0,
+        }
+    }
+
+    fn from_bits(b: u8) -> Self {
+        match b & 0xC0 {
+            0x00 => Self::Universal,
+            0x40 => Self::Application,
+            0x80 => Self::ContextSpecific,
+            _ => Self::Private,
+        }
+    }
+}
+
+/// An ASN.1 tag (class, constructed bit, and tag number)
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub struct Asn1Tag {
+    /// The class of the tag
+    pub class: Asn1Class,
+    /// If the value is constructed (contains other objects) rather than primitive
+    pub constructed: bool,
+    /// The tag number
+    pub number: u32,
+}
+
+impl Asn1Tag {
+    /// BOOLEAN
+    pub const BOOLEAN: Self = Self::universal(1, false);
+    /// INTEGER
+    pub const INTEGER: Self = Self::universal(2, false);
+    /// BIT STRING
+    pub const BIT_STRING: Self = Self::universal(3, false);
+    /// OCTET STRING
+    pub const OCTET_STRING: Self = Self::universal(4, false);
+    /// NULL
+    pub const NULL: Self = Self::universal(5, false);
+    /// OBJECT IDENTIFIER
+    pub const OBJECT_IDENTIFIER: Self = Self::universal(6, false);
+    /// ENUMERATED
+    pub const ENUMERATED: Self = Self::universal(10, false);
+    /// UTF8String
+    pub const UTF8_STRING: Self = Self::universal(12, false);
+    /// SEQUENCE and SEQUENCE OF
+    pub const SEQUENCE: Self = Self::universal(16, true);
+    /// SET and SET OF
+    pub const SET: Self = Self::universal(17, true);
+    /// NumericString
+    pub const NUMERIC_STRING: Self = Self::universal(18, false);
+    /// PrintableString
+    pub const PRINTABLE_STRING: Self = Self::universal(19, false);
+    /// TeletexString (T61String)
+    pub const TELETEX_STRING: Self = Self::universal(20, false);
+    /// IA5String
+    pub const IA5_STRING: Self = Self::universal(22, false);
+    /// UTCTime
+    pub const UTC_TIME: Self = Self::universal(23, false);
+    /// GeneralizedTime
+    pub const GENERALIZED_TIME: Self = Self::universal(24, false);
+    /// VisibleString
+    pub const VISIBLE_STRING: Self = Self::universal(26, false);
+    /// UniversalString
+    pub const UNIVERSAL_STRING: Self = Self::universal(28, false);
+    /// BMPString
+    pub const BMP_STRING: Self = Self::universal(30, false);
+
+    /// Create a new tag
+    #[must_use]
+    pub const fn new(class: Asn1Class, constructed: bool, number: u32) -> Self {
+        Self {
+            class,
+            constructed,
+            number,
+        }
+    }
+
+    const fn universal(number: u32, constructed: bool) -> Self {
+        Self::new(Asn1Class::Universal, constructed, number)
+    }
+
+    /// Create a context specific tag `[number]`
+    ///
+    /// Explicit tags are always constructed; implicit tags take the
+    /// constructed bit of the type they replace.
+    #[must_use]
+    pub const fn context_specific(number: u32, constructed: bool) -> Self {
+        Self::new(Asn1Class::ContextSpecific, constructed, number)
+    }
+
+    fn encode_into(&self, out: &mut Vec<u8>) {
+        let mut first = self.class.bits();
+        if self.constructed {
+            first |= 0x20;
+        }
+
+        if self.number < 31 {
+            out.push(first | self.number as u8);
+        } else {
+            out.push(first | 0x1F);
+            push_base128(out, u64::from(self.number));
+        }
+    }
+
+    /// Return a human readable name for this tag, eg `SEQUENCE` or `cont [ 0 ]`
+    #[must_use]
+    pub fn name(&self) -> String {
+        match self.class {
+            Asn1Class::Universal => match universal_tag_name(self.number) {
+                Some(name) => name.to_string(),
+                None => format!("univ [ {} ]", self.number),
+            },
+            Asn1Class::Application => format!("appl [ {} ]", self.number),
+            Asn1Class::ContextSpecific => format!("cont [ {} ]", self.number),
+            Asn1Class::Private => format!("priv [ {} ]", self.number),
+        }
+    }
+}
+
+fn universal_tag_name(number: u32) -> Option<&'static str> {
+    Some(match number {
+        0 => "EOC",
+        1 => "BOOLEAN",
+        2 => "INTEGER",
+        3 => "BIT STRING",
+        4 => "OCTET STRING",
+        5 => "NULL",
+        6 => "OBJECT",
+        7 => "OBJECT DESCRIPTOR",
+        8 => "EXTERNAL",
+        9 => "REAL",
+        10 => "ENUMERATED",
+        12 => "UTF8STRING",
+        13 => "RELATIVE OID",
+        16 => "SEQUENCE",
+        17 => "SET",
+        18 => "NUMERICSTRING",
+        19 => "PRINTABLESTRING",
+        20 => "T61STRING",
+        21 => "VIDEOTEXSTRING",
+        22 => "IA5STRING",
+        23 => "UTCTIME",
+        24 => "GENERALIZEDTIME",
+        25 => "GRAPHICSTRING",
+        26 => "VISIBLESTRING",
+        27 => "GENERALSTRING",
+        28 => "UNIVERSALSTRING",
+        30 => "BMPSTRING",
+        _ => return None,
+    })
+}
+
+fn push_base128(out: &mut Vec<u8>, mut v: u64) {
+    let mut tmp = [0u8; 10];
+    let mut n = 0;
+    loop {
+        tmp[n] = (v & 0x7F) as u8;
+        n += 1;
+        v >>= 7;
+        if v == 0 {
+            break;
+        }
+    }
+    for i in (0..n).rev() {
+        let cont = if i > 0 { 0x80 } else { 0 };
+        out.push(tmp[i] | cont);
+    }
+}
+
+fn der_error(msg: &str) -> Error {
+    Error::with_message(ErrorType::InvalidInput, format!("Invalid DER: {msg}"))
+}
+
+/// The character set of an ASN.1 string type
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum Asn1StringType {
+    /// UTF8String
+    Utf8,
+    /// NumericString (digits and space)
+    Numeric,
+    /// PrintableString (a restricted subset of ASCII)
+    Printable,
+    /// TeletexString, treated as ISO 8859-1
+    Teletex,
+    /// IA5String (ASCII)
+    Ia5,
+    /// VisibleString (printable ASCII)
+    Visible,
+    /// UniversalString (UCS-4)
+    Universal,
+    /// BMPString (UCS-2)
+    Bmp,
+}
+
+impl Asn1StringType {
+    /// Return the universal tag used for this string type
+    #[must_use]
+    pub fn tag(self) -> Asn1Tag {
+        match self {
+            Self::Utf8 => Asn1Tag::UTF8_STRING,
+            Self::Numeric => Asn1Tag::NUMERIC_STRING,
+            Self::Printable => Asn1Tag::PRINTABLE_STRING,
+            Self::Teletex => Asn1Tag::TELETEX_STRING,
+            Self::Ia5 => Asn1Tag::IA5_STRING,
+            Self::Visible => Asn1Tag::VISIBLE_STRING,
+            Self::Universal => Asn1Tag::UNIVERSAL_STRING,
+            Self::Bmp => Asn1Tag::BMP_STRING,
+        }
+    }
+
+    /// Return the string type with the given tag, if any
+    #[must_use]
+    pub fn from_tag(tag: Asn1Tag) -> Option<Self> {
+        if tag.class != Asn1Class::Universal || tag.constructed {
+            return None;
+        }
+        Some(match tag.number {
+            12 => Self::Utf8,
+            18 => Self::Numeric,
+            19 => Self::Printable,
+            20 => Self::Teletex,
+            22 => Self::Ia5,
+            26 => Self::Visible,
+            28 => Self::Universal,
+            30 => Self::Bmp,
+            _ => return None,
+        })
+    }
+
+    fn allows(self, c: char) -> bool {
+        match self {
+            Self::Utf8 | Self::Universal => true,
+            Self::Numeric => c.is_ascii_digit() || c == ' ',
+            Self::Printable => c.is_ascii_alphanumeric() || " '()+,-./:=?".contains(c),
+            Self::Teletex => (c as u32) <= 0xFF,
+            Self::Ia5 => c.is_ascii(),
+            Self::Visible => (' '..='~').contains(&c),
+            Self::Bmp => (c as u32) <= 0xFFFF,
+        }
+    }
+
+    fn encode(self, s: &str) -> Result<Vec<u8>> {
+        if let Some(c) = s.chars().find(|c| !self.allows(*c)) {
+            return Err(Error::with_message(
+                ErrorType::BadParameter,
+                format!("Character {c:?} cannot be encoded in a {self:?} string"),
+            ));
+        }
+
+        Ok(match self {
+            Self::Teletex => s.chars().map(|c| c as u8).collect(),
+            Self::Universal => s.chars().flat_map(|c| (c as u32).to_be_bytes()).collect(),
+            Self::Bmp => s.chars().flat_map(|c| (c as u16).to_be_bytes()).collect(),
+            _ => s.as_bytes().to_vec(),
+        })
+    }
+
+    fn decode(self, contents: &[u8]) -> Result<String> {
+        let s = match self {
+            Self::Teletex => contents.iter().map(|b| char::from(*b)).collect(),
+            Self::Universal => {
+                if contents.len() % 4 != 0 {
+                    return Err(der_error("UniversalString length not a multiple of 4"));
+                }
+                contents
+                    .chunks(4)
+                    .map(|c| {
+                        char::from_u32(u32::from_be_bytes([c[0], c[1], c[2], c[3]]))
+                            .ok_or_else(|| der_error("invalid character in UniversalString"))
+                    })
+                    .collect::<Result<String>>()?
+            }
+            Self::Bmp => {
+                if contents.len() % 2 != 0 {
+                    return Err(der_error("BMPString length not a multiple of 2"));
+                }
+                contents
+                    .chunks(2)
+                    .map(|c| {
+                        char::from_u32(u32::from(u16::from_be_bytes([c[0], c[1]])))
+                            .ok_or_else(|| der_error("invalid character in BMPString"))
+                    })
+                    .collect::<Result<String>>()?
+            }
+            _ => core::str::from_utf8(contents)
+                .map_err(|_| der_error("string is not valid UTF-8"))?
+                .to_string(),
+        };
+
+        if s.chars().all(|c| self.allows(c)) {
+            Ok(s)
+        } else {
+            Err(der_error(
+                "string contains characters not allowed by its type",
+            ))
+        }
+    }
+}
+
+/// A calendar time in UTC, as carried by UTCTime and GeneralizedTime
+///
+/// Fractional seconds are not supported.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Asn1Time {
+    /// The year (0 to 9999)
+    pub year: u16,
+    /// The month (1 to 12)
+    pub month: u8,
+    /// The day of the month (1 to 31)
+    pub day: u8,
+    /// The hour (0 to 23)
+    pub hour: u8,
+    /// The minute (0 to 59)
+    pub minute: u8,
+    /// The second (0 to 59)
+    pub second: u8,
+}
+
+fn days_in_month(year: u16, month: u8) -> u8 {
+    match month {
+        4 | 6 | 9 | 11 => 30,
+        2 => {
+            let leap = (year % 4 == 0 && year % 100 != 0) || year % 400 == 0;
+            if leap { 29 } else { 28 }
+        }
+        _ => 31,
+    }
+}
+
+impl Asn1Time {
+    /// Create a new time, checking that the fields are in range
+    pub fn new(year: u16, month: u8, day: u8, hour: u8, minute: u8, second: u8) -> Result<Self> {
+        if year > 9999
+            || !(1..=12).contains(&month)
+            || day == 0
+            || day > days_in_month(year, month)
+            || hour > 23
+            || minute > 59
+            || second > 59
+        {
+            return Err(Error::bad_parameter("Invalid calendar time"));
+        }
+
+        Ok(Self {
+            year,
+            month,
+            day,
+            hour,
+            minute,
+            second,
+        })
+    }
+
+    /// Convert a POSIX timestamp (seconds since 1970-01-01T00:00:00Z)
+    pub fn from_unix_time(secs: u64) -> Result<Self> {
+        let days = secs / 86400;
+        let rem = secs % 86400;
+
+        // Civil-from-days, see http://howardhinnant.github.io/date_algorithms.html
+        let z = days as i64 + 719468;
+        let era = z / 146097;
+        let doe = z - era * 146097;
+        let yoe = (doe - doe / 1460 + doe / 36524 - doe / 146096) / 365;
+        let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+        let mp = (5 * doy + 2) / 153;
+        let day = (doy - (153 * mp + 2) / 5 + 1) as u8;
+        let month = if mp < 10 { mp + 3 } else { mp - 9 } as u8;
+        let year = yoe + era * 400 + i64::from(month <= 2);
+
+        let year = u16::try_from(year).map_err(|_| Error::bad_parameter("Time out of range"))?;
+
+        Self::new(
+            year,
+            month,
+            day,
+            (rem / 3600) as u8,
+            ((rem / 60) % 60) as u8,
+            (rem % 60) as u8,
+        )
+    }
+
+    /// Return the POSIX timestamp of this time
+    ///
+    /// Returns an error for times before 1970
+    pub fn to_unix_time(&self) -> Result<u64> {
+        let y = i64::from(self.year) - i64::from(self.month <= 2);
+        let era = y.div_euclid(400);
+        let yoe = y - era * 400;
+        let m = i64::from(self.month);
+        let mp = if m > 2 { m - 3 } else { m + 9 };
+        let doy = (153 * mp + 2) / 5 + i64::from(self.day) - 1;
+        let doe = yoe * 365 + yoe / 4 - yoe / 100 + doy;
+        let days = era * 146097 + doe - 719468;
+
+        let secs = days * 86400
+            + i64::from(self.hour) * 3600
+            + i64::from(self.minute) * 60
+            + i64::from(self.second);
+
+        u64::try_from(secs).map_err(|_| Error::bad_parameter("Time is before 1970"))
+    }
+
+    fn to_utc_time_string(self) -> Result<String> {
+        if !(1950..2050).contains(&self.year) {
+            return Err(Error::bad_parameter(
+                "UTCTime can only represent years 1950 through 2049",
+            ));
+        }
+        Ok(format!(
+            "{:02}{:02}{:02}{:02}{:02}{:02}Z",
+            self.year % 100,
+            self.month,
+            self.day,
+            self.hour,
+            self.minute,
+            self.second
+        ))
+    }
+
+    fn to_generalized_time_string(self) -> String {
+        format!(
+            "{:04}{:02}{:02}{:02}{:02}{:02}Z",
+            self.year, self.month, self.day, self.hour, self.minute, self.second
+        )
+    }
+
+    fn parse(contents: &[u8], generalized: bool) -> Result<Self> {
+        let year_digits = if generalized { 4 } else { 2 };
+
+        // DER requires seconds to be present and the time to be in Zulu
+        if contents.len() != year_digits + 11 || contents.last() != Some(&b'Z') {
+            return Err(der_error("time must be of the form YYMMDDHHMMSSZ"));
+        }
+
+        let digits = &contents[..contents.len() - 1];
+        if !digits.iter().all(u8::is_ascii_digit) {
+            return Err(der_error("time contains a non-digit"));
+        }
+
+        let num = |s: &[u8]| s.iter().fold(0u16, |acc, d| acc * 10 + u16::from(d - b'0'));
+
+        let mut year = num(&digits[..year_digits]);
+        if !generalized {
+            year += if year >= 50 { 1900 } else { 2000 };
+        }
+
+        let rest = &digits[year_digits..];
+        let field = |i: usize| num(&rest[2 * i..2 * i + 2]) as u8;
+
+        Self::new(year, field(0), field(1), field(2), field(3), field(4))
+            .map_err(|_| der_error("time field out of range"))
+    }
+}
+
+impl core::fmt::Display for Asn1Time {
+    fn fmt(&self, f: &mut core::fmt::Formatter) -> core::fmt::Result {
+        write!(
+            f,
+            "{:04}-{:02}-{:02}T{:02}:{:02}:{:02}Z",
+            self.year, self.month, self.day, self.hour, self.minute, self.second
+        )
+    }
+}
+
+fn encode_oid_arcs(dotted: &str) -> Result<Vec<u8>> {
+    let arcs = dotted
+        .split('.')
+        .map(|a| {
+            if a.is_empty() || !a.bytes().all(|b| b.is_ascii_digit()) {
+                return Err(Error::bad_parameter("Invalid OID string"));
+            }
+            a.parse::<u64>()
+                .map_err(|_| Error::bad_parameter("OID arc too large"))
+        })
+        .collect::<Result<Vec<u64>>>()?;
+
+    if arcs.len() < 2 || arcs[0] > 2 || (arcs[0] < 2 && arcs[1] >= 40) {
+        return Err(Error::bad_parameter("Invalid OID string"));
+    }
+
+    let first = arcs[0]
+        .checked_mul(40)
+        .and_then(|v| v.checked_add(arcs[1]))
+        .ok_or_else(|| Error::bad_parameter("OID arc too large"))?;
+
+    let mut out = Vec::new();
+    push_base128(&mut out, first);
+    for arc in &arcs[2..] {
+        push_base128(&mut out, *arc);
+    }
+    Ok(out)
+}
+
+fn decode_oid_arcs(contents: &[u8]) -> Result<String> {
+    if contents.is_empty() {
+        return Err(der_error("empty OBJECT IDENTIFIER"));
+    }
+    if contents[contents.len() - 1] & 0x80 != 0 {
+        return Err(der_error("truncated OBJECT IDENTIFIER"));
+    }
+
+    let mut arcs = Vec::new();
+    let mut cur = 0u64;
+    let mut start = true;
+    for b in contents {
+        if start && *b == 0x80 {
+            return Err(der_error("non-minimal OBJECT IDENTIFIER arc"));
+        }
+        if cur >> 57 != 0 {
+            return Err(der_error("OBJECT IDENTIFIER arc too large"));
+        }
+        cur = (cur << 7) | u64::from(b & 0x7F);
+        start = b & 0x80 == 0;
+        if start {
+            arcs.push(cur);
+            cur = 0;
+        }
+    }
+
+    let (a0, a1) = match arcs[0] {
+        v if v < 40 => (0, v),
+        v if v < 80 => (1, v - 40),
+        v => (2, v - 80),
+    };
+
+    let mut s = format!("{a0}.{a1}");
+    for arc in &arcs[1..] {
+        s.push_str(&format!(".{arc}"));
+    }
+    Ok(s)
+}
+
+fn i64_to_integer_contents(v: i64) -> Vec<u8> {
+    let bytes = v.to_be_bytes();
+    let mut skip = 0;
+    while skip < 7 {
+        let redundant = (bytes[skip] == 0x00 && bytes[skip + 1] & 0x80 == 0)
+            || (bytes[skip] == 0xFF && bytes[skip + 1] & 0x80 != 0);
+        if !redundant {
+            break;
+        }
+        skip += 1;
+    }
+    bytes[skip..].to_vec()
+}
+
+/// A single decoded ASN.1 object
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct Asn1Object<'a> {
+    /// The tag of the object
+    pub tag: Asn1Tag,
+    /// The contents octets of the object
+    pub contents: &'a [u8],
+    /// The complete encoding of the object, including the tag and length
+    pub encoding: &'a [u8],
+}
+
+impl<'a> Asn1Object<'a> {
+    fn check_primitive(&self) -> Result<()> {
+        if self.tag.constructed {
+            Err(der_error("constructed encoding of a primitive type"))
+        } else {
+            Ok(())
+        }
+    }
+
+    /// Return a decoder over the contents of a constructed object
+    ///
+    /// The members of a SET must be in DER sorted order
+    pub fn decoder(&self) -> Result<DerDecoder<'a>> {
+        if !self.tag.constructed {
+            return Err(der_error("expected a constructed object"));
+        }
+        if self.tag == Asn1Tag::SET {
+            check_set_order(self.contents)?;
+        }
+        Ok(DerDecoder::new(self.contents))
+    }
+
+    /// Interpret the contents as a BOOLEAN
+    pub fn as_bool(&self) -> Result<bool> {
+        self.check_primitive()?;
+        match self.contents {
+            [0x00] => Ok(false),
+            [0xFF] => Ok(true),
+            _ => Err(der_error("BOOLEAN must be a single 0x00 or 0xFF byte")),
+        }
+    }
+
+    /// Interpret the contents as a NULL
+    pub fn as_null(&self) -> Result<()> {
+        self.check_primitive()?;
+        if self.contents.is_empty() {
+            Ok(())
+        } else {
+            Err(der_error("NULL must be empty"))
+        }
+    }
+
+    /// Interpret the contents as an INTEGER, returning the minimal
+    /// big-endian two's complement encoding
+    pub fn as_integer_bytes(&self) -> Result<&'a [u8]> {
+        self.check_primitive()?;
+        let c = self.contents;
+        if c.is_empty() {
+            return Err(der_error("empty INTEGER"));
+        }
+        if c.len() > 1 && ((c[0] == 0x00 && c[1] & 0x80 == 0) || (c[0] == 0xFF && c[1] & 0x80 != 0))
+        {
+            return Err(der_error("non-minimal INTEGER encoding"));
+        }
+        Ok(c)
+    }
+
+    /// Interpret the contents as an INTEGER
+    pub fn as_integer(&self) -> Result<MPI> {
+        MPI::new_from_signed_bytes(self.as_integer_bytes()?)
+    }
+
+    /// Interpret the contents as an INTEGER which fits in an i64
+    pub fn as_i64(&self) -> Result<i64> {
+        let c = self.as_integer_bytes()?;
+        if c.len() > 8 {
+            return Err(der_error("INTEGER too large"));
+        }
+        let fill = if c[0] & 0x80 != 0 { 0xFF } else { 0x00 };
+        let mut bytes = [fill; 8];
+        bytes[8 - c.len()..].copy_from_slice(c);
+        Ok(i64::from_be_bytes(bytes))
+    }
+
+    /// Interpret the contents as a non-negative INTEGER which fits in a u64
+    pub fn as_u64(&self) -> Result<u64> {
+        let c = self.as_integer_bytes()?;
+        if c[0] & 0x80 != 0 {
+            return Err(der_error("INTEGER is negative"));
+        }
+        let c = if c[0] == 0 && c.len() > 1 { &c[1..] } else { c };
+        if c.len() > 8 {
+            return Err(der_error("INTEGER too large"));
+        }
+        let mut bytes = [0u8; 8];
+        bytes[8 - c.len()..].copy_from_slice(c);
+        Ok(u64::from_be_bytes(bytes))
+    }
+
+    /// Interpret the contents as an OCTET STRING
+    pub fn as_octet_string(&self) -> Result<&'a [u8]> {
+        self.check_primitive()?;
+        Ok(self.contents)
+    }
+
+    /// Interpret the contents as a BIT STRING, returning the bytes and the
+    /// number of unused bits in the final byte
+    pub fn as_bit_string(&self) -> Result<(&'a [u8], u8)> {
+        self.check_primitive()?;
+        let (unused, bits) = self
+            .contents
+            .split_first()
+            .ok_or_else(|| der_error("empty BIT STRING"))?;
+
+        if *unused > 7 || (bits.is_empty() && *unused != 0) {
+            return Err(der_error("invalid BIT STRING unused bits count"));
+        }
+        if let Some(last) = bits.last() {
+            if last & ((1u8 << unused) - 1) != 0 {
+                return Err(der_error("BIT STRING unused bits are not zero"));
+            }
+        }
+        Ok((bits, *unused))
+    }
+
+    /// Interpret the contents as an OBJECT IDENTIFIER, in dotted decimal form
+    pub fn as_oid_string(&self) -> Result<String> {
+        self.check_primitive()?;
+        decode_oid_arcs(self.contents)
+    }
+
+    /// Interpret the contents as an OBJECT IDENTIFIER
+    ///
+    /// This requires Botan 3.8 or later; with older versions an error of type
+    /// [`ErrorType::NotImplemented`](crate::ErrorType::NotImplemented) is returned
+    pub fn as_oid(&self) -> Result<OID> {
+        OID::from_str(&self.as_oid_string()?)
+    }
+
+    /// Interpret the contents as a string of the given type
+    pub fn as_string(&self, typ: Asn1StringType) -> Result<String> {
+        self.check_primitive()?;
+        typ.decode(self.contents)
+    }
+
+    /// Interpret the contents as a UTCTime or GeneralizedTime, depending on the tag
+    pub fn as_time(&self) -> Result<Asn1Time> {
+        self.check_primitive()?;
+        match self.tag.number {
+            23 => Asn1Time::parse(self.contents, false),
+            24 => Asn1Time::parse(self.contents, true),
+            _ => Err(der_error("expected a UTCTime or GeneralizedTime")),
+        }
+    }
+}
+
+/// A strict DER decoder
+///
+/// Reads a series of ASN.1 objects from a buffer. Encodings which are
+/// valid BER but not canonical DER (indefinite lengths, non-minimal length
+/// or integer encodings, constructed strings, unsorted SETs, and so on)
+/// are rejected with an error of type
+/// [`ErrorType::InvalidInput`](crate::ErrorType::InvalidInput).
+///
+/// # Examples
+///
+/// ```
+/// let der = [0x30, 0x06, 0x02, 0x01, 0x05, 0x01, 0x01, 0xFF];
+/// let mut dec = botan::DerDecoder::new(&der);
+/// let mut seq = dec.start_sequence().unwrap();
+/// assert_eq!(seq.decode_u64().unwrap(), 5);
+/// assert_eq!(seq.decode_bool().unwrap(), true);
+/// seq.verify_end().unwrap();
+/// dec.verify_end().unwrap();
+/// ```
+#[derive(Clone, Debug)]
+pub struct DerDecoder<'a> {
+    data: &'a [u8],
+    pos: usize,
+}
+
+impl<'a> DerDecoder<'a> {
+    /// Create a decoder reading from the given buffer
+    #[must_use]
+    pub fn new(data: &'a [u8]) -> Self {
+        Self { data, pos: 0 }
+    }
+
+    /// Return true if there are more objects to read
+    #[must_use]
+    pub fn more_items(&self) -> bool {
+        self.pos < self.data.len()
+    }
+
+    /// Return an error if there are more objects to read
+    pub fn verify_end(&self) -> Result<()> {
+        if self.more_items() {
+            Err(der_error("unexpected trailing data"))
+        } else {
+            Ok(())
+        }
+    }
+
+    /// Return the tag of the next object without consuming it
+    pub fn peek_tag(&self) -> Result<Option<Asn1Tag>> {
+        if !self.more_items() {
+            return Ok(None);
+        }
+        let (tag, _) = parse_tag(&self.data[self.pos..])?;
+        Ok(Some(tag))
+    }
+
+    /// Read the next object
+    pub fn read_object(&mut self) -> Result<Asn1Object<'a>> {
+        let (obj, len) = parse_object(&self.data[self.pos..])?;
+        self.pos += len;
+        Ok(obj)
+    }
+
+    /// Read the next object, which must have the given tag
+    pub fn read_tagged(&mut self, tag: Asn1Tag) -> Result<Asn1Object<'a>> {
+        let save = self.pos;
+        let obj = self.read_object()?;
+        if obj.tag != tag {
+            self.pos = save;
+            return Err(der_error(&format!(
+                "expected {} but found {}",
+                tag.name(),
+                obj.tag.name()
+            )));
+        }
+        Ok(obj)
+    }
+
+    /// Read the next object if it has the given tag
+    pub fn read_optional(&mut self, tag: Asn1Tag) -> Result<Option<Asn1Object<'a>>> {
+        if self.peek_tag()? == Some(tag) {
+            Ok(Some(self.read_object()?))
+        } else {
+            Ok(None)
+        }
+    }
+
+    /// Read a constructed object with the given tag, returning a decoder over its contents
+    pub fn start_constructed(&mut self, tag: Asn1Tag) -> Result<DerDecoder<'a>> {
+        self.read_tagged(tag)?.decoder()
+    }
+
+    /// Read a SEQUENCE, returning a decoder over its contents
+    pub fn start_sequence(&mut self) -> Result<DerDecoder<'a>> {
+        self.start_constructed(Asn1Tag::SEQUENCE)
+    }
+
+    /// Read a SET, returning a decoder over its contents
+    ///
+    /// The members of the SET must be in DER sorted order
+    pub fn start_set(&mut self) -> Result<DerDecoder<'a>> {
+        self.start_constructed(Asn1Tag::SET)
+    }
+
+    /// Read an explicitly tagged `[number]` object, returning a decoder over its contents
+    pub fn start_explicit(&mut self, number: u32) -> Result<DerDecoder<'a>> {
+        self.start_constructed(Asn1Tag::context_specific(number, true))
+    }
+
+    /// Read an explicitly tagged `[number]` object if it is present
+    pub fn start_optional_explicit(&mut self, number: u32) -> Result<Option<DerDecoder<'a>>> {
+        match self.read_optional(Asn1Tag::context_specific(number, true))? {
+            Some(obj) => Ok(Some(obj.decoder()?)),
+            None => Ok(None),
+        }
+    }
+
+    /// Decode a BOOLEAN
+    pub fn decode_bool(&mut self) -> Result<bool> {
+        self.read_tagged(Asn1Tag::BOOLEAN)?.as_bool()
+    }
+
+    /// Decode a NULL
+    pub fn decode_null(&mut self) -> Result<()> {
+        self.read_tagged(Asn1Tag::NULL)?.as_null()
+    }
+
+    /// Decode an INTEGER
+    pub fn decode_integer(&mut self) -> Result<MPI> {
+        self.read_tagged(Asn1Tag::INTEGER)?.as_integer()
+    }
+
+    /// Decode an INTEGER which must fit in an i64
+    pub fn decode_i64(&mut self) -> Result<i64> {
+        self.read_tagged(Asn1Tag::INTEGER)?.as_i64()
+    }
+
+    /// Decode a non-negative INTEGER which must fit in a u64
+    pub fn decode_u64(&mut self) -> Result<u64> {
+        self.read_tagged(Asn1Tag::INTEGER)?.as_u64()
+    }
+
+    /// Decode an ENUMERATED value
+    pub fn decode_enumerated(&mut self) -> Result<u64> {
+        self.read_tagged(Asn1Tag::ENUMERATED)?.as_u64()
+    }
+
+    /// Decode an OCTET STRING
+    pub fn decode_octet_string(&mut self) -> Result<Vec<u8>> {
+        Ok(self
+            .read_tagged(Asn1Tag::OCTET_STRING)?
+            .as_octet_string()?
+            .to_vec())
+    }
+
+    /// Decode a BIT STRING, returning the bytes and the number of unused bits
+    pub fn decode_bit_string(&mut self) -> Result<(Vec<u8>, u8)> {
+        let (bits, unused) = self.read_tagged(Asn1Tag::BIT_STRING)?.as_bit_string()?;
+        Ok((bits.to_vec(), unused))
+    }
+
+    /// Decode an OBJECT IDENTIFIER
+    ///
+    /// This requires Botan 3.8 or later; with older versions an error of type
+    /// [`ErrorType::NotImplemented`](crate::ErrorType::NotImplemented) is returned
+    pub fn decode_oid(&mut self) -> Result<OID> {
+        self.read_tagged(Asn1Tag::OBJECT_IDENTIFIER)?.as_oid()
+    }
+
+    /// Decode an OBJECT IDENTIFIER, returned in dotted decimal form
+    pub fn decode_oid_string(&mut self) -> Result<String> {
+        self.read_tagged(Asn1Tag::OBJECT_IDENTIFIER)?
+            .as_oid_string()
+    }
+
+    /// Decode any of the supported string types
+    pub fn decode_string(&mut self) -> Result<(Asn1StringType, String)> {
+        let obj = self.read_object()?;
+        let typ = Asn1StringType::from_tag(obj.tag)
+            .ok_or_else(|| der_error(&format!("expected a string but found {}", obj.tag.name())))?;
+        Ok((typ, obj.as_string(typ)?))
+    }
+
+    /// Decode a UTCTime or GeneralizedTime
+    pub fn decode_time(&mut self) -> Result<Asn1Time> {
+        self.read_object()?.as_time()
+    }
+}
+
+fn check_set_order(contents: &[u8]) -> Result<()> {
+    let mut dec = DerDecoder::new(contents);
+    let mut prev: Option<&[u8]> = None;
+    while dec.more_items() {
+        let obj = dec.read_object()?;
+        if prev.is_some_and(|prev| prev > obj.encoding) {
+            return Err(der_error("SET members are not in sorted order"));
+        }
+        prev = Some(obj.encoding);
+    }
+    Ok(())
+}
+
+fn parse_tag(data: &[u8]) -> Result<(Asn1Tag, usize)> {
+    let first = *data.first().ok_or_else(|| der_error("truncated tag"))?;
+    let class = Asn1Class::from_bits(first);
+    let constructed = first & 0x20 != 0;
+
+    if first & 0x1F != 0x1F {
+        return Ok((Asn1Tag::new(class, constructed, u32::from(first & 0x1F)), 1));
+    }
+
+    let mut number = 0u32;
+    let mut i = 1;
+    loop {
+        let b = *data.get(i).ok_or_else(|| der_error("truncated tag"))?;
+        if i == 1 && b == 0x80 {
+            return Err(der_error("non-minimal tag encoding"));
+        }
+        if number >> 25 != 0 {
+            return Err(der_error("tag number too large"));
+        }
+        number = (number << 7) | u32::from(b & 0x7F);
+        i += 1;
+        if b & 0x80 == 0 {
+            break;
+        }
+    }
+
+    if number < 31 {
+        return Err(der_error("non-minimal tag encoding"));
+    }
+
+    Ok((Asn1Tag::new(class, constructed, number), i))
+}
+
+/// Parse one object, returning it and the length of its encoding
+fn parse_object(data: &[u8]) -> Result<(Asn1Object<'_>, usize)> {
+    let (tag, tag_len) = parse_tag(data)?;
+
+    let len_byte = *data
+        .get(tag_len)
+        .ok_or_else(|| der_error("truncated length"))?;
+    let mut header_len = tag_len + 1;
+
+    let length = if len_byte & 0x80 == 0 {
+        usize::from(len_byte)
+    } else {
+        let nbytes = usize::from(len_byte & 0x7F);
+        if nbytes == 0 {
+            return Err(der_error("indefinite length encoding"));
+        }
+        if nbytes > mem::size_of::<usize>() {
+            return Err(der_error("length too large"));
+        }
+        let bytes = data
+            .get(header_len..header_len + nbytes)
+            .ok_or_else(|| der_error("truncated length"))?;
+        if bytes[0] == 0 {
+            return Err(der_error("non-minimal length encoding"));
+        }
+        let length = bytes
+            .iter()
+            .fold(0usize, |acc, b| (acc << 8) | usize::from(*b));
+        if length < 128 {
+            return Err(der_error("non-minimal length encoding"));
+        }
+        header_len += nbytes;
+        length
+    };
+
+    let end = header_len
+        .checked_add(length)
+        .filter(|end| *end <= data.len())
+        .ok_or_else(|| der_error("object length exceeds the available data"))?;
+
+    let obj = Asn1Object {
+        tag,
+        contents: &data[header_len..end],
+        encoding: &data[..end],
+    };
+    Ok((obj, end))
+}
+
+fn encode_length(out: &mut Vec<u8>, len: usize) {
+    if len < 128 {
+        out.push(len as u8);
+    } else {
+        let bytes = len.to_be_bytes();
+        let skip = bytes.iter().take_while(|b| **b == 0).count();
+        out.push(0x80 | (bytes.len() - skip) as u8);
+        out.extend_from_slice(&bytes[skip..]);
+    }
+}
+
+#[derive(Debug)]
+struct DerFrame {
+    tag: Asn1Tag,
+    contents: Vec<u8>,
+    // Offsets of each member within contents, used to sort SET members
+    members: Vec<usize>,
+}
+
+/// A DER encoder
+///
+/// Constructed types are written by calling a `start_` function, encoding
+/// the members, and then calling [`DerEncoder::end_cons`]. The members of
+/// a SET are sorted as DER requires.
+///
+/// # Examples
+///
+/// ```
+/// let mut enc = botan::DerEncoder::new();
+/// enc.start_sequence()
+///     .encode_u64(5)
+///     .encode_bool(true)
+///     .end_cons()
+///     .unwrap();
+/// assert_eq!(enc.finish().unwrap(), vec![0x30, 0x06, 0x02, 0x01, 0x05, 0x01, 0x01, 0xFF]);
+/// ```
+#[derive(Debug, Default)]
+pub struct DerEncoder {
+    output: Vec<u8>,
+    stack: Vec<DerFrame>,
+}
+
+impl DerEncoder {
+    /// Create a new encoder
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Return the encoded output
+    ///
+    /// Fails if any constructed type was started but not ended
+    pub fn finish(self) -> Result<Vec<u8>> {
+        if !self.stack.is_empty() {
+            return Err(Error::with_message(
+                ErrorType::InvalidObjectState,
+                "DerEncoder finished with unclosed constructed types".to_owned(),
+            ));
+        }
+        Ok(self.output)
+    }
+
+    fn push_encoded(&mut self, encoded: &[u8]) {
+        match self.stack.last_mut() {
+            Some(frame) => {
+                frame.members.push(frame.contents.len());
+                frame.contents.extend_from_slice(encoded);
+            }
+            None => self.output.extend_from_slice(encoded),
+        }
+    }
+
+    /// Write an object with the given tag and contents
+    pub fn encode_object(&mut self, tag: Asn1Tag, contents: &[u8]) -> &mut Self {
+        let mut encoded = Vec::with_capacity(contents.len() + 8);
+        tag.encode_into(&mut encoded);
+        encode_length(&mut encoded, contents.len());
+        encoded.extend_from_slice(contents);
+        self.push_encoded(&encoded);
+        self
+    }
+
+    /// Write a pre-encoded DER object, such as a public key, unchanged
+    ///
+    /// The input must consist of exactly one well formed DER object
+    pub fn encode_raw(&mut self, der: &[u8]) -> Result<&mut Self> {
+        let (_, len) = parse_object(der)?;
+        if len != der.len() {
+            return Err(der_error("unexpected trailing data"));
+        }
+        self.push_encoded(der);
+        Ok(self)
+    }
+
+    /// Start a constructed type with the given tag
+    pub fn start_constructed(&mut self, tag: Asn1Tag) -> Result<&mut Self> {
+        if !tag.constructed {
+            return Err(Error::bad_parameter(
+                "Constructed type must use a constructed tag",
+            ));
+        }
+        Ok(self.push_frame(tag))
+    }
+
+    fn push_frame(&mut self, tag: Asn1Tag) -> &mut Self {
+        self.stack.push(DerFrame {
+            tag,
+            contents: Vec::new(),
+            members: Vec::new(),
+        });
+        self
+    }
+
+    /// Start a SEQUENCE
+    pub fn start_sequence(&mut self) -> &mut Self {
+        self.push_frame(Asn1Tag::SEQUENCE)
+    }
+
+    /// Start a SET; the members are sorted when the set is ended
+    pub fn start_set(&mut self) -> &mut Self {
+        self.push_frame(Asn1Tag::SET)
+    }
+
+    /// Start an explicitly tagged `[number]` object
+    pub fn start_explicit(&mut self, number: u32) -> &mut Self {
+        self.push_frame(Asn1Tag::context_specific(number, true))
+    }
+
+    /// End the most recently started constructed type
+    pub fn end_cons(&mut self) -> Result<&mut Self> {
+        let frame = self.stack.pop().ok_or_else(|| {
+            Error::with_message(
+                ErrorType::InvalidObjectState,
+                "DerEncoder::end_cons called without a matching start".to_owned(),
+            )
+        })?;
+
+        let contents = if frame.tag == Asn1Tag::SET {
+            let mut members = Vec::with_capacity(frame.members.len());
+            for (i, start) in frame.members.iter().enumerate() {
+                let end = frame
+                    .members
+                    .get(i + 1)
+                    .copied()
+                    .unwrap_or(frame.contents.len());
+                members.push(&frame.contents[*start..end]);
+            }
+            members.sort();
+            members.concat()
+        } else {
+            frame.contents
+        };
+
+        Ok(self.encode_object(frame.tag, &contents))
+    }
+
+    /// Encode a BOOLEAN
+    pub fn encode_bool(&mut self, v: bool) -> &mut Self {
+        self.encode_object(Asn1Tag::BOOLEAN, &[if v { 0xFF } else { 0x00 }])
+    }
+
+    /// Encode a NULL
+    pub fn encode_null(&mut self) -> &mut Self {
+        self.encode_object(Asn1Tag::NULL, &[])
+    }
+
+    /// Encode an INTEGER
+    pub fn encode_integer(&mut self, v: &MPI) -> Result<&mut Self> {
+        let contents = v.to_signed_bin()?;
+        Ok(self.encode_object(Asn1Tag::INTEGER, &contents))
+    }
+
+    /// Encode an INTEGER from an i64
+    pub fn encode_i64(&mut self, v: i64) -> &mut Self {
+        self.encode_object(Asn1Tag::INTEGER, &i64_to_integer_contents(v))
+    }
+
+    /// Encode an INTEGER from a u64
+    pub fn encode_u64(&mut self, v: u64) -> &mut Self {
+        let mut contents = v.to_be_bytes().to_vec();
+        let skip = contents.iter().take_while(|b| **b == 0).count().min(7);
+        contents.drain(..skip);
+        if contents[0] & 0x80 != 0 {
+            contents.insert(0, 0);
+        }
+        self.encode_object(Asn1Tag::INTEGER, &contents)
+    }
+
+    /// Encode an ENUMERATED value
+    pub fn encode_enumerated(&mut self, v: u32) -> &mut Self {
+        self.encode_object(Asn1Tag::ENUMERATED, &i64_to_integer_contents(i64::from(v)))
+    }
+
+    /// Encode an OCTET STRING
+    pub fn encode_octet_string(&mut self, v: &[u8]) -> &mut Self {
+        self.encode_object(Asn1Tag::OCTET_STRING, v)
+    }
+
+    /// Encode a BIT STRING with the given number of unused bits in the final byte
+    pub fn encode_bit_string(&mut self, v: &[u8], unused_bits: u8) -> Result<&mut Self> {
+        if unused_bits > 7 || (v.is_empty() && unused_bits != 0) {
+            return Err(Error::bad_parameter("Invalid BIT STRING unused bits count"));
+        }
+        if let Some(last) = v.last() {
+            if last & ((1u8 << unused_bits) - 1) != 0 {
+                return Err(Error::bad_parameter("BIT STRING unused bits must be zero"));
+            }
+        }
+        let mut contents = Vec::with_capacity(v.len() + 1);
+        contents.push(unused_bits);
+        contents.extend_from_slice(v);
+        Ok(self.encode_object(Asn1Tag::BIT_STRING, &contents))
+    }
+
+    /// Encode an OBJECT IDENTIFIER
+    pub fn encode_oid(&mut self, oid: &OID) -> Result<&mut Self> {
+        self.encode_oid_str(&oid.as_string()?)
+    }
+
+    /// Encode an OBJECT IDENTIFIER given in dotted decimal form
+    pub fn encode_oid_str(&mut self, oid: &str) -> Result<&mut Self> {
+        let contents = encode_oid_arcs(oid)?;
+        Ok(self.encode_object(Asn1Tag::OBJECT_IDENTIFIER, &contents))
+    }
+
+    /// Encode a string of the given type
+    ///
+    /// Fails if the string contains characters the type cannot represent
+    pub fn encode_string(&mut self, typ: Asn1StringType, v: &str) -> Result<&mut Self> {
+        let contents = typ.encode(v)?;
+        Ok(self.encode_object(typ.tag(), &contents))
+    }
+
+    /// Encode a UTCTime; only years 1950 through 2049 can be represented
+    pub fn encode_utc_time(&mut self, t: &Asn1Time) -> Result<&mut Self> {
+        let s = t.to_utc_time_string()?;
+        Ok(self.encode_object(Asn1Tag::UTC_TIME, s.as_bytes()))
+    }
+
+    /// Encode a GeneralizedTime
+    pub fn encode_generalized_time(&mut self, t: &Asn1Time) -> &mut Self {
+        let s = t.to_generalized_time_string();
+        self.encode_object(Asn1Tag::GENERALIZED_TIME, s.as_bytes())
+    }
+
+    /// Encode a time as RFC 5280 requires: UTCTime for years through 2049,
+    /// and GeneralizedTime for later years
+    pub fn encode_time(&mut self, t: &Asn1Time) -> Result<&mut Self> {
+        if t.year < 2050 && t.year >= 1950 {
+            self.encode_utc_time(t)
+        } else {
+            Ok(self.encode_generalized_time(t))
+        }
+    }
+}
+
+/// Return a human readable dump of some DER data
+///
+/// The format is similar to the output of `openssl asn1parse`: each line
+/// gives the offset, depth, header length, and content length of an object,
+/// followed by its tag and (for primitive types) a rendering of its value.
+/// The input is parsed strictly, and an error is returned if it is not
+/// valid DER, or if objects are nested more than 64 levels deep.
+///
+/// # Examples
+///
+/// ```
+/// let dump = botan::der_dump(&[0x30, 0x03, 0x02, 0x01, 0x05]).unwrap();
+/// assert_eq!(dump, "    0:d=0  hl=2 l=   3 cons: SEQUENCE\n    2:d=1  hl=2 l=   1 prim:  INTEGER           :05\n");
+/// ```
+pub fn der_dump(der: &[u8]) -> Result<String> {
+    let mut out = String::new();
+    dump_objects(&mut out, der, 0, 0)?;
+    Ok(out)
+}
+
+fn dump_objects(out: &mut String, data: &[u8], offset: usize, depth: usize) -> Result<()> {
+    // Bounds the recursion, which untrusted input could otherwise exhaust the stack with
+    const MAX_DEPTH: usize = 64;
+
+    if depth > MAX_DEPTH {
+        return Err(der_error("objects are nested too deeply"));
+    }
+
+    let mut dec = DerDecoder::new(data);
+    while dec.more_items() {
+        let start = dec.pos;
+        let obj = dec.read_object()?;
+        let header_len = obj.encoding.len() - obj.contents.len();
+
+        let line = format!(
+            "{:5}:d={:<2} hl={} l={:4} {}: {}",
+            offset + start,
+            depth,
+            header_len,
+            obj.contents.len(),
+            if obj.tag.constructed { "cons" } else { "prim" },
+            if obj.tag.constructed { "" } else { " " },
+        );
+        out.push_str(&line);
+
+        if obj.tag.constructed {
+            out.push_str(&obj.tag.name());
+            out.push('\n');
+            obj.decoder()?;
+            dump_objects(out, obj.contents, offset + start + header_len, depth + 1)?;
+        } else {
+            match dump_value(&obj)? {
+                Some(value) => out.push_str(&format!("{:<18}:{}", obj.tag.name(), value)),
+                None => out.push_str(&obj.tag.name()),
+            }
+            out.push('\n');
+        }
+    }
+    Ok(())
+}
+
+fn dump_value(obj: &Asn1Object) -> Result<Option<String>> {
+    if obj.tag.class != Asn1Class::Universal {
+        return Ok(Some(format!("[HEX DUMP]:{}", hex_upper(obj.contents))));
+    }
+
+    if let Some(typ) = Asn1StringType::from_tag(obj.tag) {
+        return Ok(Some(obj.as_string(typ)?));
+    }
+
+    let value = match obj.tag.number {
+        1 => {
+            if obj.as_bool()? {
+                "255".to_string()
+            } else {
+                "0".to_string()
+            }
+        }
+        2 | 10 => {
+            let bytes = obj.as_integer_bytes()?;
+            if bytes[0] & 0x80 != 0 {
+                // Print the magnitude of negative values, as OpenSSL does
+                let mut mag = bytes.to_vec();
+                for b in mag.iter_mut() {
+                    *b = !*b;
+                }
+                for b in mag.iter_mut().rev() {
+                    let (r, carry) = b.overflowing_add(1);
+                    *b = r;
+                    if !carry {
+                        break;
+                    }
+                }
+                let skip = mag.iter().take_while(|b| **b == 0).count();
+                format!("-{}", hex_upper(&mag[skip..]))
+            } else {
+                hex_upper(bytes)
+            }
+        }
+        3 => {
+            let (bits, unused) = obj.as_bit_string()?;
+            format!("[{unused} unused bits]{}", hex_upper(bits))
+        }
+        4 => format!("[HEX DUMP]:{}", hex_upper(obj.as_octet_string()?)),
+        5 => return obj.as_null().map(|_| None),
+        6 => {
+            let dotted = obj.as_oid_string()?;
+            // Use the name Botan knows for this OID, if any
+            match OID::from_str(&dotted).and_then(|oid| oid.as_name()) {
+                Ok(name) => name,
+                Err(_) => dotted,
+            }
+        }
+        23 | 24 => {
+            let raw = core::str::from_utf8(obj.contents).unwrap_or_default();
+            obj.as_time()?;
+            raw.to_string()
+        }
+        _ => format!("[HEX DUMP]:{}", hex_upper(obj.contents)),
+    };
+
+    Ok(Some(value))
+}
+
+fn hex_upper(bytes: &[u8]) -> String {
+    let mut s = String::with_capacity(bytes.len() * 2);
+    for b in bytes {
+        s.push_str(&format!("{b:02X}"));
+    }
+    s
+}
@@ -0,0 +1,582 @@
+use crate::utils::*;
+use botan_sys::*;
+
+use crate::{
+    BlockCipher, BlockCipherAlgorithm, Cipher, CipherAlgorithm, CipherDirection,
+    ClassicMcElieceParams, DerEncoder, DlGroup, EcGroup, EcGroupId, FrodoKemParams, HashAlgorithm,
+    HashFunction, KdfAlgorithm, MPI, MacAlgorithm, MlDsaParams, MlKemParams, MsgAuthCode,
+    PasswordHashAlgorithm, Privkey, Pubkey, PublicKeyAlgorithm, RandomNumberGenerator,
+    SlhDsaParams, StreamCipherAlgorithm, Version, XmssParams, derive_key_from_password, kdf,
+};
+
+#[cfg(feature = "std")]
+use std::sync::{Mutex, OnceLock, PoisonError};
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+/// The algorithms supported by the Botan library in use
+///
+/// Which algorithms are available depends on the modules Botan was built
+/// with and on its version, which with the `dynamic-loading` feature is only
+/// known at run time. Each list holds the algorithms that were successfully
+/// instantiated, in a fixed order; algorithms with a size or other parameter
+/// are only probed at their commonly used settings (for example `SHA-3(256)`
+/// but not `SHA-3(200)`), and modes and MACs only over the supported block
+/// ciphers and hashes.
+///
+/// Use [`capabilities`] to obtain a cached copy, or [`Capabilities::probe`]
+/// to query the library directly.
+pub struct Capabilities {
+    /// Supported hash functions
+    pub hashes: Vec<HashAlgorithm>,
+    /// Supported block ciphers
+    pub block_ciphers: Vec<BlockCipherAlgorithm>,
+    /// Supported cipher modes, AEADs and stream ciphers, with their default
+    /// parameters
+    pub ciphers: Vec<CipherAlgorithm>,
+    /// Supported message authentication codes
+    pub macs: Vec<MacAlgorithm>,
+    /// Supported key derivation functions. HMAC based KDFs are listed with
+    /// their hash function, eg [`KdfAlgorithm::Hkdf`] rather than
+    /// [`KdfAlgorithm::HkdfMac`]
+    pub kdfs: Vec<KdfAlgorithm>,
+    /// Supported password hashing schemes
+    pub password_hashes: Vec<PasswordHashAlgorithm>,
+    /// Public key algorithms for which a key could be created or loaded
+    pub public_key_algorithms: Vec<PublicKeyAlgorithm>,
+    /// Supported named elliptic curve groups
+    pub ec_groups: Vec<EcGroupId>,
+    /// Supported ML-KEM parameter sets
+    pub ml_kem: Vec<MlKemParams>,
+    /// Supported ML-DSA parameter sets
+    pub ml_dsa: Vec<MlDsaParams>,
+    /// Supported SLH-DSA parameter sets
+    ///
+    /// This requires Botan 3.8 or later; with older versions it is empty
+    pub slh_dsa: Vec<SlhDsaParams>,
+    /// Supported FrodoKEM parameter sets
+    pub frodokem: Vec<FrodoKemParams>,
+    /// Supported Classic McEliece parameter sets
+    ///
+    /// This requires Botan 3.8 or later; with older versions it is empty
+    pub classic_mceliece: Vec<ClassicMcElieceParams>,
+    /// Supported XMSS parameter sets
+    pub xmss: Vec<XmssParams>,
+}
+
+const HASHES: &[HashAlgorithm] = &[
+    HashAlgorithm::AsconHash256,
+    HashAlgorithm::Blake2b(256),
+    HashAlgorithm::Blake2b(512),
+    HashAlgorithm::Blake2s(256),
+    HashAlgorithm::Crc24,
+    HashAlgorithm::Md5,
+    HashAlgorithm::Ripemd160,
+    HashAlgorithm::Sha1,
+    HashAlgorithm::Sha224,
+    HashAlgorithm::Sha256,
+    HashAlgorithm::Sha384,
+    HashAlgorithm::Sha512,
+    HashAlgorithm::Sha512_256,
+    HashAlgorithm::Sha3(224),
+    HashAlgorithm::Sha3(256),
+    HashAlgorithm::Sha3(384),
+    HashAlgorithm::Sha3(512),
+    HashAlgorithm::Shake128(256),
+    HashAlgorithm::Shake256(512),
+    HashAlgorithm::Skein512(512),
+    HashAlgorithm::Sm3,
+    HashAlgorithm::Whirlpool,
+];
+
+const BLOCK_CIPHERS: &[BlockCipherAlgorithm] = &[
+    BlockCipherAlgorithm::Aes128,
+    BlockCipherAlgorithm::Aes192,
+    BlockCipherAlgorithm::Aes256,
+    BlockCipherAlgorithm::Aria128,
+    BlockCipherAlgorithm::Aria192,
+    BlockCipherAlgorithm::Aria256,
+    BlockCipherAlgorithm::Blowfish,
+    BlockCipherAlgorithm::Camellia128,
+    BlockCipherAlgorithm::Camellia192,
+    BlockCipherAlgorithm::Camellia256,
+    BlockCipherAlgorithm::Cast128,
+    BlockCipherAlgorithm::Des,
+    BlockCipherAlgorithm::Idea,
+    BlockCipherAlgorithm::Kuznyechik,
+    BlockCipherAlgorithm::Seed,
+    BlockCipherAlgorithm::Serpent,
+    BlockCipherAlgorithm::Shacal2,
+    BlockCipherAlgorithm::Sm4,
+    BlockCipherAlgorithm::Threefish512,
+    BlockCipherAlgorithm::TripleDes,
+    BlockCipherAlgorithm::Twofish,
+];
+
+const BLOCK_CIPHER_MODES: &[fn(BlockCipherAlgorithm) -> CipherAlgorithm] = &[
+    |c| CipherAlgorithm::Cbc(c, None),
+    |c| CipherAlgorithm::Ccm(c, None, None),
+    |c| CipherAlgorithm::Cfb(c, None),
+    |c| CipherAlgorithm::CtrBe(c, None),
+    |c| CipherAlgorithm::Eax(c, None),
+    |c| CipherAlgorithm::Gcm(c, None),
+    CipherAlgorithm::GcmSiv,
+    |c| CipherAlgorithm::Ocb(c, None),
+    CipherAlgorithm::Siv,
+    CipherAlgorithm::Xts,
+    |c| CipherAlgorithm::Stream(StreamCipherAlgorithm::Ofb(c)),
+];
+
+const OTHER_CIPHERS: &[CipherAlgorithm] = &[
+    CipherAlgorithm::AsconAead128,
+    CipherAlgorithm::ChaCha20Poly1305,
+    CipherAlgorithm::Stream(StreamCipherAlgorithm::ChaCha8),
+    CipherAlgorithm::Stream(StreamCipherAlgorithm::ChaCha12),
+    CipherAlgorithm::Stream(StreamCipherAlgorithm::ChaCha20),
+    CipherAlgorithm::Stream(StreamCipherAlgorithm::Rc4(None)),
+    CipherAlgorithm::Stream(StreamCipherAlgorithm::Salsa20),
+];
+
+const OTHER_MACS: &[MacAlgorithm] = &[
+    MacAlgorithm::Blake2b(256),
+    MacAlgorithm::Blake2b(512),
+    MacAlgorithm::Kmac128(256),
+    MacAlgorithm::Kmac256(512),
+    MacAlgorithm::Poly1305,
+];
+
+const HASH_KDFS: &[fn(HashAlgorithm) -> KdfAlgorithm] = &[
+    KdfAlgorithm::Hkdf,
+    KdfAlgorithm::HkdfExpand,
+    KdfAlgorithm::HkdfExtract,
+    KdfAlgorithm::Kdf1,
+    KdfAlgorithm::Kdf1Iso18033,
+    KdfAlgorithm::Kdf2,
+    KdfAlgorithm::Sp80056aHash,
+    KdfAlgorithm::Sp80056cHash,
+    |h| KdfAlgorithm::Sp800108Counter(MacAlgorithm::Hmac(h), None, None),
+    |h| KdfAlgorithm::Sp800108Feedback(MacAlgorithm::Hmac(h), None, None),
+    |h| KdfAlgorithm::Sp800108Pipeline(MacAlgorithm::Hmac(h), None, None),
+    KdfAlgorithm::Tls12Prf,
+];
+
+// Cheapest valid (param1, param2, param3) for each password hash
+const PASSWORD_HASHES: &[(PasswordHashAlgorithm, [usize; 3])] = &[
+    (PasswordHashAlgorithm::Argon2d, [8, 1, 1]),
+    (PasswordHashAlgorithm::Argon2i, [8, 1, 1]),
+    (PasswordHashAlgorithm::Argon2id, [8, 1, 1]),
+    (PasswordHashAlgorithm::BcryptPbkdf, [1, 0, 0]),
+    (PasswordHashAlgorithm::Scrypt, [16, 1, 1]),
+];
+
+// These are probed with 1024 iterations, which every one of them accepts
+const HASH_PASSWORD_HASHES: &[fn(HashAlgorithm) -> PasswordHashAlgorithm] = &[
+    PasswordHashAlgorithm::OpenPgpS2k,
+    PasswordHashAlgorithm::Pbkdf2,
+    |h| PasswordHashAlgorithm::Pkcs12Kdf(h, 1),
+];
+
+const EC_GROUPS: &[EcGroupId] = &[
+    EcGroupId::Secp256r1,
+    EcGroupId::Secp384r1,
+    EcGroupId::Secp521r1,
+    EcGroupId::Secp256k1,
+    EcGroupId::Secp224r1,
+    EcGroupId::Secp192r1,
+    EcGroupId::Brainpool256r1,
+    EcGroupId::Brainpool384r1,
+    EcGroupId::Brainpool512r1,
+    EcGroupId::Frp256v1,
+    EcGroupId::Numsp512d1,
+    EcGroupId::Sm2p256v1,
+];
+
+impl Capabilities {
+    /// Probe the Botan library in use for the algorithms it supports
+    ///
+    /// This instantiates every candidate algorithm and loads (or, where
+    /// that is not possible, creates) a key for each public key algorithm
+    /// and parameter set, which takes a noticeable amount of time; prefer
+    /// [`capabilities`], which caches the result, where it is available.
+    ///
+    /// Fails only if the library itself is not usable (eg it could not be
+    /// loaded).
+    pub fn probe() -> Result<Capabilities> {
+        Version::current()?;
+
+        let hashes = supported(HASHES.iter().cloned(), |h| HashFunction::new(h).is_ok());
+
+        let block_ciphers = supported(BLOCK_CIPHERS.iter().cloned(), |c| {
+            BlockCipher::new(c).is_ok()
+        });
+
+        let ciphers = supported(
+            block_ciphers
+                .iter()
+                .flat_map(|c| BLOCK_CIPHER_MODES.iter().map(|mode| mode(c.clone())))
+                .chain(OTHER_CIPHERS.iter().cloned()),
+            |c| Cipher::new(c, CipherDirection::Encrypt).is_ok(),
+        );
+
+        let macs = supported(
+            hashes
+                .iter()
+                .cloned()
+                .map(MacAlgorithm::Hmac)
+                .chain(block_ciphers.iter().cloned().map(MacAlgorithm::Cmac))
+                .chain(block_ciphers.iter().cloned().map(MacAlgorithm::Gmac))
+                .chain(OTHER_MACS.iter().cloned()),
+            |m| MsgAuthCode::new(m).is_ok(),
+        );
+
+        let kdfs = supported(
+            hashes
+                .iter()
+                .flat_map(|h| HASH_KDFS.iter().map(|kdf| kdf(h.clone())))
+                .chain([KdfAlgorithm::Raw]),
+            |k| kdf(k, 1, &[0; 16], &[0; 16], &[]).is_ok(),
+        );
+
+        let password_hashes = supported(
+            PASSWORD_HASHES
+                .iter()
+                .cloned()
+                .chain(hashes.iter().flat_map(|h| {
+                    HASH_PASSWORD_HASHES
+                        .iter()
+                        .map(|pwdhash| (pwdhash(h.clone()), [1024, 0, 0]))
+                })),
+            |(p, [param1, param2, param3])| {
+                derive_key_from_password(p, 16, "", &[0; 16], *param1, *param2, *param3).is_ok()
+            },
+        )
+        .into_iter()
+        .map(|(p, _)| p)
+        .collect();
+
+        let mut rng = RandomNumberGenerator::new_system()?;
+        let mut creates = |alg: &str, params: &str| Privkey::create(alg, params, &mut rng).is_ok();
+
+        let ec_groups = supported(EC_GROUPS.iter().cloned(), |g| {
+            EcGroup::supports_named_group(&g.botan_name())
+                .unwrap_or_else(|_| creates("ECDSA", &g.botan_name()))
+        });
+
+        // Loading a public key (of all zero bytes) is much cheaper than
+        // generating a key, so keys are only generated if loading fails, for
+        // example because the library is too old to provide the loader
+        let ml_kem = supported(MlKemParams::ALL.iter().cloned(), |p| {
+            loads_raw_pubkey(
+                botan_pubkey_load_ml_kem,
+                &p.botan_name(),
+                ml_kem_pubkey_len(p),
+            ) || creates("ML-KEM", &p.botan_name())
+        });
+        let ml_dsa = supported(MlDsaParams::ALL.iter().cloned(), |p| {
+            loads_raw_pubkey(
+                botan_pubkey_load_ml_dsa,
+                &p.botan_name(),
+                ml_dsa_pubkey_len(p),
+            ) || creates("ML-DSA", &p.botan_name())
+        });
+        let frodokem = supported(FrodoKemParams::ALL.iter().cloned(), |p| {
+            loads_raw_pubkey(
+                botan_pubkey_load_frodokem,
+                &p.botan_name(),
+                frodokem_pubkey_len(p),
+            ) || creates("FrodoKEM", &p.botan_name())
+        });
+
+        // Key generation is slow for these, so instead check that a public
+        // key (of all zero bytes) can be loaded
+        let slh_dsa = supported(SlhDsaParams::ALL.iter().cloned(), |p| {
+            loads_raw_pubkey(
+                botan_pubkey_load_slh_dsa,
+                &p.botan_name(),
+                slh_dsa_pubkey_len(p),
+            )
+        });
+        let classic_mceliece = supported(ClassicMcElieceParams::ALL.iter().cloned(), |p| {
+            loads_raw_pubkey(
+                botan_pubkey_load_classic_mceliece,
+                &p.botan_name(),
+                classic_mceliece_pubkey_len(p),
+            )
+        });
+        let xmss = supported(XmssParams::ALL.iter().cloned(), |p| {
+            let (id, n, hash) = xmss_params_info(p);
+            hashes.contains(&hash) && loads_xmss_pubkey(id, n)
+        });
+
+        let ec_group = ec_groups.first().map(EcGroupId::botan_name);
+
+        let public_key_algorithms = supported(
+            [
+                PublicKeyAlgorithm::ClassicMcEliece,
+                PublicKeyAlgorithm::Dh,
+                PublicKeyAlgorithm::Ecdh,
+                PublicKeyAlgorithm::Ecdsa,
+                PublicKeyAlgorithm::Ecgdsa,
+                PublicKeyAlgorithm::Eckcdsa,
+                PublicKeyAlgorithm::Ed25519,
+                PublicKeyAlgorithm::Ed448,
+                PublicKeyAlgorithm::FrodoKem,
+                PublicKeyAlgorithm::HssLms,
+                PublicKeyAlgorithm::MlDsa,
+                PublicKeyAlgorithm::MlKem,
+                PublicKeyAlgorithm::Rsa,
+                PublicKeyAlgorithm::SlhDsa,
+                PublicKeyAlgorithm::Sm2,
+                PublicKeyAlgorithm::X25519,
+                PublicKeyAlgorithm::X448,
+                PublicKeyAlgorithm::Xmss,
+            ],
+            |alg| match alg {
+                PublicKeyAlgorithm::ClassicMcEliece => !classic_mceliece.is_empty(),
+                PublicKeyAlgorithm::Dh => {
+                    loads_dh_pubkey() || creates("DH", &DlGroup::FfdheIetf2048.botan_name())
+                }
+                PublicKeyAlgorithm::Ecdh
+                | PublicKeyAlgorithm::Ecdsa
+                | PublicKeyAlgorithm::Ecgdsa
+                | PublicKeyAlgorithm::Eckcdsa => ec_group
+                    .as_deref()
+                    .is_some_and(|group| creates(&alg.botan_name(), group)),
+                PublicKeyAlgorithm::FrodoKem => !frodokem.is_empty(),
+                PublicKeyAlgorithm::HssLms => creates("HSS-LMS", "SHA-256,HW(5,1)"),
+                PublicKeyAlgorithm::MlDsa => !ml_dsa.is_empty(),
+                PublicKeyAlgorithm::MlKem => !ml_kem.is_empty(),
+                PublicKeyAlgorithm::Rsa => loads_rsa_pubkey() || creates("RSA", "1024"),
+                PublicKeyAlgorithm::SlhDsa => !slh_dsa.is_empty(),
+                PublicKeyAlgorithm::Sm2 => {
+                    ec_groups.contains(&EcGroupId::Sm2p256v1)
+                        && creates("SM2", &EcGroupId::Sm2p256v1.botan_name())
+                }
+                PublicKeyAlgorithm::Xmss => !xmss.is_empty(),
+                _ => creates(&alg.botan_name(), ""),
+            },
+        );
+
+        Ok(Capabilities {
+            hashes,
+            block_ciphers,
+            ciphers,
+            macs,
+            kdfs,
+            password_hashes,
+            public_key_algorithms,
+            ec_groups,
+            ml_kem,
+            ml_dsa,
+            slh_dsa,
+            frodokem,
+            classic_mceliece,
+            xmss,
+        })
+    }
+}
+
+/// Return the algorithms supported by the Botan library in use
+///
+/// The library is probed (see [`Capabilities::probe`]) on the first
+/// successful call, and the same result is returned from then on.
+///
+/// This is only available with the `std` feature.
+///
+/// # Examples
+///
+/// ```
+/// let caps = botan::capabilities().unwrap();
+/// let hash = if caps.hashes.contains(&botan::HashAlgorithm::Sha3(256)) {
+///     botan::HashAlgorithm::Sha3(256)
+/// } else {
+///     botan::HashAlgorithm::Sha256
+/// };
+/// ```
+#[cfg(feature = "std")]
+pub fn capabilities() -> Result<&'static Capabilities> {
+    static CAPABILITIES: OnceLock<Capabilities> = OnceLock::new();
+
+    static PROBE_LOCK: Mutex<()> = Mutex::new(());
+
+    if let Some(caps) = CAPABILITIES.get() {
+        return Ok(caps);
+    }
+
+    // Only one thread probes at a time; any others wait for its result
+    let _guard = PROBE_LOCK.lock().unwrap_or_else(PoisonError::into_inner);
+    if let Some(caps) = CAPABILITIES.get() {
+        return Ok(caps);
+    }
+    let caps = Capabilities::probe()?;
+    Ok(CAPABILITIES.get_or_init(|| caps))
+}
+
+fn supported<T>(candidates: impl IntoIterator<Item = T>, probe: impl FnMut(&T) -> bool) -> Vec<T> {
+    candidates.into_iter().filter(probe).collect()
+}
+
+type RawPubkeyLoader =
+    unsafe extern "C" fn(*mut botan_pubkey_t, *const u8, usize, *const c_char) -> c_int;
+
+fn loads_raw_pubkey(load: RawPubkeyLoader, mode: &str, len: usize) -> bool {
+    let Ok(mode) = make_cstr(mode) else {
+        return false;
+    };
+    let key = vec![0u8; len];
+    let mut obj = ptr::null_mut();
+    let rc = unsafe { load(&mut obj, key.as_ptr(), key.len(), mode.as_ptr()) };
+    if rc != 0 {
+        return false;
+    }
+    drop(Pubkey::from_handle(obj));
+    true
+}
+
+fn loads_rsa_pubkey() -> bool {
+    // Only the form of the modulus and exponent is checked
+    (|| {
+        Pubkey::load_rsa(
+            &MPI::new_from_bytes(&[0xFF; 128])?,
+            &MPI::new_from_u32(65537)?,
+        )
+    })()
+    .is_ok()
+}
+
+fn loads_dh_pubkey() -> bool {
+    // The group is not checked to be prime when loading a public key
+    (|| {
+        let p = MPI::new_from_bytes(&[0xFF; 256])?;
+        Pubkey::load_dh(&p, &MPI::new_from_u32(2)?, &MPI::new_from_u32(4)?)
+    })()
+    .is_ok()
+}
+
+fn ml_kem_pubkey_len(params: &MlKemParams) -> usize {
+    // The k polynomials of t, of 384 bytes each, and the seed rho
+    let k = match params {
+        MlKemParams::MlKem512 => 2,
+        MlKemParams::MlKem768 => 3,
+        MlKemParams::MlKem1024 => 4,
+    };
+    384 * k + 32
+}
+
+fn ml_dsa_pubkey_len(params: &MlDsaParams) -> usize {
+    // The seed rho, and the k polynomials of t1, of 320 bytes each
+    let k = match params {
+        MlDsaParams::MlDsa4x4 => 4,
+        MlDsaParams::MlDsa6x5 => 6,
+        MlDsaParams::MlDsa8x7 => 8,
+    };
+    32 + 320 * k
+}
+
+fn frodokem_pubkey_len(params: &FrodoKemParams) -> usize {
+    // The seed for A, and the n x 8 matrix B packed with D bits per entry
+    let (n, d) = match params {
+        FrodoKemParams::Frodo640Shake
+        | FrodoKemParams::Frodo640Aes
+        | FrodoKemParams::EFrodo640Shake
+        | FrodoKemParams::EFrodo640Aes => (640, 15),
+        FrodoKemParams::Frodo976Shake
+        | FrodoKemParams::Frodo976Aes
+        | FrodoKemParams::EFrodo976Shake
+        | FrodoKemParams::EFrodo976Aes => (976, 16),
+        FrodoKemParams::Frodo1344Shake
+        | FrodoKemParams::Frodo1344Aes
+        | FrodoKemParams::EFrodo1344Shake
+        | FrodoKemParams::EFrodo1344Aes => (1344, 16),
+    };
+    16 + n * 8 * d / 8
+}
+
+fn slh_dsa_pubkey_len(params: &SlhDsaParams) -> usize {
+    // PK.seed and PK.root, each of the security parameter n bytes
+    match params {
+        SlhDsaParams::Sha2_128s
+        | SlhDsaParams::Sha2_128f
+        | SlhDsaParams::Shake128s
+        | SlhDsaParams::Shake128f => 32,
+        SlhDsaParams::Sha2_192s
+        | SlhDsaParams::Sha2_192f
+        | SlhDsaParams::Shake192s
+        | SlhDsaParams::Shake192f => 48,
+        SlhDsaParams::Sha2_256s
+        | SlhDsaParams::Sha2_256f
+        | SlhDsaParams::Shake256s
+        | SlhDsaParams::Shake256f => 64,
+    }
+}
+
+fn classic_mceliece_pubkey_len(params: &ClassicMcElieceParams) -> usize {
+    // The mt x (n - mt) bit matrix T, with each row padded to a whole byte
+    let (m, n, t): (usize, usize, usize) = match params {
+        ClassicMcElieceParams::P348864 | ClassicMcElieceParams::P348864f => (12, 3488, 64),
+        ClassicMcElieceParams::P460896 | ClassicMcElieceParams::P460896f => (13, 4608, 96),
+        ClassicMcElieceParams::P6688128
+        | ClassicMcElieceParams::P6688128f
+        | ClassicMcElieceParams::P6688128pc
+        | ClassicMcElieceParams::P6688128pcf => (13, 6688, 128),
+        ClassicMcElieceParams::P6960119
+        | ClassicMcElieceParams::P6960119f
+        | ClassicMcElieceParams::P6960119pc
+        | ClassicMcElieceParams::P6960119pcf => (13, 6960, 119),
+        ClassicMcElieceParams::P8192128
+        | ClassicMcElieceParams::P8192128f
+        | ClassicMcElieceParams::P8192128pc
+        | ClassicMcElieceParams::P8192128pcf => (13, 8192, 128),
+    };
+    m * t * (n - m * t).div_ceil(8)
+}
+
+/// The RFC 8391 / SP 800-208 identifier, node size in bytes, and hash
+/// function of an XMSS parameter set
+fn xmss_params_info(params: &XmssParams) -> (u32, usize, HashAlgorithm) {
+    match params {
+        XmssParams::Sha2_10_256 => (0x01, 32, HashAlgorithm::Sha256),
+        XmssParams::Sha2_16_256 => (0x02, 32, HashAlgorithm::Sha256),
+        XmssParams::Sha2_20_256 => (0x03, 32, HashAlgorithm::Sha256),
+        XmssParams::Sha2_10_512 => (0x04, 64, HashAlgorithm::Sha512),
+        XmssParams::Sha2_16_512 => (0x05, 64, HashAlgorithm::Sha512),
+        XmssParams::Sha2_20_512 => (0x06, 64, HashAlgorithm::Sha512),
+        XmssParams::Shake10_256 => (0x07, 32, HashAlgorithm::Shake128(256)),
+        XmssParams::Shake16_256 => (0x08, 32, HashAlgorithm::Shake128(256)),
+        XmssParams::Shake20_256 => (0x09, 32, HashAlgorithm::Shake128(256)),
+        XmssParams::Shake10_512 => (0x0a, 64, HashAlgorithm::Shake256(512)),
+        XmssParams::Shake16_512 => (0x0b, 64, HashAlgorithm::Shake256(512)),
+        XmssParams::Shake20_512 => (0x0c, 64, HashAlgorithm::Shake256(512)),
+        XmssParams::Sha2_10_192 => (0x0d, 24, HashAlgorithm::Sha256),
+        XmssParams::Sha2_16_192 => (0x0e, 24, HashAlgorithm::Sha256),
+        XmssParams::Sha2_20_192 => (0x0f, 24, HashAlgorithm::Sha256),
+        XmssParams::Shake256_10_256 => (0x10, 32, HashAlgorithm::Shake256(512)),
+        XmssParams::Shake256_16_256 => (0x11, 32, HashAlgorithm::Shake256(512)),
+        XmssParams::Shake256_20_256 => (0x12, 32, HashAlgorithm::Shake256(512)),
+        XmssParams::Shake256_10_192 => (0x13, 24, HashAlgorithm::Shake256(512)),
+        XmssParams::Shake256_16_192 => (0x14, 24, HashAlgorithm::Shake256(512)),
+        XmssParams::Shake256_20_192 => (0x15, 24, HashAlgorithm::Shake256(512)),
+    }
+}
+
+fn loads_xmss_pubkey(id: u32, n: usize) -> bool {
+    // Botan 3 uses the OID of RFC 9802, Botan 2 its own
+    const XMSS_OIDS: [&str; 2] = ["1.3.6.1.5.5.7.6.34", "1.3.6.1.4.1.25258.1.8"];
+
+    // The raw key is the parameter set identifier, root and public seed
+    let mut key = id.to_be_bytes().to_vec();
+    key.resize(4 + 2 * n, 0);
+
+    XMSS_OIDS.iter().any(|oid| {
+        let der = (|| -> Result<Vec<u8>> {
+            let mut enc = DerEncoder::new();
+            enc.start_sequence()
+                .start_sequence()
+                .encode_oid_str(oid)?
+                .end_cons()?
+                .encode_bit_string(&key, 0)?
+                .end_cons()?;
+            enc.finish()
+        })();
+        der.is_ok_and(|der| Pubkey::load_der(&der).is_ok())
+    })
+}
@@ -18,16 +18,49 @@ use crate::utils::*;
 /// If the library cannot be loaded, or is not a usable version of Botan, an
 /// error of type [`ErrorType::LibraryNotLoaded`] is returned.
 pub fn load_library<P: AsRef<std::ffi::OsStr>>(path: P) -> Result<()> {
-    match botan_sys::load_library(path) {
-        Ok(()) => Ok(()),
-        Err(botan_sys::LoadError::AlreadyLoaded) => Err(Error::with_message(
+    botan_sys::load_library(path).map_err(load_error)
+}
+
+pub use botan_sys::{LoadError, LoadReport, LoaderConfig, RejectedCandidate};
+
+/// Load the Botan shared library chosen according to a [`LoaderConfig`]
+///
+/// This is only available with the `dynamic-loading` feature. It behaves
+/// like [`load_library`], except that the candidates of the configuration
+/// are tried in order, and only a library within the configured range of
+/// versions (and matching the pinned SHA-256 digest, if any) is accepted.
+/// The returned report names the library chosen and why each candidate
+/// tried before it was rejected.
+///
+/// If no candidate is acceptable, an error of type
+/// [`ErrorType::LibraryNotLoaded`] is returned whose message lists every
+/// candidate and the reason it was rejected; use
+/// [`LoaderConfig::load`] directly to inspect these programmatically. Other
+/// functions then fail in the same way, rather than loading whatever library
+/// the default search finds; a library can still be loaded explicitly.
+///
+/// # Examples
+///
+/// ```no_run
+/// let config = botan::LoaderConfig::new()
+///     .with_candidate("/opt/botan/lib/libbotan-3.so.13")
+///     .with_candidate("/usr/lib/libbotan-3.so.13")
+///     .with_min_version(3, 13, 0)
+///     .with_max_version(3, 99, 99);
+/// let report = botan::load_library_with_config(&config).unwrap();
+/// println!("Loaded {}", report.library);
+/// ```
+pub fn load_library_with_config(config: &LoaderConfig) -> Result<LoadReport> {
+    config.load().map_err(load_error)
+}
+
+fn load_error(e: botan_sys::LoadError) -> Error {
+    match e {
+        botan_sys::LoadError::AlreadyLoaded => Error::with_message(
             ErrorType::InvalidObjectState,
             "A Botan library has already been loaded".to_owned(),
-        )),
-        Err(e) => Err(Error::with_message(
-            ErrorType::LibraryNotLoaded,
-            e.to_string(),
-        )),
+        ),
+        e => Error::with_message(ErrorType::LibraryNotLoaded, e.to_string()),
     }
 }
 
@@ -38,3 +71,91 @@ pub fn load_library<P: AsRef<std::ffi::OsStr>>(path: P) -> Result<()> {
 pub fn loaded_library_name() -> Option<&'static str> {
     botan_sys::loaded_library_name()
 }
+
+pub use botan_sys::{FfiLevelSymbols, FfiSymbolReport};
+
+/// Resolve every FFI function this crate uses from the Botan shared library,
+/// loading it if necessary, and report which are available
+///
+/// This is only available with the `dynamic-loading` feature. Functions are
+/// otherwise resolved on first use, so that one missing from the library is
+/// only noticed when an operation needing it fails (see
+/// [`Error::is_function_unavailable`]); this allows an application to log
+/// what the library provides at startup. The functions are grouped by the
+/// FFI API version (`YYYYMMDD`) which introduced them.
+///
+/// If the library cannot be loaded, an error of type
+/// [`ErrorType::LibraryNotLoaded`] is returned.
+pub fn ffi_symbol_report() -> Result<FfiSymbolReport> {
+    botan_sys::ffi_symbol_report()
+        .map_err(|e| Error::with_message(ErrorType::LibraryNotLoaded, e.to_string()))
+}
+
+/// Check that the Botan shared library provides the given FFI API version
+///
+/// This is only available with the `dynamic-loading` feature. It is intended
+/// to be called at startup with the FFI API version (`YYYYMMDD`, as also
+/// accepted by [`Version::supports_version`]) which the features the
+/// application uses require, so that a library which is
+/// too old is rejected up front with a clear message rather than by the
+/// first operation which needs a newer function.
+///
+/// Succeeds if the library reports at least that API version and exports
+/// every function this crate declares for it and all earlier versions.
+/// Otherwise an error of type [`ErrorType::NotImplemented`] is returned,
+/// whose message names the library, the Botan release required, and any
+/// missing functions. If the library cannot be loaded, an error of type
+/// [`ErrorType::LibraryNotLoaded`] is returned.
+///
+/// # Examples
+///
+/// ```no_run
+/// // Require Botan 3.8 or later
+/// botan::require_ffi_level(20250506).unwrap();
+/// ```
+pub fn require_ffi_level(level: u32) -> Result<()> {
+    // Listing the missing functions is just for the message, so cap it
+    const MAX_LISTED: usize = 8;
+
+    let report = ffi_symbol_report()?;
+
+    let missing: Vec<&str> = report
+        .levels
+        .iter()
+        .filter(|l| l.level <= level)
+        .flat_map(|l| l.missing.iter().copied())
+        .collect();
+
+    if report.api_version >= level && missing.is_empty() {
+        return Ok(());
+    }
+
+    let required = match botan_sys::botan_release_for_ffi_level(level) {
+        Some(release) => format!("FFI API version {level} (Botan {release} or later)"),
+        None => format!("FFI API version {level}"),
+    };
+
+    let mut msg = if report.api_version < level {
+        format!(
+            "The Botan library {} provides FFI API version {}, but {required} is required",
+            report.library, report.api_version
+        )
+    } else {
+        format!(
+            "The Botan library {} reports FFI API version {}, but is missing functions of {required}",
+            report.library, report.api_version
+        )
+    };
+
+    if !missing.is_empty() {
+        msg.push_str(&format!(
+            "; it does not export {}",
+            missing[..missing.len().min(MAX_LISTED)].join(", ")
+        ));
+        if missing.len() > MAX_LISTED {
+            msg.push_str(&format!(" and {} more", missing.len() - MAX_LISTED));
+        }
+    }
+
+    Err(Error::with_message(ErrorType::NotImplemented, msg))
+}
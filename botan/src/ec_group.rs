@@ -3,30 +3,74 @@ use botan_sys::*;
 
 use crate::OID;
 
-use crate::EcPoint;
+use crate::{EcPoint, EcScalar};
+
+#[cfg(not(feature = "std"))]
+use alloc::sync::Arc;
+#[cfg(feature = "std")]
+use std::sync::Arc;
 
 #[derive(Debug)]
+struct EcGroupObj {
+    obj: botan_ec_group_t,
+    // Cached since every scalar operation reduces modulo the order
+    order: MPI,
+    order_bytes: usize,
+}
+
+unsafe impl Sync for EcGroupObj {}
+unsafe impl Send for EcGroupObj {}
+
+botan_impl_drop!(EcGroupObj, botan_ec_group_destroy);
+
+#[derive(Debug, Clone)]
 /// An elliptic curve group
 ///
+/// Cloning a group is cheap, as the underlying object is shared.
+///
 /// Creating this object requires Botan 3.8 or later; with older versions
 /// an error of type [`ErrorType::NotImplemented`](crate::ErrorType::NotImplemented) is returned
 pub struct EcGroup {
-    obj: botan_ec_group_t,
+    inner: Arc<EcGroupObj>,
 }
 
-unsafe impl Sync for EcGroup {}
-unsafe impl Send for EcGroup {}
-
-botan_impl_drop!(EcGroup, botan_ec_group_destroy);
-
 impl EcGroup {
     pub(crate) fn handle(&self) -> botan_ec_group_t {
-        self.obj
+        self.inner.obj
+    }
+
+    pub(crate) fn from_handle(obj: botan_ec_group_t) -> Result<Self> {
+        let order = botan_init!(botan_ec_group_get_order, obj)
+            .and_then(MPI::from_handle)
+            .and_then(|order| Ok((order.byte_count()?, order)));
+        match order {
+            Ok((order_bytes, order)) => Ok(Self {
+                inner: Arc::new(EcGroupObj {
+                    obj,
+                    order,
+                    order_bytes,
+                }),
+            }),
+            Err(e) => {
+                unsafe { botan_ec_group_destroy(obj) };
+                Err(e)
+            }
+        }
+    }
+
+    /// Return the cached group order
+    pub(crate) fn order_ref(&self) -> &MPI {
+        &self.inner.order
     }
 
-    #[allow(dead_code)]
-    pub(crate) fn from_handle(obj: botan_ec_group_t) -> Self {
-        Self { obj }
+    /// Return the length in bytes of the group order
+    pub(crate) fn order_bytes(&self) -> usize {
+        self.inner.order_bytes
+    }
+
+    /// Check if two groups share the same underlying object
+    pub(crate) fn is_same_object(&self, other: &Self) -> bool {
+        Arc::ptr_eq(&self.inner, &other.inner)
     }
 
     /// Does this build configuration support application specific groups
@@ -70,25 +114,25 @@ impl EcGroup {
     /// Create a group from a named/well known set of parameters
     pub fn from_name(name: &str) -> Result<Self> {
         let obj = botan_init!(botan_ec_group_from_name, make_cstr(name)?.as_ptr())?;
-        Ok(Self { obj })
+        Self::from_handle(obj)
     }
 
     /// Create a group from a named/well known set of parameters
     pub fn from_oid(oid: &OID) -> Result<Self> {
         let obj = botan_init!(botan_ec_group_from_oid, oid.handle())?;
-        Ok(Self { obj })
+        Self::from_handle(obj)
     }
 
     /// Parse the PEM encoding of an EC group
     pub fn from_pem(pem: &str) -> Result<Self> {
         let obj = botan_init!(botan_ec_group_from_pem, make_cstr(pem)?.as_ptr())?;
-        Ok(Self { obj })
+        Self::from_handle(obj)
     }
 
     /// Parse the DER encoding of an EC group
     pub fn from_der(ber: &[u8]) -> Result<Self> {
         let obj = botan_init!(botan_ec_group_from_ber, ber.as_ptr(), ber.len())?;
-        Ok(Self { obj })
+        Self::from_handle(obj)
     }
 
     /// Initial an EcGroup from a custom set of parameters
@@ -115,52 +159,52 @@ impl EcGroup {
             g_y.handle(),
             order.handle()
         )?;
-        Ok(Self { obj })
+        Self::from_handle(obj)
     }
 
     /// Return the DER encoding of the group
     pub fn der(&self) -> Result<Vec<u8>> {
-        botan_view_vec!(botan_ec_group_view_der, self.obj)
+        botan_view_vec!(botan_ec_group_view_der, self.handle())
     }
 
     /// Return the PEM encoding of the group
     pub fn pem(&self) -> Result<String> {
-        botan_view_str!(botan_ec_group_view_pem, self.obj)
+        botan_view_str!(botan_ec_group_view_pem, self.handle())
     }
 
     /// Return the group's parameter p
     pub fn p(&self) -> Result<MPI> {
-        MPI::from_handle(botan_init!(botan_ec_group_get_p, self.obj)?)
+        MPI::from_handle(botan_init!(botan_ec_group_get_p, self.handle())?)
     }
 
     /// Return the group's parameter a
     pub fn a(&self) -> Result<MPI> {
-        MPI::from_handle(botan_init!(botan_ec_group_get_a, self.obj)?)
+        MPI::from_handle(botan_init!(botan_ec_group_get_a, self.handle())?)
     }
 
     /// Return the group's parameter b
     pub fn b(&self) -> Result<MPI> {
-        MPI::from_handle(botan_init!(botan_ec_group_get_b, self.obj)?)
+        MPI::from_handle(botan_init!(botan_ec_group_get_b, self.handle())?)
     }
 
     /// Return the group's order
     pub fn order(&self) -> Result<MPI> {
-        MPI::from_handle(botan_init!(botan_ec_group_get_order, self.obj)?)
+        MPI::from_handle(botan_init!(botan_ec_group_get_order, self.handle())?)
     }
 
     /// Return the group's generator x coordinate
     pub fn g_x(&self) -> Result<MPI> {
-        MPI::from_handle(botan_init!(botan_ec_group_get_g_x, self.obj)?)
+        MPI::from_handle(botan_init!(botan_ec_group_get_g_x, self.handle())?)
     }
 
     /// Return the group's generator y coordinate
     pub fn g_y(&self) -> Result<MPI> {
-        MPI::from_handle(botan_init!(botan_ec_group_get_g_y, self.obj)?)
+        MPI::from_handle(botan_init!(botan_ec_group_get_g_y, self.handle())?)
     }
 
     /// Return the group's object identifier
     pub fn oid(&self) -> Result<OID> {
-        OID::from_handle(botan_init!(botan_ec_group_get_curve_oid, self.obj)?)
+        OID::from_handle(botan_init!(botan_ec_group_get_curve_oid, self.handle())?)
     }
 
     /// Return the group's identity element
@@ -179,9 +223,54 @@ impl EcGroup {
         EcPoint::generator(self)
     }
 
+    /// Hash a message to a point on the curve (RFC 9380 `hash_to_curve`)
+    ///
+    /// This uses the random oracle suite for the group, for example
+    /// `P256_XMD:SHA-256_SSWU_RO_` for secp256r1. The supported groups are
+    /// secp256r1, secp384r1, secp521r1 and secp256k1; for other groups an
+    /// error of type [`ErrorType::NotImplemented`](crate::ErrorType::NotImplemented)
+    /// is returned.
+    ///
+    /// The domain separation tag `dst` should be unique to the protocol
+    /// and its use of the hash function. This implementation is not
+    /// constant time, so the message should not be secret.
+    ///
+    /// # Examples
+    /// ```
+    /// let group = botan::EcGroup::from_name("secp256r1").unwrap();
+    /// let pt = group.hash_to_curve(b"abc", b"QUUX-V01-CS02-with-P256_XMD:SHA-256_SSWU_RO_").unwrap();
+    /// assert_eq!(
+    ///     botan::hex_encode(&pt.to_x_bytes().unwrap()).unwrap(),
+    ///     "0BB8B87485551AA43ED54F009230450B492FEAD5F1CC91658775DAC4A3388A0F"
+    /// );
+    /// ```
+    pub fn hash_to_curve(&self, msg: &[u8], dst: &[u8]) -> Result<EcPoint> {
+        crate::hash_to_curve::hash_to_curve(self, msg, dst)
+    }
+
+    /// Encode a message to a point on the curve (RFC 9380 `encode_to_curve`)
+    ///
+    /// This uses the nonuniform suite for the group, for example
+    /// `P256_XMD:SHA-256_SSWU_NU_`. The output distribution is not
+    /// uniform; prefer [`EcGroup::hash_to_curve`] unless a protocol
+    /// specifically calls for this variant.
+    pub fn encode_to_curve(&self, msg: &[u8], dst: &[u8]) -> Result<EcPoint> {
+        crate::hash_to_curve::encode_to_curve(self, msg, dst)
+    }
+
+    /// Hash a message to a scalar modulo the group order
+    ///
+    /// This is RFC 9380 `hash_to_field` with the group order as the
+    /// modulus, using the same hash and expansion length as
+    /// [`EcGroup::hash_to_curve`]. This is the `HashToScalar` function
+    /// used by (for example) the RFC 9497 OPRF ciphersuites.
+    pub fn hash_to_scalar(&self, msg: &[u8], dst: &[u8]) -> Result<EcScalar> {
+        crate::hash_to_curve::hash_to_scalar(self, msg, dst)
+    }
+
     /// Check two groups for equality
     pub fn equals(&self, other: &Self) -> Result<bool> {
-        botan_bool_in_rc!(botan_ec_group_equal, self.obj, other.obj)
+        botan_bool_in_rc!(botan_ec_group_equal, self.handle(), other.handle())
     }
 }
 
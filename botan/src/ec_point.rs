@@ -1,16 +1,38 @@
 use crate::{MPI, RandomNumberGenerator, utils::*};
 use botan_sys::*;
-use core::ops::Add;
+use core::ops::{Add, Mul, Neg, Sub};
 
 use crate::EcGroup;
 
 #[derive(Debug)]
 /// An Integer modulo the prime group order of an elliptic curve
 ///
+/// Scalars support the usual arithmetic operators, with all results
+/// reduced modulo the group order. Combining scalars from different
+/// groups is an error (and causes the operators to panic), while
+/// comparing them with `==` returns false.
+///
 /// Creating this object requires Botan 3.12 or later; with older versions
 /// an error of type [`ErrorType::NotImplemented`](crate::ErrorType::NotImplemented) is returned
+///
+/// # Examples
+/// ```
+/// let group = botan::EcGroup::from_name("secp256r1").unwrap();
+/// let mut rng = botan::RandomNumberGenerator::new().unwrap();
+/// let a = botan::EcScalar::random(&group, &mut rng).unwrap();
+/// let b = botan::EcScalar::random(&group, &mut rng).unwrap();
+///
+/// let c = &(&a * &b) + &a;
+/// assert_eq!(&(&c - &a) * &b.invert().unwrap(), a);
+/// assert!((&a + &(-&a)).is_zero().unwrap());
+///
+/// let encoded = c.to_bytes().unwrap();
+/// assert_eq!(encoded.len(), 32);
+/// assert_eq!(botan::EcScalar::from_bytes(&group, &encoded).unwrap(), c);
+/// ```
 pub struct EcScalar {
     obj: botan_ec_scalar_t,
+    group: EcGroup,
 }
 
 unsafe impl Sync for EcScalar {}
@@ -23,20 +45,24 @@ impl EcScalar {
         self.obj
     }
 
-    pub(crate) fn from_handle(obj: botan_ec_scalar_t) -> Self {
-        Self { obj }
+    pub(crate) fn from_handle(obj: botan_ec_scalar_t, group: EcGroup) -> Self {
+        Self { obj, group }
+    }
+
+    fn reduce(group: &EcGroup, mpi: &MPI) -> Result<Self> {
+        Self::from_mpi(group, &mpi.divrem(group.order_ref())?.1)
     }
 
     /// Create a new scalar with a random value
     pub fn random(group: &EcGroup, rng: &mut RandomNumberGenerator) -> Result<Self> {
         let obj = botan_init!(botan_ec_scalar_random, group.handle(), rng.handle())?;
-        Ok(Self { obj })
+        Ok(Self::from_handle(obj, group.clone()))
     }
 
     /// Convert from an MPI to a scalar, fails if the MPI is negative or too large
     pub fn from_mpi(group: &EcGroup, mpi: &MPI) -> Result<Self> {
         let obj = botan_init!(botan_ec_scalar_from_mp, group.handle(), mpi.handle())?;
-        Ok(Self { obj })
+        Ok(Self::from_handle(obj, group.clone()))
     }
 
     /// Convert from a scalar to an MPI
@@ -44,6 +70,197 @@ impl EcScalar {
         let obj = botan_init_at!(botan_ec_scalar_to_mp, self.obj;)?;
         MPI::from_handle(obj)
     }
+
+    /// Parse the canonical encoding of a scalar, as produced by [`EcScalar::to_bytes`]
+    ///
+    /// Fails unless the input is exactly the length of the group order
+    /// and encodes an integer smaller than the order.
+    pub fn from_bytes(group: &EcGroup, bytes: &[u8]) -> Result<Self> {
+        if bytes.len() != group.order_bytes() {
+            return Err(Error::with_message(
+                ErrorType::InvalidInput,
+                format!("Invalid length {} for EC scalar", bytes.len()),
+            ));
+        }
+        Self::from_mpi(group, &MPI::new_from_bytes(bytes)?)
+    }
+
+    /// Interpret bytes as a big endian integer and reduce it modulo the group order
+    ///
+    /// Unless the input is much longer than the group order the result is
+    /// biased; use [`EcScalar::from_wide_bytes`] to map uniform random bytes
+    /// (such as a hash output) to a uniform scalar.
+    pub fn from_bytes_reduce(group: &EcGroup, bytes: &[u8]) -> Result<Self> {
+        Self::reduce(group, &MPI::new_from_bytes(bytes)?)
+    }
+
+    /// Reduce a wide big endian integer modulo the group order
+    ///
+    /// The input must be at least 16 bytes longer than the group order, so
+    /// that if the bytes are uniformly random the bias of the resulting
+    /// scalar is negligible.
+    pub fn from_wide_bytes(group: &EcGroup, bytes: &[u8]) -> Result<Self> {
+        if bytes.len() < group.order_bytes() + 16 {
+            return Err(Error::with_message(
+                ErrorType::InvalidInput,
+                format!(
+                    "Wide reduction requires at least {} bytes",
+                    group.order_bytes() + 16
+                ),
+            ));
+        }
+        Self::reduce(group, &MPI::new_from_bytes(bytes)?)
+    }
+
+    /// Return the canonical encoding of this scalar
+    ///
+    /// This is big endian, and always the same length as the group order.
+    pub fn to_bytes(&self) -> Result<Vec<u8>> {
+        self.to_mpi()?.to_bin_padded(self.group.order_bytes())
+    }
+
+    fn same_group(&self, other: &EcScalar) -> bool {
+        self.group.is_same_object(&other.group) || self.group.order_ref() == other.group.order_ref()
+    }
+
+    fn check_same_group(&self, other: &EcScalar) -> Result<()> {
+        if self.same_group(other) {
+            Ok(())
+        } else {
+            Err(Error::bad_parameter(
+                "Cannot combine scalars from different groups",
+            ))
+        }
+    }
+
+    /// Check if this scalar is zero
+    pub fn is_zero(&self) -> Result<bool> {
+        self.to_mpi()?.is_zero()
+    }
+
+    /// Check if this scalar and another one are equal
+    pub fn is_equal(&self, other: &EcScalar) -> Result<bool> {
+        self.check_same_group(other)?;
+        Ok(self.to_mpi()? == other.to_mpi()?)
+    }
+
+    /// Add another scalar to this one, creating a new scalar
+    pub fn scalar_add(&self, other: &EcScalar) -> Result<EcScalar> {
+        self.check_same_group(other)?;
+        Self::reduce(&self.group, &self.to_mpi()?.mp_add(&other.to_mpi()?)?)
+    }
+
+    /// Subtract another scalar from this one, creating a new scalar
+    pub fn scalar_sub(&self, other: &EcScalar) -> Result<EcScalar> {
+        self.check_same_group(other)?;
+        let r = self.to_mpi()?.mp_add(self.group.order_ref())?;
+        Self::reduce(&self.group, &r.mp_sub(&other.to_mpi()?)?)
+    }
+
+    /// Multiply this scalar by another one, creating a new scalar
+    pub fn scalar_mul(&self, other: &EcScalar) -> Result<EcScalar> {
+        self.check_same_group(other)?;
+        let r = self
+            .to_mpi()?
+            .mod_mul(&other.to_mpi()?, self.group.order_ref())?;
+        Self::from_mpi(&self.group, &r)
+    }
+
+    /// Create a new scalar with the negated value of this one
+    pub fn negate(&self) -> Result<EcScalar> {
+        let r = self.group.order_ref().mp_sub(&self.to_mpi()?)?;
+        Self::reduce(&self.group, &r)
+    }
+
+    /// Create a new scalar with the multiplicative inverse of this one
+    ///
+    /// Fails if this scalar is zero
+    pub fn invert(&self) -> Result<EcScalar> {
+        if self.is_zero()? {
+            return Err(Error::with_message(
+                ErrorType::InvalidInput,
+                "Cannot invert zero".to_string(),
+            ));
+        }
+        let r = MPI::modular_inverse(&self.to_mpi()?, self.group.order_ref())?;
+        Self::from_mpi(&self.group, &r)
+    }
+}
+
+impl Clone for EcScalar {
+    fn clone(&self) -> EcScalar {
+        let v = self.to_mpi().expect("botan_ec_scalar_to_mp should succeed");
+        EcScalar::from_mpi(&self.group, &v).expect("botan_ec_scalar_from_mp should succeed")
+    }
+}
+
+impl PartialEq for EcScalar {
+    fn eq(&self, other: &EcScalar) -> bool {
+        // Scalars from different groups are never equal
+        self.same_group(other)
+            && self
+                .is_equal(other)
+                .expect("botan_ec_scalar_to_mp should succeed")
+    }
+}
+
+impl Eq for EcScalar {}
+
+macro_rules! ec_scalar_binop {
+    ($trait:ident, $fn:ident, $method:ident) => {
+        impl<'b> $trait<&'b EcScalar> for &EcScalar {
+            type Output = EcScalar;
+
+            fn $fn(self, other: &'b EcScalar) -> EcScalar {
+                self.$method(other)
+                    .expect("scalars should be from the same group")
+            }
+        }
+
+        impl $trait<&EcScalar> for EcScalar {
+            type Output = EcScalar;
+
+            fn $fn(self, other: &EcScalar) -> EcScalar {
+                (&self).$fn(other)
+            }
+        }
+
+        impl $trait<EcScalar> for &EcScalar {
+            type Output = EcScalar;
+
+            fn $fn(self, other: EcScalar) -> EcScalar {
+                self.$fn(&other)
+            }
+        }
+
+        impl $trait<EcScalar> for EcScalar {
+            type Output = EcScalar;
+
+            fn $fn(self, other: EcScalar) -> EcScalar {
+                (&self).$fn(&other)
+            }
+        }
+    };
+}
+
+ec_scalar_binop!(Add, add, scalar_add);
+ec_scalar_binop!(Sub, sub, scalar_sub);
+ec_scalar_binop!(Mul, mul, scalar_mul);
+
+impl Neg for &EcScalar {
+    type Output = EcScalar;
+
+    fn neg(self) -> EcScalar {
+        self.negate().expect("scalar negation should succeed")
+    }
+}
+
+impl Neg for EcScalar {
+    type Output = EcScalar;
+
+    fn neg(self) -> EcScalar {
+        -&self
+    }
 }
 
 #[derive(Debug)]
@@ -0,0 +1,388 @@
+use crate::utils::*;
+
+use crate::{
+    BlockCipherAlgorithm, Cipher, CipherAlgorithm, CipherDirection, EcGroup, EcGroupId, EcPoint,
+    HashAlgorithm, KdfAlgorithm, KeyAgreement, MacAlgorithm, MsgAuthCode, Privkey, Pubkey,
+    RandomNumberGenerator, const_time_compare, kdf,
+};
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+/// The SEC 1 encoding of an elliptic curve point
+pub enum EcPointEncoding {
+    /// Both coordinates, prefixed by 0x04
+    #[default]
+    Uncompressed,
+    /// Only the x coordinate, prefixed by 0x02 or 0x03 depending on the sign of y
+    Compressed,
+    /// Both coordinates, prefixed by 0x06 or 0x07 depending on the sign of y
+    Hybrid,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+/// The group used for the ephemeral key agreement of ECIES
+pub enum EciesGroup {
+    /// ECDH over an elliptic curve group
+    Ec(EcGroupId),
+    /// X25519 (RFC 7748)
+    X25519,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+/// Parameters for ECIES (SEC 1 v2 and ISO 18033-2)
+///
+/// The ciphertext consists of the encoded ephemeral public key, followed by
+/// the encrypted message, followed by a MAC over the encrypted message and
+/// label (if a MAC is used). The encryption key, followed by the MAC key,
+/// are derived using the KDF; the cipher always uses an all-zero nonce,
+/// which is safe since every key is used only once.
+///
+/// This is the format used by Botan's C++ ECIES implementation.
+pub struct EciesParams {
+    /// The key agreement group; the recipient key must be in this group
+    pub group: EciesGroup,
+    /// The KDF applied to the shared secret
+    pub kdf: KdfAlgorithm,
+    /// The cipher used to encrypt the message
+    ///
+    /// If this is not an AEAD, a MAC must also be specified.
+    pub cipher: CipherAlgorithm,
+    /// The MAC used to authenticate the encrypted message
+    pub mac: Option<MacAlgorithm>,
+    /// The length of the MAC key; ignored if no MAC is used
+    pub mac_key_len: usize,
+    /// The encoding of the ephemeral public key, ignored for X25519
+    pub point_encoding: EcPointEncoding,
+    /// If set, only the shared secret is input to the KDF, as in SEC 1.
+    /// Otherwise the encoded ephemeral public key is prepended to it, as in
+    /// ISO 18033-2.
+    ///
+    /// Note that in single hash mode the ciphertext is malleable, since
+    /// negating the ephemeral public key leaves the shared secret unchanged.
+    pub single_hash_mode: bool,
+}
+
+impl Default for EciesParams {
+    /// ECDH over P-256 with KDF2(SHA-256), AES-256/GCM, and uncompressed
+    /// points in ISO 18033-2 mode
+    fn default() -> Self {
+        Self {
+            group: EciesGroup::Ec(EcGroupId::Secp256r1),
+            kdf: KdfAlgorithm::Kdf2(HashAlgorithm::Sha256),
+            cipher: CipherAlgorithm::Gcm(BlockCipherAlgorithm::Aes256, None),
+            mac: None,
+            mac_key_len: 32,
+            point_encoding: EcPointEncoding::Uncompressed,
+            single_hash_mode: false,
+        }
+    }
+}
+
+fn ecies_error(msg: &str) -> Error {
+    Error::with_message(ErrorType::InvalidInput, format!("ECIES: {msg}"))
+}
+
+/// The negotiated algorithms and sizes shared by encryption and decryption
+struct EciesScheme {
+    params: EciesParams,
+    group: Option<EcGroup>,
+    cipher_key_len: usize,
+    nonce_len: usize,
+    tag_len: usize,
+}
+
+impl EciesScheme {
+    fn new(params: &EciesParams) -> Result<Self> {
+        let group = match &params.group {
+            EciesGroup::Ec(id) => Some(EcGroup::from_name(&id.botan_name())?),
+            EciesGroup::X25519 => None,
+        };
+
+        let cipher = Cipher::new(params.cipher.clone(), CipherDirection::Encrypt)?;
+        let cipher_key_len = cipher.key_spec()?.maximum_keylength();
+        let nonce_len = cipher.default_nonce_length();
+
+        let tag_len = match &params.mac {
+            Some(mac) => {
+                if params.mac_key_len == 0 {
+                    return Err(Error::bad_parameter(
+                        "ECIES MAC key length must not be zero",
+                    ));
+                }
+                MsgAuthCode::new(mac.clone())?.output_length()?
+            }
+            None if cipher.tag_length() > 0 => 0,
+            None => {
+                return Err(Error::bad_parameter(
+                    "ECIES requires a MAC unless the cipher is an AEAD",
+                ));
+            }
+        };
+
+        Ok(Self {
+            params: params.clone(),
+            group,
+            cipher_key_len,
+            nonce_len,
+            tag_len,
+        })
+    }
+
+    fn mac_key_len(&self) -> usize {
+        if self.params.mac.is_some() {
+            self.params.mac_key_len
+        } else {
+            0
+        }
+    }
+
+    /// Return the length of an encoded ephemeral public key
+    fn encoded_point_len(&self) -> Result<usize> {
+        match &self.group {
+            Some(group) => {
+                let field_len = group.p()?.byte_count()?;
+                Ok(match self.params.point_encoding {
+                    EcPointEncoding::Compressed => 1 + field_len,
+                    EcPointEncoding::Uncompressed | EcPointEncoding::Hybrid => 1 + 2 * field_len,
+                })
+            }
+            None => Ok(32),
+        }
+    }
+
+    /// Check and decode a public key, returning the form used for key agreement
+    fn decode_point(&self, encoded: &[u8]) -> Result<Vec<u8>> {
+        let group = match &self.group {
+            Some(group) => group,
+            None => {
+                if encoded.len() != 32 {
+                    return Err(ecies_error("invalid X25519 public key"));
+                }
+                return Ok(encoded.to_vec());
+            }
+        };
+
+        let mut encoded = encoded.to_vec();
+        if let Some(prefix @ (0x06 | 0x07)) = encoded.first().copied() {
+            // Hybrid encoding repeats the sign of y in the prefix
+            if encoded.last().map(|y| y & 1) != Some(prefix & 1) {
+                return Err(ecies_error("invalid hybrid point encoding"));
+            }
+            encoded[0] = 0x04;
+        }
+
+        let point = EcPoint::from_bytes(group, &encoded)?;
+        if point.is_identity()? {
+            return Err(ecies_error("public key is the identity"));
+        }
+        point.to_uncompressed()
+    }
+
+    /// Encode an uncompressed point according to the configured encoding
+    fn encode_point(&self, uncompressed: &[u8]) -> Result<Vec<u8>> {
+        let group = match &self.group {
+            Some(group) => group,
+            None => return Ok(uncompressed.to_vec()),
+        };
+
+        match self.params.point_encoding {
+            EcPointEncoding::Uncompressed => Ok(uncompressed.to_vec()),
+            EcPointEncoding::Compressed => {
+                EcPoint::from_bytes(group, uncompressed)?.to_compressed()
+            }
+            EcPointEncoding::Hybrid => {
+                let mut encoded = uncompressed.to_vec();
+                encoded[0] = 0x06 | (encoded[encoded.len() - 1] & 1);
+                Ok(encoded)
+            }
+        }
+    }
+
+    fn check_key_algo(&self, algo: &str) -> Result<()> {
+        let expected = match self.params.group {
+            EciesGroup::Ec(_) => matches!(algo, "ECDH" | "ECDSA" | "ECGDSA" | "ECKCDSA"),
+            EciesGroup::X25519 => matches!(algo, "X25519" | "Curve25519"),
+        };
+        if !expected {
+            return Err(Error::with_message(
+                ErrorType::BadParameter,
+                format!("ECIES: {algo} key does not match the ECIES group"),
+            ));
+        }
+        Ok(())
+    }
+
+    /// Derive the cipher key and MAC key from the ephemeral public key and shared secret
+    fn derive_keys(&self, encoded_ephemeral: &[u8], shared: &[u8]) -> Result<(Vec<u8>, Vec<u8>)> {
+        if shared.iter().all(|b| *b == 0) {
+            return Err(ecies_error("key agreement produced an all-zero secret"));
+        }
+
+        let mut secret = Vec::with_capacity(encoded_ephemeral.len() + shared.len());
+        if !self.params.single_hash_mode {
+            secret.extend_from_slice(encoded_ephemeral);
+        }
+        secret.extend_from_slice(shared);
+
+        let mut keys = kdf(
+            self.params.kdf.clone(),
+            self.cipher_key_len + self.mac_key_len(),
+            &secret,
+            &[],
+            &[],
+        )?;
+        let mac_key = keys.split_off(self.cipher_key_len);
+        Ok((keys, mac_key))
+    }
+
+    fn compute_tag(&self, mac_key: &[u8], ctext: &[u8], label: &[u8]) -> Result<Vec<u8>> {
+        match &self.params.mac {
+            Some(mac) => {
+                let mut mac = MsgAuthCode::new(mac.clone())?;
+                mac.set_key(mac_key)?;
+                mac.update(ctext)?;
+                mac.update(label)?;
+                mac.finish()
+            }
+            None => Ok(Vec::new()),
+        }
+    }
+
+    fn cipher(&self, direction: CipherDirection, key: &[u8], label: &[u8]) -> Result<Cipher> {
+        let mut cipher = Cipher::new(self.params.cipher.clone(), direction)?;
+        cipher.set_key(key)?;
+        if cipher.tag_length() > 0 {
+            cipher.set_associated_data(label)?;
+        }
+        Ok(cipher)
+    }
+}
+
+/// ECIES encryption to a recipient's public key
+///
+/// # Examples
+/// ```
+/// let mut rng = botan::RandomNumberGenerator::new().unwrap();
+/// let params = botan::EciesParams::default();
+/// let key = botan::Privkey::create("ECDH", "secp256r1", &mut rng).unwrap();
+///
+/// let enc = botan::EciesEncryptor::new(&key.pubkey().unwrap(), &params).unwrap();
+/// let ctext = enc.encrypt(b"message", b"label", &mut rng).unwrap();
+///
+/// let mut dec = botan::EciesDecryptor::new(&key, &params).unwrap();
+/// assert_eq!(dec.decrypt(&ctext, b"label").unwrap(), b"message");
+/// ```
+pub struct EciesEncryptor {
+    scheme: EciesScheme,
+    recipient: Vec<u8>,
+}
+
+impl EciesEncryptor {
+    /// Create an encryptor for the recipient's public key
+    pub fn new(recipient: &Pubkey, params: &EciesParams) -> Result<Self> {
+        let scheme = EciesScheme::new(params)?;
+        scheme.check_key_algo(&recipient.algo_name()?)?;
+
+        let recipient = match scheme.params.group {
+            EciesGroup::Ec(_) => recipient.ec_public_point()?,
+            EciesGroup::X25519 => recipient.get_x25519_key()?,
+        };
+        let recipient = scheme.decode_point(&recipient)?;
+
+        Ok(Self { scheme, recipient })
+    }
+
+    /// Encrypt a message, binding it to the (possibly empty) label
+    pub fn encrypt(
+        &self,
+        plaintext: &[u8],
+        label: &[u8],
+        rng: &mut RandomNumberGenerator,
+    ) -> Result<Vec<u8>> {
+        let ephemeral = match &self.scheme.params.group {
+            EciesGroup::Ec(id) => Privkey::create("ECDH", id, rng)?,
+            EciesGroup::X25519 => Privkey::create("X25519", "", rng)?,
+        };
+        self.encrypt_with_ephemeral(plaintext, label, &ephemeral)
+    }
+
+    /// Encrypt a message using the specified ephemeral key
+    ///
+    /// This is intended for testing against known answers; the ephemeral
+    /// key must be freshly generated for every message.
+    pub fn encrypt_with_ephemeral(
+        &self,
+        plaintext: &[u8],
+        label: &[u8],
+        ephemeral: &Privkey,
+    ) -> Result<Vec<u8>> {
+        let scheme = &self.scheme;
+        scheme.check_key_algo(&ephemeral.algo_name()?)?;
+
+        let ephemeral_public = scheme.decode_point(&ephemeral.key_agreement_key()?)?;
+        let mut out = scheme.encode_point(&ephemeral_public)?;
+
+        let shared =
+            KeyAgreement::new(ephemeral, KdfAlgorithm::Raw)?.agree(0, &self.recipient, &[])?;
+        let (cipher_key, mac_key) = scheme.derive_keys(&out, &shared)?;
+
+        let mut cipher = scheme.cipher(CipherDirection::Encrypt, &cipher_key, label)?;
+        let ctext = cipher.process(&vec![0; scheme.nonce_len], plaintext)?;
+        let tag = scheme.compute_tag(&mac_key, &ctext, label)?;
+
+        out.extend_from_slice(&ctext);
+        out.extend_from_slice(&tag);
+        Ok(out)
+    }
+}
+
+/// ECIES decryption using the recipient's private key
+///
+/// See [`EciesEncryptor`] for an example
+pub struct EciesDecryptor {
+    scheme: EciesScheme,
+    ka: KeyAgreement,
+}
+
+impl EciesDecryptor {
+    /// Create a decryptor for the recipient's private key
+    pub fn new(key: &Privkey, params: &EciesParams) -> Result<Self> {
+        let scheme = EciesScheme::new(params)?;
+        scheme.check_key_algo(&key.algo_name()?)?;
+        // Check the key is in the expected group
+        scheme.decode_point(&key.key_agreement_key()?)?;
+
+        let ka = KeyAgreement::new(key, KdfAlgorithm::Raw)?;
+        Ok(Self { scheme, ka })
+    }
+
+    /// Decrypt a message, checking it was bound to the label
+    ///
+    /// If the ciphertext was modified, an error is returned
+    pub fn decrypt(&mut self, ciphertext: &[u8], label: &[u8]) -> Result<Vec<u8>> {
+        let scheme = &self.scheme;
+        let point_len = scheme.encoded_point_len()?;
+
+        if ciphertext.len() < point_len + scheme.tag_len {
+            return Err(ecies_error("ciphertext too short"));
+        }
+        let (encoded_ephemeral, rest) = ciphertext.split_at(point_len);
+        let (ctext, tag) = rest.split_at(rest.len() - scheme.tag_len);
+
+        let ephemeral_public = scheme.decode_point(encoded_ephemeral)?;
+        let shared = self.ka.agree(0, &ephemeral_public, &[])?;
+        let (cipher_key, mac_key) = scheme.derive_keys(encoded_ephemeral, &shared)?;
+
+        if scheme.params.mac.is_some() {
+            let expected_tag = scheme.compute_tag(&mac_key, ctext, label)?;
+            if !const_time_compare(&expected_tag, tag) {
+                return Err(Error::with_message(
+                    ErrorType::BadAuthCode,
+                    "ECIES: message authentication failed".to_string(),
+                ));
+            }
+        }
+
+        let mut cipher = scheme.cipher(CipherDirection::Decrypt, &cipher_key, label)?;
+        cipher.process(&vec![0; scheme.nonce_len], ctext)
+    }
+}
@@ -0,0 +1,359 @@
+use crate::utils::*;
+
+use crate::{
+    EcGroup, EcPoint, EcScalar, HashFunction, MsgAuthCode, Privkey, Pubkey, RandomNumberGenerator,
+};
+
+const P256_POINT_LEN: usize = 33;
+const P256_SCALAR_LEN: usize = 32;
+const CHALLENGE_LEN: usize = 16;
+
+#[derive(Eq, PartialEq, Debug, Copy, Clone)]
+/// An ECVRF ciphersuite from RFC 9381
+///
+/// The ECVRF-EDWARDS25519-SHA512 suites are not supported, since they
+/// require arithmetic on the Edwards curve which is not exposed by Botan.
+pub enum EcVrfSuite {
+    /// ECVRF-P256-SHA256-TAI, which hashes to the curve using try-and-increment
+    P256Sha256Tai,
+    /// ECVRF-P256-SHA256-SSWU, which hashes to the curve using RFC 9380
+    P256Sha256Sswu,
+}
+
+impl EcVrfSuite {
+    fn suite_string(self) -> u8 {
+        match self {
+            Self::P256Sha256Tai => 0x01,
+            Self::P256Sha256Sswu => 0x02,
+        }
+    }
+
+    fn group(self) -> Result<EcGroup> {
+        EcGroup::from_name("secp256r1")
+    }
+
+    fn hash(self) -> Result<HashFunction> {
+        HashFunction::new("SHA-256")
+    }
+
+    /// Return the length of a proof in bytes
+    pub fn proof_length(self) -> usize {
+        P256_POINT_LEN + CHALLENGE_LEN + P256_SCALAR_LEN
+    }
+
+    /// Return the length of the VRF output in bytes
+    pub fn output_length(self) -> usize {
+        32
+    }
+
+    /// Compute the VRF output from a proof (`ECVRF_proof_to_hash`)
+    ///
+    /// This does not verify the proof; an unverified output must not be
+    /// trusted. Use [`EcVrfVerifier::verify`], which returns the output of
+    /// a valid proof, instead.
+    pub fn proof_to_hash(self, proof: &[u8]) -> Result<Vec<u8>> {
+        let (gamma, _, _) = self.decode_proof(&self.group()?, proof)?;
+        self.gamma_to_hash(&gamma)
+    }
+
+    fn decode_proof(self, group: &EcGroup, proof: &[u8]) -> Result<(EcPoint, Vec<u8>, EcScalar)> {
+        if proof.len() != self.proof_length() {
+            return Err(Error::with_message(
+                ErrorType::InvalidInput,
+                format!("Invalid length {} for ECVRF proof", proof.len()),
+            ));
+        }
+
+        let (gamma, rest) = proof.split_at(P256_POINT_LEN);
+        let (c, s) = rest.split_at(CHALLENGE_LEN);
+
+        let gamma = EcPoint::from_bytes(group, gamma)?;
+        let s = EcScalar::from_bytes(group, s)?;
+        Ok((gamma, c.to_vec(), s))
+    }
+
+    fn gamma_to_hash(self, gamma: &EcPoint) -> Result<Vec<u8>> {
+        // The cofactor of P-256 is 1
+        let mut hash = self.hash()?;
+        hash.update(&[self.suite_string(), 0x03])?;
+        hash.update(&gamma.to_compressed()?)?;
+        hash.update(&[0x00])?;
+        hash.finish()
+    }
+
+    fn encode_to_curve(self, group: &EcGroup, pk: &[u8], alpha: &[u8]) -> Result<EcPoint> {
+        match self {
+            Self::P256Sha256Tai => {
+                let mut hash = self.hash()?;
+                for ctr in 0..=255u8 {
+                    hash.update(&[self.suite_string(), 0x01])?;
+                    hash.update(pk)?;
+                    hash.update(alpha)?;
+                    hash.update(&[ctr, 0x00])?;
+
+                    let mut encoded = vec![0x02];
+                    encoded.extend_from_slice(&hash.finish()?);
+
+                    // Roughly half of all x coordinates are valid
+                    if let Ok(pt) = EcPoint::from_bytes(group, &encoded) {
+                        return Ok(pt);
+                    }
+                }
+
+                Err(Error::with_message(
+                    ErrorType::InternalError,
+                    "ECVRF try-and-increment failed to find a point".to_string(),
+                ))
+            }
+            Self::P256Sha256Sswu => {
+                let mut input = pk.to_vec();
+                input.extend_from_slice(alpha);
+                let mut dst = b"ECVRF_P256_XMD:SHA-256_SSWU_NU_".to_vec();
+                dst.push(self.suite_string());
+                group.encode_to_curve(&input, &dst)
+            }
+        }
+    }
+
+    fn challenge(self, points: [&EcPoint; 5]) -> Result<Vec<u8>> {
+        let mut hash = self.hash()?;
+        hash.update(&[self.suite_string(), 0x02])?;
+        for pt in points {
+            hash.update(&pt.to_compressed()?)?;
+        }
+        hash.update(&[0x00])?;
+        let mut c = hash.finish()?;
+        c.truncate(CHALLENGE_LEN);
+        Ok(c)
+    }
+
+    /// Deterministic nonce generation from RFC 6979 Section 3.2
+    fn nonce(self, group: &EcGroup, x: &EcScalar, h_string: &[u8]) -> Result<EcScalar> {
+        let mut hash = self.hash()?;
+        hash.update(h_string)?;
+        let h1 = EcScalar::from_bytes_reduce(group, &hash.finish()?)?;
+
+        let mut seed = x.to_bytes()?;
+        seed.extend_from_slice(&h1.to_bytes()?);
+
+        let mut hmac = MsgAuthCode::new("HMAC(SHA-256)")?;
+        let mut hmac_with = |key: &[u8], parts: &[&[u8]]| -> Result<Vec<u8>> {
+            hmac.set_key(key)?;
+            for part in parts {
+                hmac.update(part)?;
+            }
+            hmac.finish()
+        };
+
+        let mut v = vec![0x01; 32];
+        let mut k = vec![0x00; 32];
+
+        k = hmac_with(&k, &[&v, &[0x00], &seed])?;
+        v = hmac_with(&k, &[&v])?;
+        k = hmac_with(&k, &[&v, &[0x01], &seed])?;
+        v = hmac_with(&k, &[&v])?;
+
+        loop {
+            v = hmac_with(&k, &[&v])?;
+
+            if let Ok(nonce) = EcScalar::from_bytes(group, &v) {
+                if !nonce.is_zero()? {
+                    return Ok(nonce);
+                }
+            }
+
+            k = hmac_with(&k, &[&v, &[0x00]])?;
+            v = hmac_with(&k, &[&v])?;
+        }
+    }
+}
+
+fn check_key_group(suite: EcVrfSuite, key_group: &EcGroup) -> Result<EcGroup> {
+    let group = suite.group()?;
+    if !group.equals(key_group)? {
+        return Err(Error::bad_parameter(
+            "Key does not match the ECVRF ciphersuite",
+        ));
+    }
+    Ok(group)
+}
+
+#[derive(Debug)]
+/// Computes ECVRF proofs (RFC 9381)
+///
+/// A verifiable random function maps an input to an output which is
+/// pseudorandom to anyone who does not know the secret key, along with a
+/// proof which allows anyone with the public key to check that the output
+/// is correct. For a given key and input there is exactly one valid output.
+///
+/// # Examples
+/// ```
+/// let mut rng = botan::RandomNumberGenerator::new().unwrap();
+/// let key = botan::Privkey::create("ECDSA", "secp256r1", &mut rng).unwrap();
+/// let suite = botan::EcVrfSuite::P256Sha256Sswu;
+///
+/// let prover = botan::EcVrfProver::new(suite, &key).unwrap();
+/// let proof = prover.prove(b"round 17", &mut rng).unwrap();
+///
+/// let verifier = botan::EcVrfVerifier::new(suite, &key.pubkey().unwrap()).unwrap();
+/// let output = verifier.verify(b"round 17", &proof, &mut rng).unwrap();
+/// assert_eq!(output, suite.proof_to_hash(&proof).unwrap());
+/// assert!(verifier.verify(b"round 18", &proof, &mut rng).is_err());
+/// ```
+pub struct EcVrfProver {
+    suite: EcVrfSuite,
+    group: EcGroup,
+    secret: EcScalar,
+    public: EcPoint,
+    public_bytes: Vec<u8>,
+}
+
+impl EcVrfProver {
+    /// Create a prover from an EC private key
+    ///
+    /// The key must be on the curve used by the ciphersuite (P-256)
+    pub fn new(suite: EcVrfSuite, key: &Privkey) -> Result<Self> {
+        let group = check_key_group(suite, &key.get_group()?)?;
+        let secret = key.get_private_value()?;
+        if secret.is_zero()? {
+            return Err(Error::bad_parameter("Invalid ECVRF private key"));
+        }
+
+        let public = EcPoint::from_bytes(&group, &key.pubkey()?.ec_public_point()?)?;
+        let public_bytes = public.to_compressed()?;
+
+        Ok(Self {
+            suite,
+            group,
+            secret,
+            public,
+            public_bytes,
+        })
+    }
+
+    /// Return the ciphersuite
+    pub fn suite(&self) -> EcVrfSuite {
+        self.suite
+    }
+
+    /// Return the public key, as a compressed point
+    pub fn public_key(&self) -> Vec<u8> {
+        self.public_bytes.clone()
+    }
+
+    /// Compute the proof for an input (`ECVRF_prove`)
+    ///
+    /// Proofs are deterministic; the RNG is only used for blinding of the
+    /// scalar multiplications.
+    pub fn prove(&self, alpha: &[u8], rng: &mut RandomNumberGenerator) -> Result<Vec<u8>> {
+        let suite = self.suite;
+        let h = suite.encode_to_curve(&self.group, &self.public_bytes, alpha)?;
+        let h_string = h.to_compressed()?;
+
+        let gamma = h.mul(&self.secret, rng)?;
+        let k = suite.nonce(&self.group, &self.secret, &h_string)?;
+        let kb = self.group.generator()?.mul(&k, rng)?;
+        let kh = h.mul(&k, rng)?;
+
+        let c = suite.challenge([&self.public, &h, &gamma, &kb, &kh])?;
+        let s = &k + &(&EcScalar::from_bytes_reduce(&self.group, &c)? * &self.secret);
+
+        let mut proof = gamma.to_compressed()?;
+        proof.extend_from_slice(&c);
+        proof.extend_from_slice(&s.to_bytes()?);
+        Ok(proof)
+    }
+}
+
+#[derive(Debug)]
+/// Verifies ECVRF proofs (RFC 9381)
+///
+/// See [`EcVrfProver`] for an example
+pub struct EcVrfVerifier {
+    suite: EcVrfSuite,
+    group: EcGroup,
+    public: EcPoint,
+    public_bytes: Vec<u8>,
+}
+
+impl EcVrfVerifier {
+    /// Create a verifier from an EC public key
+    ///
+    /// The key must be on the curve used by the ciphersuite (P-256)
+    pub fn new(suite: EcVrfSuite, key: &Pubkey) -> Result<Self> {
+        let group = check_key_group(suite, &key.get_group()?)?;
+        let public = EcPoint::from_bytes(&group, &key.ec_public_point()?)?;
+        Self::from_point(suite, group, public)
+    }
+
+    /// Create a verifier from an encoded public key
+    ///
+    /// Accepts the compressed or uncompressed SEC1 encoding of a point
+    pub fn from_public_key(suite: EcVrfSuite, key: &[u8]) -> Result<Self> {
+        let group = suite.group()?;
+        let public = EcPoint::from_bytes(&group, key)?;
+        Self::from_point(suite, group, public)
+    }
+
+    fn from_point(suite: EcVrfSuite, group: EcGroup, public: EcPoint) -> Result<Self> {
+        if public.is_identity()? {
+            return Err(Error::bad_parameter("Invalid ECVRF public key"));
+        }
+        let public_bytes = public.to_compressed()?;
+        Ok(Self {
+            suite,
+            group,
+            public,
+            public_bytes,
+        })
+    }
+
+    /// Return the ciphersuite
+    pub fn suite(&self) -> EcVrfSuite {
+        self.suite
+    }
+
+    /// Return the public key, as a compressed point
+    pub fn public_key(&self) -> Vec<u8> {
+        self.public_bytes.clone()
+    }
+
+    /// Verify a proof for an input (`ECVRF_verify`), returning the VRF output
+    ///
+    /// If the proof is not valid an error of type
+    /// [`ErrorType::InvalidVerifier`](crate::ErrorType::InvalidVerifier) is returned.
+    /// The RNG is only used for blinding of the scalar multiplications.
+    pub fn verify(
+        &self,
+        alpha: &[u8],
+        proof: &[u8],
+        rng: &mut RandomNumberGenerator,
+    ) -> Result<Vec<u8>> {
+        let suite = self.suite;
+        let invalid = || {
+            Error::with_message(
+                ErrorType::InvalidVerifier,
+                "ECVRF proof is invalid".to_string(),
+            )
+        };
+
+        let (gamma, c, s) = suite
+            .decode_proof(&self.group, proof)
+            .map_err(|_| invalid())?;
+        let c_scalar = EcScalar::from_bytes_reduce(&self.group, &c)?;
+
+        let h = suite.encode_to_curve(&self.group, &self.public_bytes, alpha)?;
+
+        // U = s*B - c*Y and V = s*H - c*Gamma
+        let u =
+            self.group.generator()?.mul(&s, rng)? + self.public.mul(&c_scalar, rng)?.negate()?;
+        let v = h.mul(&s, rng)? + gamma.mul(&c_scalar, rng)?.negate()?;
+
+        let expected = suite.challenge([&self.public, &h, &gamma, &u, &v])?;
+        if !crate::const_time_compare(&c, &expected) {
+            return Err(invalid());
+        }
+
+        suite.gamma_to_hash(&gamma)
+    }
+}
@@ -1,6 +1,7 @@
 use crate::utils::*;
 use botan_sys::*;
 
+use crate::block::BlockCipher;
 use crate::mp::MPI;
 
 /// Represents an instance of format preserving encryption
@@ -76,3 +77,478 @@ impl FPE {
         Ok(r)
     }
 }
+
+fn fpe_input_error(msg: &str) -> Error {
+    Error::with_message(ErrorType::InvalidInput, msg.to_owned())
+}
+
+/// The set of characters that format preserving encryption operates over
+///
+/// Each character is mapped to a numeral by its position in the alphabet,
+/// so the alphabet `0123456789` gives the usual radix 10 representation.
+///
+/// # Examples
+///
+/// ```
+/// let digits = botan::FpeAlphabet::digits();
+/// assert_eq!(digits.radix(), 10);
+/// assert_eq!(digits.to_numerals("4096").unwrap(), vec![4, 0, 9, 6]);
+/// assert!(digits.to_numerals("40x6").is_err());
+///
+/// // With FE1, the string is treated as a number below radix^len
+/// let modulus = digits.modulus(16).unwrap();
+/// let fpe = botan::FPE::new_fe1(&modulus, &[0; 32], 16, false).unwrap();
+/// let x = digits.to_mpi("4111111111111111").unwrap();
+/// let ctext = digits.from_mpi(&fpe.encrypt(&x, b"tweak").unwrap(), 16).unwrap();
+/// assert_eq!(ctext.len(), 16);
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct FpeAlphabet {
+    chars: Vec<char>,
+}
+
+impl FpeAlphabet {
+    /// Create an alphabet from a string of distinct characters
+    ///
+    /// The alphabet must have between 2 and 65536 characters.
+    pub fn new(chars: &str) -> Result<Self> {
+        let chars: Vec<char> = chars.chars().collect();
+        if chars.len() < 2 || chars.len() > 65536 {
+            return Err(Error::bad_parameter("Invalid FPE alphabet size"));
+        }
+        for (i, c) in chars.iter().enumerate() {
+            if chars[..i].contains(c) {
+                return Err(Error::bad_parameter("FPE alphabet has repeated characters"));
+            }
+        }
+        Ok(Self { chars })
+    }
+
+    /// The decimal digits `0` to `9`
+    #[must_use]
+    pub fn digits() -> Self {
+        Self {
+            chars: "0123456789".chars().collect(),
+        }
+    }
+
+    /// Digits followed by lowercase letters, as used by the NIST radix 36 samples
+    #[must_use]
+    pub fn lowercase_alphanumeric() -> Self {
+        Self {
+            chars: "0123456789abcdefghijklmnopqrstuvwxyz".chars().collect(),
+        }
+    }
+
+    /// Digits followed by uppercase and then lowercase letters
+    #[must_use]
+    pub fn alphanumeric() -> Self {
+        Self {
+            chars: "0123456789ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz"
+                .chars()
+                .collect(),
+        }
+    }
+
+    /// Return the number of characters in the alphabet
+    #[must_use]
+    pub fn radix(&self) -> usize {
+        self.chars.len()
+    }
+
+    /// Convert a string to numerals, failing if it has characters outside the alphabet
+    pub fn to_numerals(&self, input: &str) -> Result<Vec<u32>> {
+        input
+            .chars()
+            .map(|c| {
+                self.chars
+                    .iter()
+                    .position(|&a| a == c)
+                    .map(|p| p as u32)
+                    .ok_or_else(|| fpe_input_error("Input character not in FPE alphabet"))
+            })
+            .collect()
+    }
+
+    /// Convert numerals back to a string
+    pub fn from_numerals(&self, numerals: &[u32]) -> Result<String> {
+        numerals
+            .iter()
+            .map(|&n| {
+                self.chars
+                    .get(n as usize)
+                    .copied()
+                    .ok_or_else(|| fpe_input_error("Numeral out of range for FPE alphabet"))
+            })
+            .collect()
+    }
+
+    /// Return radix^len, the modulus for strings of this length
+    pub fn modulus(&self, len: usize) -> Result<MPI> {
+        radix_pow(self.radix() as u32, len)
+    }
+
+    /// Interpret a string as a number, with the first character most significant
+    pub fn to_mpi(&self, input: &str) -> Result<MPI> {
+        num_radix(&self.to_numerals(input)?, self.radix() as u32)
+    }
+
+    /// Convert a number below radix^len back to a string of exactly `len` characters
+    pub fn from_mpi(&self, x: &MPI, len: usize) -> Result<String> {
+        if x.is_negative()? || *x >= self.modulus(len)? {
+            return Err(fpe_input_error("Value too large for FPE string length"));
+        }
+        self.from_numerals(&str_radix(x, self.radix() as u32, len)?)
+    }
+}
+
+fn radix_pow(radix: u32, len: usize) -> Result<MPI> {
+    let radix = MPI::new_from_u32(radix)?;
+    let mut r = MPI::new_from_u32(1)?;
+    for _ in 0..len {
+        r = r.mp_mul(&radix)?;
+    }
+    Ok(r)
+}
+
+/// NUM_radix: the number represented by numerals, most significant first
+fn num_radix(x: &[u32], radix: u32) -> Result<MPI> {
+    let radix = MPI::new_from_u32(radix)?;
+    let mut r = MPI::new()?;
+    for &n in x {
+        r = r.mp_mul(&radix)?.mp_add_u32(n)?;
+    }
+    Ok(r)
+}
+
+/// STR^m_radix: the m numerals representing x, most significant first
+fn str_radix(x: &MPI, radix: u32, m: usize) -> Result<Vec<u32>> {
+    let radix = MPI::new_from_u32(radix)?;
+    let mut x = x.duplicate()?;
+    let mut out = vec![0; m];
+    for n in out.iter_mut().rev() {
+        let (q, r) = x.divrem(&radix)?;
+        *n = r.to_u32()?;
+        x = q;
+    }
+    Ok(out)
+}
+
+/// (a + y) mod modulus when encrypting, (a - y) mod modulus when decrypting
+fn mod_add_or_sub(a: &MPI, y: &MPI, modulus: &MPI, add: bool) -> Result<MPI> {
+    let (_, y) = y.divrem(modulus)?;
+    let sum = if add {
+        a.mp_add(&y)?
+    } else {
+        a.mp_add(modulus)?.mp_sub(&y)?
+    };
+    Ok(sum.divrem(modulus)?.1)
+}
+
+fn aes_for_key(key: &[u8]) -> Result<BlockCipher> {
+    let name = match key.len() {
+        16 => "AES-128",
+        24 => "AES-192",
+        32 => "AES-256",
+        _ => return Err(Error::bad_parameter("Invalid AES key length")),
+    };
+    let mut cipher = BlockCipher::new(name)?;
+    cipher.set_key(key)?;
+    Ok(cipher)
+}
+
+/// Check the NIST domain size requirement radix^len >= 1000000
+fn check_min_length(radix: u32, len: usize) -> Result<()> {
+    let mut domain = 1u64;
+    for _ in 0..len {
+        domain = domain.saturating_mul(u64::from(radix));
+    }
+    if len < 2 || domain < 1_000_000 {
+        return Err(fpe_input_error("Input too short for FPE"));
+    }
+    Ok(())
+}
+
+/// FF1 format preserving encryption (NIST SP 800-38G)
+///
+/// FF1 encrypts strings over an arbitrary alphabet using AES, keeping
+/// the length and character set, and supports tweaks of any length.
+/// The input must be at least 2 characters long, and long enough that
+/// there are at least one million possible inputs.
+///
+/// # Examples
+///
+/// ```
+/// let key = botan::hex_decode("2B7E151628AED2A6ABF7158809CF4F3C").unwrap();
+/// let ff1 = botan::Ff1::new(&key, botan::FpeAlphabet::digits()).unwrap();
+/// let ctext = ff1.encrypt("0123456789", b"").unwrap();
+/// assert_eq!(ctext, "2433477484");
+/// assert_eq!(ff1.decrypt(&ctext, b"").unwrap(), "0123456789");
+/// ```
+pub struct Ff1 {
+    cipher: BlockCipher,
+    alphabet: FpeAlphabet,
+}
+
+impl Ff1 {
+    /// Create a new FF1 instance using AES with the given key
+    ///
+    /// The key length selects AES-128, AES-192 or AES-256.
+    pub fn new(key: &[u8], alphabet: FpeAlphabet) -> Result<Self> {
+        Ok(Self {
+            cipher: aes_for_key(key)?,
+            alphabet,
+        })
+    }
+
+    /// Return the alphabet
+    #[must_use]
+    pub fn alphabet(&self) -> &FpeAlphabet {
+        &self.alphabet
+    }
+
+    /// Encrypt a string
+    pub fn encrypt(&self, input: &str, tweak: &[u8]) -> Result<String> {
+        let x = self.alphabet.to_numerals(input)?;
+        self.alphabet
+            .from_numerals(&self.encrypt_numerals(&x, tweak)?)
+    }
+
+    /// Decrypt a string
+    pub fn decrypt(&self, input: &str, tweak: &[u8]) -> Result<String> {
+        let x = self.alphabet.to_numerals(input)?;
+        self.alphabet
+            .from_numerals(&self.decrypt_numerals(&x, tweak)?)
+    }
+
+    /// Encrypt a sequence of numerals, each less than the radix
+    pub fn encrypt_numerals(&self, x: &[u32], tweak: &[u8]) -> Result<Vec<u32>> {
+        self.crypt(x, tweak, true)
+    }
+
+    /// Decrypt a sequence of numerals, each less than the radix
+    pub fn decrypt_numerals(&self, x: &[u32], tweak: &[u8]) -> Result<Vec<u32>> {
+        self.crypt(x, tweak, false)
+    }
+
+    fn prf(&self, input: &[u8]) -> Result<[u8; 16]> {
+        // CBC-MAC with a zero IV; the input is always a multiple of the block size
+        let mut y = [0u8; 16];
+        for block in input.chunks(16) {
+            for (y, b) in y.iter_mut().zip(block) {
+                *y ^= b;
+            }
+            self.cipher.encrypt_in_place(&mut y)?;
+        }
+        Ok(y)
+    }
+
+    fn crypt(&self, x: &[u32], tweak: &[u8], encrypt: bool) -> Result<Vec<u32>> {
+        let radix = self.alphabet.radix() as u32;
+        let n = x.len();
+
+        if x.iter().any(|&c| c >= radix) {
+            return Err(fpe_input_error("Numeral out of range for FPE alphabet"));
+        }
+        check_min_length(radix, n)?;
+        let n32 = u32::try_from(n).map_err(|_| fpe_input_error("Input too long for FF1"))?;
+        let t32 =
+            u32::try_from(tweak.len()).map_err(|_| fpe_input_error("Tweak too long for FF1"))?;
+
+        let u = n / 2;
+        let v = n - u;
+        let mut a = x[..u].to_vec();
+        let mut b = x[u..].to_vec();
+
+        let mod_u = radix_pow(radix, u)?;
+        let mod_v = radix_pow(radix, v)?;
+
+        // b = ceil(ceil(v * log2(radix)) / 8), which is the length of radix^v - 1
+        let b_len = mod_v.mp_sub_u32(1)?.byte_count()?;
+        let d = 4 * b_len.div_ceil(4) + 4;
+
+        let mut p = vec![1, 2, 1];
+        p.extend_from_slice(&radix.to_be_bytes()[1..]);
+        p.push(10);
+        p.push(u as u8);
+        p.extend_from_slice(&n32.to_be_bytes());
+        p.extend_from_slice(&t32.to_be_bytes());
+
+        let pad = (16 - (tweak.len() + b_len + 1) % 16) % 16;
+
+        for round in 0..10u8 {
+            let i = if encrypt { round } else { 9 - round };
+            let (m, modulus) = if i % 2 == 0 { (u, &mod_u) } else { (v, &mod_v) };
+            let (input, target) = if encrypt { (&b, &a) } else { (&a, &b) };
+
+            let mut pq = p.clone();
+            pq.extend_from_slice(tweak);
+            pq.resize(pq.len() + pad, 0);
+            pq.push(i);
+            pq.extend_from_slice(&num_radix(input, radix)?.to_bin_padded(b_len)?);
+
+            let r = self.prf(&pq)?;
+
+            let mut s = r.to_vec();
+            let mut j = 1u128;
+            while s.len() < d {
+                let mut block = (u128::from_be_bytes(r) ^ j).to_be_bytes();
+                self.cipher.encrypt_in_place(&mut block)?;
+                s.extend_from_slice(&block);
+                j += 1;
+            }
+            s.truncate(d);
+
+            let y = MPI::new_from_bytes(&s)?;
+            let c = mod_add_or_sub(&num_radix(target, radix)?, &y, modulus, encrypt)?;
+            let c = str_radix(&c, radix, m)?;
+
+            if encrypt {
+                a = mem::replace(&mut b, c);
+            } else {
+                b = mem::replace(&mut a, c);
+            }
+        }
+
+        a.extend_from_slice(&b);
+        Ok(a)
+    }
+}
+
+/// FF3-1 format preserving encryption (NIST SP 800-38G Revision 1)
+///
+/// FF3-1 encrypts strings over an arbitrary alphabet using AES, keeping
+/// the length and character set. The tweak is always 56 bits (7 bytes).
+/// The input must be long enough that there are at least one million
+/// possible inputs, and each half of it must be representable in 96 bits.
+///
+/// # Examples
+///
+/// ```
+/// let key = botan::hex_decode("2DE79D232DF5585D68CE47882AE256D6").unwrap();
+/// let tweak = botan::hex_decode("CBD09280979564").unwrap();
+/// let ff3 = botan::Ff3_1::new(&key, botan::FpeAlphabet::digits()).unwrap();
+/// let ctext = ff3.encrypt("3992520240", &tweak).unwrap();
+/// assert_eq!(ctext, "8901801106");
+/// assert_eq!(ff3.decrypt(&ctext, &tweak).unwrap(), "3992520240");
+/// ```
+pub struct Ff3_1 {
+    cipher: BlockCipher,
+    alphabet: FpeAlphabet,
+}
+
+/// The length of an FF3-1 tweak in bytes
+pub const FF3_1_TWEAK_LEN: usize = 7;
+
+impl Ff3_1 {
+    /// Create a new FF3-1 instance using AES with the given key
+    ///
+    /// The key length selects AES-128, AES-192 or AES-256.
+    pub fn new(key: &[u8], alphabet: FpeAlphabet) -> Result<Self> {
+        // FF3 uses the byte reversed key
+        let mut rev_key = key.to_vec();
+        rev_key.reverse();
+        Ok(Self {
+            cipher: aes_for_key(&rev_key)?,
+            alphabet,
+        })
+    }
+
+    /// Return the alphabet
+    #[must_use]
+    pub fn alphabet(&self) -> &FpeAlphabet {
+        &self.alphabet
+    }
+
+    /// Encrypt a string
+    pub fn encrypt(&self, input: &str, tweak: &[u8]) -> Result<String> {
+        let x = self.alphabet.to_numerals(input)?;
+        self.alphabet
+            .from_numerals(&self.encrypt_numerals(&x, tweak)?)
+    }
+
+    /// Decrypt a string
+    pub fn decrypt(&self, input: &str, tweak: &[u8]) -> Result<String> {
+        let x = self.alphabet.to_numerals(input)?;
+        self.alphabet
+            .from_numerals(&self.decrypt_numerals(&x, tweak)?)
+    }
+
+    /// Encrypt a sequence of numerals, each less than the radix
+    pub fn encrypt_numerals(&self, x: &[u32], tweak: &[u8]) -> Result<Vec<u32>> {
+        let (t_l, t_r) = Self::split_tweak(tweak)?;
+        self.crypt(x, t_l, t_r, true)
+    }
+
+    /// Decrypt a sequence of numerals, each less than the radix
+    pub fn decrypt_numerals(&self, x: &[u32], tweak: &[u8]) -> Result<Vec<u32>> {
+        let (t_l, t_r) = Self::split_tweak(tweak)?;
+        self.crypt(x, t_l, t_r, false)
+    }
+
+    fn split_tweak(tweak: &[u8]) -> Result<([u8; 4], [u8; 4])> {
+        if tweak.len() != FF3_1_TWEAK_LEN {
+            return Err(Error::bad_parameter("FF3-1 tweak must be 7 bytes"));
+        }
+        let t_l = [tweak[0], tweak[1], tweak[2], tweak[3] & 0xF0];
+        let t_r = [tweak[4], tweak[5], tweak[6], tweak[3] << 4];
+        Ok((t_l, t_r))
+    }
+
+    fn crypt(&self, x: &[u32], t_l: [u8; 4], t_r: [u8; 4], encrypt: bool) -> Result<Vec<u32>> {
+        let radix = self.alphabet.radix() as u32;
+        let n = x.len();
+
+        if x.iter().any(|&c| c >= radix) {
+            return Err(fpe_input_error("Numeral out of range for FPE alphabet"));
+        }
+        check_min_length(radix, n)?;
+
+        let u = n.div_ceil(2);
+        let v = n - u;
+
+        let mod_u = radix_pow(radix, u)?;
+        let mod_v = radix_pow(radix, v)?;
+        if mod_u.mp_sub_u32(1)?.bit_count()? > 96 {
+            return Err(fpe_input_error("Input too long for FF3-1"));
+        }
+
+        // All numeral strings are processed in reverse order
+        let mut a: Vec<u32> = x[..u].iter().rev().copied().collect();
+        let mut b: Vec<u32> = x[u..].iter().rev().copied().collect();
+
+        for round in 0..8u8 {
+            let i = if encrypt { round } else { 7 - round };
+            let (m, modulus, w) = if i % 2 == 0 {
+                (u, &mod_u, t_r)
+            } else {
+                (v, &mod_v, t_l)
+            };
+            let (input, target) = if encrypt { (&b, &a) } else { (&a, &b) };
+
+            let mut p = [0u8; 16];
+            p[..4].copy_from_slice(&w);
+            p[3] ^= i;
+            p[4..].copy_from_slice(&num_radix(input, radix)?.to_bin_padded(12)?);
+
+            p.reverse();
+            self.cipher.encrypt_in_place(&mut p)?;
+            p.reverse();
+
+            let y = MPI::new_from_bytes(&p)?;
+            let c = mod_add_or_sub(&num_radix(target, radix)?, &y, modulus, encrypt)?;
+            let c = str_radix(&c, radix, m)?;
+
+            if encrypt {
+                a = mem::replace(&mut b, c);
+            } else {
+                b = mem::replace(&mut a, c);
+            }
+        }
+
+        a.reverse();
+        b.reverse();
+        a.extend_from_slice(&b);
+        Ok(a)
+    }
+}
@@ -0,0 +1,369 @@
+use crate::utils::*;
+
+use crate::{EcGroup, EcPoint, EcScalar, HashFunction, MPI};
+
+/// The 3-isogeny from the curve E' used by the secp256k1 suites to
+/// secp256k1 itself (RFC 9380 Appendix E.1)
+struct Isogeny {
+    a: &'static str,
+    b: &'static str,
+    x_num: [&'static str; 4],
+    x_den: [&'static str; 2],
+    y_num: [&'static str; 4],
+    y_den: [&'static str; 3],
+}
+
+const SECP256K1_ISOGENY: Isogeny = Isogeny {
+    a: "3F8731ABDD661ADCA08A5558F0F5D272E953D363CB6F0E5D405447C01A444533",
+    b: "06EB",
+    x_num: [
+        "8E38E38E38E38E38E38E38E38E38E38E38E38E38E38E38E38E38E38DAAAAA8C7",
+        "07D3D4C80BC321D5B9F315CEA7FD44C5D595D2FC0BF63B92DFFF1044F17C6581",
+        "534C328D23F234E6E2A413DECA25CAECE4506144037C40314ECBD0B53D9DD262",
+        "8E38E38E38E38E38E38E38E38E38E38E38E38E38E38E38E38E38E38DAAAAA88C",
+    ],
+    x_den: [
+        "D35771193D94918A9CA34CCBB7B640DD86CD409542F8487D9FE6B745781EB49B",
+        "EDADC6F64383DC1DF7C4B2D51B54225406D36B641F5E41BBC52A56612A8C6D14",
+    ],
+    y_num: [
+        "4BDA12F684BDA12F684BDA12F684BDA12F684BDA12F684BDA12F684B8E38E23C",
+        "C75E0C32D5CB7C0FA9D0A54B12A0A6D5647AB046D686DA6FDFFC90FC201D71A3",
+        "29A6194691F91A73715209EF6512E576722830A201BE2018A765E85A9ECEE931",
+        "2F684BDA12F684BDA12F684BDA12F684BDA12F684BDA12F684BDA12F38E38D84",
+    ],
+    y_den: [
+        "FFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFEFFFFF93B",
+        "7A06534BB8BDB49FD5E9E6632722C2989467C1BFC8E8D978DFB425D2685C2573",
+        "6484AA716545CA2CF3A70C3FA8FE337E0A3D21162F0D6299A7BF8192BFD2A76F",
+    ],
+};
+
+/// The parameters of a hash to curve suite (RFC 9380 Section 8)
+struct HashToCurveSuite {
+    curve: &'static str,
+    hash: &'static str,
+    /// The SSWU parameter Z is -z
+    z: u32,
+    /// Length in bytes of each hash_to_field output
+    l: usize,
+    isogeny: Option<&'static Isogeny>,
+}
+
+const SUITES: [HashToCurveSuite; 4] = [
+    HashToCurveSuite {
+        curve: "secp256r1",
+        hash: "SHA-256",
+        z: 10,
+        l: 48,
+        isogeny: None,
+    },
+    HashToCurveSuite {
+        curve: "secp384r1",
+        hash: "SHA-384",
+        z: 12,
+        l: 72,
+        isogeny: None,
+    },
+    HashToCurveSuite {
+        curve: "secp521r1",
+        hash: "SHA-512",
+        z: 4,
+        l: 98,
+        isogeny: None,
+    },
+    HashToCurveSuite {
+        curve: "secp256k1",
+        hash: "SHA-256",
+        z: 11,
+        l: 48,
+        isogeny: Some(&SECP256K1_ISOGENY),
+    },
+];
+
+impl HashToCurveSuite {
+    fn for_group(group: &EcGroup) -> Result<&'static Self> {
+        for suite in &SUITES {
+            if let Ok(candidate) = EcGroup::from_name(suite.curve) {
+                if group.equals(&candidate)? {
+                    return Ok(suite);
+                }
+            }
+        }
+
+        Err(Error::with_message(
+            ErrorType::NotImplemented,
+            "Hash to curve is not supported for this group".to_string(),
+        ))
+    }
+}
+
+/// Arithmetic modulo the (odd) prime p
+///
+/// All of the curves supported here have p == 3 mod 4 which makes
+/// square roots easy.
+struct PrimeField {
+    p: MPI,
+    p_minus_2: MPI,
+    sqrt_exp: MPI,
+    legendre_exp: MPI,
+}
+
+impl PrimeField {
+    fn new(p: MPI) -> Result<Self> {
+        let p_minus_2 = p.mp_sub_u32(2)?;
+        let sqrt_exp = p.mp_add_u32(1)?.mp_shr(2)?;
+        let legendre_exp = p.mp_sub_u32(1)?.mp_shr(1)?;
+        Ok(Self {
+            p,
+            p_minus_2,
+            sqrt_exp,
+            legendre_exp,
+        })
+    }
+
+    fn neg_u32(&self, v: u32) -> Result<MPI> {
+        self.p.mp_sub_u32(v)
+    }
+
+    fn add(&self, x: &MPI, y: &MPI) -> Result<MPI> {
+        let r = x.mp_add(y)?;
+        if r >= self.p {
+            r.mp_sub(&self.p)
+        } else {
+            Ok(r)
+        }
+    }
+
+    fn mul(&self, x: &MPI, y: &MPI) -> Result<MPI> {
+        x.mod_mul(y, &self.p)
+    }
+
+    fn square(&self, x: &MPI) -> Result<MPI> {
+        x.mod_mul(x, &self.p)
+    }
+
+    fn neg(&self, x: &MPI) -> Result<MPI> {
+        if x.is_zero()? {
+            Ok(x.clone())
+        } else {
+            self.p.mp_sub(x)
+        }
+    }
+
+    /// Inversion, returning zero for zero
+    fn inv0(&self, x: &MPI) -> Result<MPI> {
+        MPI::powmod(x, &self.p_minus_2, &self.p)
+    }
+
+    fn is_square(&self, x: &MPI) -> Result<bool> {
+        let l = MPI::powmod(x, &self.legendre_exp, &self.p)?;
+        Ok(l.is_zero()? || l == MPI::new_from_u32(1)?)
+    }
+
+    fn sqrt(&self, x: &MPI) -> Result<MPI> {
+        MPI::powmod(x, &self.sqrt_exp, &self.p)
+    }
+
+    /// Evaluate a polynomial with coefficients given lowest degree first
+    fn poly(&self, coeffs: &[&str], x: &MPI, monic: bool) -> Result<MPI> {
+        let mut r = if monic {
+            MPI::new_from_u32(1)?
+        } else {
+            MPI::new()?
+        };
+        for c in coeffs.iter().rev() {
+            r = self.add(&self.mul(&r, x)?, &MPI::from_str_radix(c, 16)?)?;
+        }
+        Ok(r)
+    }
+}
+
+/// The curve the SSWU map targets, which for secp256k1 is an isogenous curve
+struct MapToCurve<'a> {
+    group: &'a EcGroup,
+    suite: &'static HashToCurveSuite,
+    field: PrimeField,
+    a: MPI,
+    b: MPI,
+    z: MPI,
+}
+
+impl<'a> MapToCurve<'a> {
+    fn new(group: &'a EcGroup) -> Result<Self> {
+        let suite = HashToCurveSuite::for_group(group)?;
+        let field = PrimeField::new(group.p()?)?;
+
+        let (a, b) = match suite.isogeny {
+            Some(iso) => (
+                MPI::from_str_radix(iso.a, 16)?,
+                MPI::from_str_radix(iso.b, 16)?,
+            ),
+            None => (group.a()?, group.b()?),
+        };
+        let z = field.neg_u32(suite.z)?;
+
+        Ok(Self {
+            group,
+            suite,
+            field,
+            a,
+            b,
+            z,
+        })
+    }
+
+    fn hash_to_field(&self, msg: &[u8], dst: &[u8], count: usize) -> Result<Vec<MPI>> {
+        let l = self.suite.l;
+        let uniform = expand_message_xmd(self.suite.hash, msg, dst, count * l)?;
+        uniform
+            .chunks(l)
+            .map(|chunk| Ok(MPI::new_from_bytes(chunk)?.divrem(&self.field.p)?.1))
+            .collect()
+    }
+
+    fn curve_rhs(&self, x: &MPI) -> Result<MPI> {
+        let f = &self.field;
+        let x3 = f.mul(&f.square(x)?, x)?;
+        f.add(&f.add(&x3, &f.mul(&self.a, x)?)?, &self.b)
+    }
+
+    /// Simplified Shallue-van de Woestijne-Ulas method (RFC 9380 Section 6.6.2)
+    fn sswu(&self, u: &MPI) -> Result<(MPI, MPI)> {
+        let f = &self.field;
+
+        let zu2 = f.mul(&self.z, &f.square(u)?)?;
+        let tv1 = f.inv0(&f.add(&f.square(&zu2)?, &zu2)?)?;
+
+        let x1 = if tv1.is_zero()? {
+            f.mul(&self.b, &f.inv0(&f.mul(&self.z, &self.a)?)?)?
+        } else {
+            let minus_b_over_a = f.neg(&f.mul(&self.b, &f.inv0(&self.a)?)?)?;
+            f.mul(&minus_b_over_a, &f.add(&MPI::new_from_u32(1)?, &tv1)?)?
+        };
+
+        let gx1 = self.curve_rhs(&x1)?;
+
+        let (x, y) = if f.is_square(&gx1)? {
+            let y1 = f.sqrt(&gx1)?;
+            (x1, y1)
+        } else {
+            let x2 = f.mul(&zu2, &x1)?;
+            let y2 = f.sqrt(&self.curve_rhs(&x2)?)?;
+            (x2, y2)
+        };
+
+        let y = if u.is_odd()? != y.is_odd()? {
+            f.neg(&y)?
+        } else {
+            y
+        };
+
+        Ok((x, y))
+    }
+
+    fn map_to_curve(&self, u: &MPI) -> Result<EcPoint> {
+        let (x, y) = self.sswu(u)?;
+
+        match self.suite.isogeny {
+            None => EcPoint::from_xy(self.group, &x, &y),
+            Some(iso) => {
+                let f = &self.field;
+                let x_num = f.poly(&iso.x_num, &x, false)?;
+                let x_den = f.poly(&iso.x_den, &x, true)?;
+                let y_num = f.poly(&iso.y_num, &x, false)?;
+                let y_den = f.poly(&iso.y_den, &x, true)?;
+
+                if x_den.is_zero()? || y_den.is_zero()? {
+                    // Exceptional case of the isogeny, the kernel maps to the identity
+                    return EcPoint::identity(self.group);
+                }
+
+                let x = f.mul(&x_num, &f.inv0(&x_den)?)?;
+                let y = f.mul(&y, &f.mul(&y_num, &f.inv0(&y_den)?)?)?;
+                EcPoint::from_xy(self.group, &x, &y)
+            }
+        }
+    }
+}
+
+/// Expand a message to a uniformly random byte string (RFC 9380 Section 5.3.1)
+///
+/// This is `expand_message_xmd` from RFC 9380, using a Merkle-Damgard hash
+/// function such as SHA-256. Domain separation tags longer than 255 bytes
+/// are first hashed as described in RFC 9380 Section 5.3.3.
+///
+/// # Examples
+/// ```
+/// let out = botan::expand_message_xmd("SHA-256", b"abc", b"my-protocol-v1", 64).unwrap();
+/// assert_eq!(out.len(), 64);
+/// ```
+pub fn expand_message_xmd<A: crate::HashAlgorithmIdentifier>(
+    hash: A,
+    msg: &[u8],
+    dst: &[u8],
+    len: usize,
+) -> Result<Vec<u8>> {
+    let mut hash = HashFunction::new(hash)?;
+    let b_in_bytes = hash.output_length()?;
+    let s_in_bytes = hash.block_size()?;
+
+    let ell = len.div_ceil(b_in_bytes);
+    if ell > 255 || len > 65535 {
+        return Err(Error::bad_parameter(
+            "Requested output too long for expand_message_xmd",
+        ));
+    }
+
+    let mut dst_prime = if dst.len() > 255 {
+        hash.update(b"H2C-OVERSIZE-DST-")?;
+        hash.update(dst)?;
+        hash.finish()?
+    } else {
+        dst.to_vec()
+    };
+    dst_prime.push(dst_prime.len() as u8);
+
+    hash.update(&vec![0u8; s_in_bytes])?;
+    hash.update(msg)?;
+    hash.update(&(len as u16).to_be_bytes())?;
+    hash.update(&[0])?;
+    hash.update(&dst_prime)?;
+    let b_0 = hash.finish()?;
+
+    let mut output = Vec::with_capacity(ell * b_in_bytes);
+    let mut b_i = vec![0u8; b_in_bytes];
+
+    for i in 1..=ell {
+        let input: Vec<u8> = b_0.iter().zip(b_i.iter()).map(|(x, y)| x ^ y).collect();
+        hash.update(&input)?;
+        hash.update(&[i as u8])?;
+        hash.update(&dst_prime)?;
+        b_i = hash.finish()?;
+        output.extend_from_slice(&b_i);
+    }
+
+    output.truncate(len);
+    Ok(output)
+}
+
+pub(crate) fn hash_to_curve(group: &EcGroup, msg: &[u8], dst: &[u8]) -> Result<EcPoint> {
+    let map = MapToCurve::new(group)?;
+    let u = map.hash_to_field(msg, dst, 2)?;
+    let q0 = map.map_to_curve(&u[0])?;
+    let q1 = map.map_to_curve(&u[1])?;
+    // All supported curves have cofactor 1
+    q0.pt_add(&q1)
+}
+
+pub(crate) fn encode_to_curve(group: &EcGroup, msg: &[u8], dst: &[u8]) -> Result<EcPoint> {
+    let map = MapToCurve::new(group)?;
+    let u = map.hash_to_field(msg, dst, 1)?;
+    map.map_to_curve(&u[0])
+}
+
+pub(crate) fn hash_to_scalar(group: &EcGroup, msg: &[u8], dst: &[u8]) -> Result<EcScalar> {
+    let suite = HashToCurveSuite::for_group(group)?;
+    let uniform = expand_message_xmd(suite.hash, msg, dst, suite.l)?;
+    EcScalar::from_bytes_reduce(group, &uniform)
+}
@@ -26,7 +26,9 @@
 //! [`ErrorType::NotImplemented`] is returned and [`Error::is_function_unavailable`]
 //! returns true. The documentation of each affected item notes the minimum
 //! version of Botan required. [`Version::current`] and
-//! [`Version::supports_version`] report on the library in use.
+//! [`Version::supports_version`] report on the library in use, and
+//! [`Capabilities::probe`] (or the cached `capabilities`) on the algorithms
+//! it was built with.
 
 #![cfg_attr(not(feature = "std"), no_std)]
 
@@ -159,9 +161,12 @@ macro_rules! botan_bool_in_rc {
 }
 
 mod algo;
+mod algo_oid;
+mod algo_parse;
 mod asn1;
 mod bcrypt;
 mod block;
+mod capabilities;
 mod cipher;
 mod ec_group;
 mod ec_point;
@@ -184,9 +189,11 @@ mod x509_crl;
 mod zfec;
 
 pub use algo::*;
+pub use algo_oid::*;
 pub use asn1::*;
 pub use bcrypt::*;
 pub use block::*;
+pub use capabilities::*;
 pub use cipher::*;
 pub use ec_group::*;
 pub use ec_point::*;
@@ -208,11 +215,34 @@ pub use x509_cert::*;
 pub use x509_crl::*;
 pub use zfec::*;
 
+mod aead_stream;
+mod age;
+mod ecies;
+mod ecvrf;
+mod hash_to_curve;
+mod noise;
+mod oprf;
+mod password_hash;
+mod pbenc;
 mod pk_ops_kem;
+mod secret_sharing;
+mod segmented;
 mod spake2p;
-
+mod zkp;
+
+pub use aead_stream::*;
+pub use age::*;
+pub use ecies::*;
+pub use ecvrf::*;
+pub use hash_to_curve::*;
+pub use noise::*;
+pub use oprf::*;
+pub use password_hash::*;
+pub use pbenc::*;
 pub use pk_ops_kem::*;
+pub use secret_sharing::*;
 pub use spake2p::*;
+pub use zkp::*;
 
 #[cfg(feature = "dynamic-loading")]
 mod dynamic_loading;
@@ -126,3 +126,109 @@ pub fn base64_decode(x: &str) -> Result<Vec<u8>> {
         botan_base64_decode(input.as_ptr(), x.len(), out_buf, out_len)
     })
 }
+
+/// Load an object from a string holding either its PEM encoding or its
+/// base64 encoded DER, as accepted when deserializing keys, certificates and CRLs.
+#[cfg(feature = "serde")]
+pub(crate) fn deserialize_pem_or_der<'de, D: serde::Deserializer<'de>, T>(
+    deserializer: D,
+    load_pem: impl FnOnce(&str) -> Result<T>,
+    load_der: impl FnOnce(&[u8]) -> Result<T>,
+) -> core::result::Result<T, D::Error> {
+    use serde::Deserialize;
+
+    let encoded = String::deserialize(deserializer)?;
+    let encoded = encoded.trim();
+
+    let obj = if encoded.starts_with("-----BEGIN ") {
+        load_pem(encoded)
+    } else {
+        base64_decode(encoded).and_then(|der| load_der(&der))
+    };
+
+    obj.map_err(serde::de::Error::custom)
+}
+
+const BASE32_ALPHABET: &[u8; 32] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZ234567";
+
+/// Base32 encode some data, using the RFC 4648 alphabet with padding
+///
+/// # Examples
+///
+/// ```
+/// assert_eq!(botan::base32_encode(b"foobar"), "MZXW6YTBOI======");
+/// ```
+#[must_use]
+pub fn base32_encode(x: &[u8]) -> String {
+    let mut output = String::with_capacity(x.len().div_ceil(5) * 8);
+
+    for chunk in x.chunks(5) {
+        let mut block = [0u8; 8];
+        block[..chunk.len()].copy_from_slice(chunk);
+        let bits = u64::from_be_bytes(block);
+
+        let chars = (chunk.len() * 8).div_ceil(5);
+        for i in 0..8 {
+            if i < chars {
+                let idx = (bits >> (59 - 5 * i)) & 0x1F;
+                output.push(char::from(BASE32_ALPHABET[idx as usize]));
+            } else {
+                output.push('=');
+            }
+        }
+    }
+
+    output
+}
+
+/// Base32 decode some data
+///
+/// The RFC 4648 alphabet is used. Padding is optional, and lowercase
+/// letters are accepted, since secrets for authenticator apps are often
+/// written that way. The unused bits of the final character must be zero.
+///
+/// # Examples
+///
+/// ```
+/// assert_eq!(botan::base32_decode("MZXW6YTBOI======").unwrap(), b"foobar");
+/// assert_eq!(botan::base32_decode("mzxw6ytboi").unwrap(), b"foobar");
+/// assert!(botan::base32_decode("MZXW6YTBO").is_err());
+/// assert!(botan::base32_decode("MZXW6YTBOJ======").is_err());
+/// ```
+pub fn base32_decode(x: &str) -> Result<Vec<u8>> {
+    let invalid = || Error::with_message(ErrorType::InvalidInput, "Invalid base32".to_owned());
+
+    let unpadded = x.trim_end_matches('=');
+    if x.len() != unpadded.len() && x.len() % 8 != 0 {
+        return Err(invalid());
+    }
+    // A final partial block must encode a whole number of bytes
+    if matches!(unpadded.len() % 8, 1 | 3 | 6) {
+        return Err(invalid());
+    }
+
+    let mut output = Vec::with_capacity(unpadded.len() * 5 / 8);
+    let mut bits = 0u32;
+    let mut nbits = 0;
+
+    for c in unpadded.bytes() {
+        let v = match c.to_ascii_uppercase() {
+            c @ b'A'..=b'Z' => c - b'A',
+            c @ b'2'..=b'7' => c - b'2' + 26,
+            _ => return Err(invalid()),
+        };
+        bits = (bits << 5) | u32::from(v);
+        nbits += 5;
+        if nbits >= 8 {
+            nbits -= 8;
+            output.push((bits >> nbits) as u8);
+            bits &= (1 << nbits) - 1;
+        }
+    }
+
+    if bits != 0 {
+        return Err(invalid());
+    }
+
+    Ok(output)
+}
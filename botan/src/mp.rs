@@ -57,6 +57,87 @@ impl MPI {
         Ok(mpi)
     }
 
+    /// Crate a new MPI setting value from an array of bytes (little-endian)
+    pub fn new_from_bytes_le(val: &[u8]) -> Result<MPI> {
+        let mut be = val.to_vec();
+        be.reverse();
+        MPI::new_from_bytes(&be)
+    }
+
+    /// Crate a new MPI from a big-endian two's complement encoding
+    ///
+    /// This is the format used for the contents of an ASN.1 INTEGER
+    pub fn new_from_signed_bytes(val: &[u8]) -> Result<MPI> {
+        if val.first().is_none_or(|b| b & 0x80 == 0) {
+            return MPI::new_from_bytes(val);
+        }
+
+        let mut mag = val.to_vec();
+        twos_complement_negate(&mut mag);
+        let mut mpi = MPI::new_from_bytes(&mag)?;
+        mpi.flip_sign()?;
+        Ok(mpi)
+    }
+
+    /// Crate a new MPI by parsing a string in the given radix
+    ///
+    /// The radix must be between 2 and 36; digits past 9 are letters,
+    /// in either case. A leading `-` is accepted for negative values.
+    ///
+    /// # Examples
+    /// ```
+    /// let x = botan::MPI::from_str_radix("-zz", 36).unwrap();
+    /// assert_eq!(x, botan::MPI::new_from_i32(-1295).unwrap());
+    /// assert_eq!(x.to_str_radix(2).unwrap(), "-10100001111");
+    /// ```
+    pub fn from_str_radix(val: &str, radix: u32) -> Result<MPI> {
+        check_radix(radix)?;
+
+        let (negative, digits) = match val.strip_prefix('-') {
+            Some(digits) => (true, digits),
+            None => (false, val),
+        };
+
+        if digits.is_empty() || !digits.chars().all(|c| c.is_digit(radix)) {
+            return Err(Error::with_message(
+                ErrorType::InvalidInput,
+                format!("Invalid base {radix} integer '{val}'"),
+            ));
+        }
+
+        let mut mpi = MPI::new()?;
+
+        if radix == 10 || radix == 16 {
+            let cstr = make_cstr(digits)?;
+            botan_call!(
+                botan_mp_set_from_radix_str,
+                mpi.obj,
+                cstr.as_ptr(),
+                radix as usize
+            )?;
+        } else {
+            let (chunk_digits, _) = radix_chunk(radix);
+            let bytes = digits.as_bytes();
+            let first = bytes.len() % chunk_digits;
+
+            let chunks =
+                core::iter::once(&bytes[..first]).chain(bytes[first..].chunks(chunk_digits));
+            for chunk in chunks.filter(|c| !c.is_empty()) {
+                let mut v = 0u32;
+                for &c in chunk {
+                    v = v * radix + (c as char).to_digit(radix).expect("checked above");
+                }
+                let scale = radix.pow(chunk.len() as u32);
+                mpi = mpi.mp_mul(&MPI::new_from_u32(scale)?)?.mp_add_u32(v)?;
+            }
+        }
+
+        if negative && !mpi.is_zero()? {
+            mpi.flip_sign()?;
+        }
+        Ok(mpi)
+    }
+
     /// Crate a new MPI setting value from a i32
     pub fn new_from_i32(val: i32) -> Result<MPI> {
         let mut mpi = MPI::new()?;
@@ -162,6 +243,50 @@ impl MPI {
         }
     }
 
+    /// Return value of self as a string in the given radix
+    ///
+    /// The radix must be between 2 and 36; digits past 9 are lowercase
+    /// letters. Negative values are prefixed with `-`.
+    pub fn to_str_radix(&self, radix: u32) -> Result<String> {
+        check_radix(radix)?;
+
+        let mut abs = self.duplicate()?;
+        if abs.is_negative()? {
+            abs.flip_sign()?;
+        }
+
+        let mut digits = match radix {
+            10 => abs.to_string()?,
+            16 => abs.to_hex()?.to_ascii_lowercase(),
+            _ => {
+                let (chunk_digits, chunk) = radix_chunk(radix);
+                let chunk = MPI::new_from_u32(chunk)?;
+
+                // Produced least significant digit first
+                let mut digits = Vec::new();
+                while !abs.is_zero()? {
+                    let (q, r) = abs.divrem(&chunk)?;
+                    let mut r = r.to_u32()?;
+                    for _ in 0..chunk_digits {
+                        digits.push(char::from_digit(r % radix, radix).expect("valid digit"));
+                        r /= radix;
+                    }
+                    abs = q;
+                }
+                digits.iter().rev().collect()
+            }
+        };
+
+        let leading_zeros = digits.len() - digits.trim_start_matches('0').len();
+        digits.drain(..leading_zeros);
+        if digits.is_empty() {
+            digits.push('0');
+        } else if self.is_negative()? {
+            digits.insert(0, '-');
+        }
+        Ok(digits)
+    }
+
     /// Return value of self as a byte array (big endian)
     pub fn to_bin(&self) -> Result<Vec<u8>> {
         let bytes = self.byte_count()?;
@@ -170,6 +295,85 @@ impl MPI {
         Ok(output)
     }
 
+    /// Return value of self as a big endian byte array of exactly `len` bytes
+    ///
+    /// Only the magnitude is encoded. Fails if the value does not fit.
+    ///
+    /// # Examples
+    /// ```
+    /// let x = botan::MPI::new_from_u32(0x1234).unwrap();
+    /// assert_eq!(x.to_bin_padded(4).unwrap(), [0, 0, 0x12, 0x34]);
+    /// assert!(x.to_bin_padded(1).is_err());
+    /// ```
+    pub fn to_bin_padded(&self, len: usize) -> Result<Vec<u8>> {
+        let bytes = self.byte_count()?;
+        if bytes > len {
+            return Err(encoding_too_large(len));
+        }
+        let mut output = vec![0; len];
+        botan_call!(
+            botan_mp_to_bin,
+            self.obj,
+            output[len - bytes..].as_mut_ptr()
+        )?;
+        Ok(output)
+    }
+
+    /// Return value of self as a byte array (little endian)
+    pub fn to_bin_le(&self) -> Result<Vec<u8>> {
+        let mut output = self.to_bin()?;
+        output.reverse();
+        Ok(output)
+    }
+
+    /// Return value of self as a little endian byte array of exactly `len` bytes
+    ///
+    /// Only the magnitude is encoded. Fails if the value does not fit.
+    pub fn to_bin_padded_le(&self, len: usize) -> Result<Vec<u8>> {
+        let mut output = self.to_bin_padded(len)?;
+        output.reverse();
+        Ok(output)
+    }
+
+    /// Return value of self as a minimal big endian two's complement encoding
+    ///
+    /// This is the format used for the contents of an ASN.1 INTEGER
+    ///
+    /// # Examples
+    /// ```
+    /// let x = botan::MPI::new_from_i32(-129).unwrap();
+    /// assert_eq!(x.to_signed_bin().unwrap(), [0xFF, 0x7F]);
+    /// assert_eq!(botan::MPI::new_from_signed_bytes(&[0xFF, 0x7F]).unwrap(), x);
+    /// ```
+    pub fn to_signed_bin(&self) -> Result<Vec<u8>> {
+        let mut output = self.to_bin()?;
+
+        if self.is_negative()? {
+            twos_complement_negate(&mut output);
+            if output.first().is_none_or(|b| b & 0x80 == 0) {
+                output.insert(0, 0xFF);
+            }
+        } else if output.first().is_none_or(|b| b & 0x80 != 0) {
+            output.insert(0, 0x00);
+        }
+
+        Ok(output)
+    }
+
+    /// Return value of self as a big endian two's complement encoding of exactly `len` bytes
+    ///
+    /// Fails if the value does not fit.
+    pub fn to_signed_bin_padded(&self, len: usize) -> Result<Vec<u8>> {
+        let minimal = self.to_signed_bin()?;
+        if minimal.len() > len {
+            return Err(encoding_too_large(len));
+        }
+        let fill = if minimal[0] & 0x80 != 0 { 0xFF } else { 0x00 };
+        let mut output = vec![fill; len - minimal.len()];
+        output.extend_from_slice(&minimal);
+        Ok(output)
+    }
+
     /// Return number of significant bits
     pub fn bit_count(&self) -> Result<usize> {
         let mut bits = 0;
@@ -327,6 +531,13 @@ impl MPI {
         botan_call!(botan_mp_rshift, self.obj, self.obj, shift)
     }
 
+    /// Modular multiplication, returning (self * other) mod modulus
+    pub fn mod_mul(&self, other: &MPI, modulus: &MPI) -> Result<MPI> {
+        let r = MPI::new()?;
+        botan_call!(botan_mp_mod_mul, r.obj, self.obj, other.obj, modulus.obj)?;
+        Ok(r)
+    }
+
     /// Division/modulo operator
     pub fn divrem(&self, z: &MPI) -> Result<(MPI, MPI)> {
         let q = MPI::new()?;
@@ -386,6 +597,45 @@ impl MPI {
     }
 }
 
+fn check_radix(radix: u32) -> Result<()> {
+    if !(2..=36).contains(&radix) {
+        return Err(Error::bad_parameter("Radix must be between 2 and 36"));
+    }
+    Ok(())
+}
+
+// The largest number of digits, and the corresponding power of the
+// radix, such that a chunk of digits fits in a u32
+fn radix_chunk(radix: u32) -> (usize, u32) {
+    let mut digits = 1;
+    let mut power = radix;
+    while let Some(next) = power.checked_mul(radix) {
+        digits += 1;
+        power = next;
+    }
+    (digits, power)
+}
+
+fn twos_complement_negate(bytes: &mut [u8]) {
+    for b in bytes.iter_mut() {
+        *b = !*b;
+    }
+    for b in bytes.iter_mut().rev() {
+        let (r, carry) = b.overflowing_add(1);
+        *b = r;
+        if !carry {
+            break;
+        }
+    }
+}
+
+fn encoding_too_large(len: usize) -> Error {
+    Error::with_message(
+        ErrorType::InsufficientBufferSpace,
+        format!("Integer does not fit in {len} bytes"),
+    )
+}
+
 impl PartialOrd for MPI {
     fn partial_cmp(&self, other: &MPI) -> Option<Ordering> {
         Some(self.cmp(other))
@@ -470,6 +720,14 @@ impl Add<&MPI> for &MPI {
     }
 }
 
+impl Add<MPI> for MPI {
+    type Output = MPI;
+
+    fn add(self, other: MPI) -> MPI {
+        self + &other
+    }
+}
+
 impl Add<u32> for MPI {
     type Output = MPI;
 
@@ -520,6 +778,14 @@ impl Sub<&MPI> for &MPI {
     }
 }
 
+impl Sub<MPI> for MPI {
+    type Output = MPI;
+
+    fn sub(self, other: MPI) -> MPI {
+        self - &other
+    }
+}
+
 impl Sub<u32> for MPI {
     type Output = MPI;
 
@@ -570,6 +836,14 @@ impl Mul<&MPI> for &MPI {
     }
 }
 
+impl Mul<MPI> for MPI {
+    type Output = MPI;
+
+    fn mul(self, other: MPI) -> MPI {
+        self * &other
+    }
+}
+
 impl MulAssign<&MPI> for MPI {
     fn mul_assign(&mut self, other: &MPI) {
         self.mp_mul_assign(other)
@@ -587,6 +861,22 @@ impl Div<&MPI> for &MPI {
     }
 }
 
+impl Div<&MPI> for MPI {
+    type Output = MPI;
+
+    fn div(self, other: &MPI) -> MPI {
+        &self / other
+    }
+}
+
+impl Div<MPI> for MPI {
+    type Output = MPI;
+
+    fn div(self, other: MPI) -> MPI {
+        &self / &other
+    }
+}
+
 impl<'a> DivAssign<&'a MPI> for MPI {
     fn div_assign(&mut self, other: &'a MPI) {
         *self = &*self / other;
@@ -602,6 +892,22 @@ impl Rem<&MPI> for &MPI {
     }
 }
 
+impl Rem<&MPI> for MPI {
+    type Output = MPI;
+
+    fn rem(self, other: &MPI) -> MPI {
+        &self % other
+    }
+}
+
+impl Rem<MPI> for MPI {
+    type Output = MPI;
+
+    fn rem(self, other: MPI) -> MPI {
+        &self % &other
+    }
+}
+
 impl RemAssign<&MPI> for MPI {
     fn rem_assign(&mut self, other: &MPI) {
         *self = &*self % other;
@@ -646,3 +952,215 @@ impl Neg for MPI {
         self
     }
 }
+
+/// Serialized as a hex string, with a leading `-` if negative.
+#[cfg(feature = "serde")]
+impl serde::Serialize for MPI {
+    fn serialize<S: serde::Serializer>(
+        &self,
+        serializer: S,
+    ) -> core::result::Result<S::Ok, S::Error> {
+        let hex = self.to_str_radix(16).map_err(serde::ser::Error::custom)?;
+        serializer.serialize_str(&hex)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de> serde::Deserialize<'de> for MPI {
+    fn deserialize<D: serde::Deserializer<'de>>(
+        deserializer: D,
+    ) -> core::result::Result<Self, D::Error> {
+        let hex = <String as serde::Deserialize>::deserialize(deserializer)?;
+        MPI::from_str_radix(&hex, 16).map_err(serde::de::Error::custom)
+    }
+}
+
+#[cfg(feature = "num")]
+impl TryFrom<&num_bigint::BigUint> for MPI {
+    type Error = Error;
+
+    fn try_from(v: &num_bigint::BigUint) -> Result<MPI> {
+        MPI::new_from_bytes(&v.to_bytes_be())
+    }
+}
+
+#[cfg(feature = "num")]
+impl TryFrom<num_bigint::BigUint> for MPI {
+    type Error = Error;
+
+    fn try_from(v: num_bigint::BigUint) -> Result<MPI> {
+        MPI::try_from(&v)
+    }
+}
+
+#[cfg(feature = "num")]
+impl TryFrom<&num_bigint::BigInt> for MPI {
+    type Error = Error;
+
+    fn try_from(v: &num_bigint::BigInt) -> Result<MPI> {
+        let (sign, bytes) = v.to_bytes_be();
+        let mut mpi = MPI::new_from_bytes(&bytes)?;
+        if sign == num_bigint::Sign::Minus {
+            mpi.flip_sign()?;
+        }
+        Ok(mpi)
+    }
+}
+
+#[cfg(feature = "num")]
+impl TryFrom<num_bigint::BigInt> for MPI {
+    type Error = Error;
+
+    fn try_from(v: num_bigint::BigInt) -> Result<MPI> {
+        MPI::try_from(&v)
+    }
+}
+
+#[cfg(feature = "num")]
+impl TryFrom<&MPI> for num_bigint::BigUint {
+    type Error = Error;
+
+    /// Fails if the value is negative
+    fn try_from(v: &MPI) -> Result<num_bigint::BigUint> {
+        if v.is_negative()? {
+            return Err(Error::with_message(
+                ErrorType::ConversionError,
+                "Cannot convert a negative MPI to BigUint".to_string(),
+            ));
+        }
+        Ok(num_bigint::BigUint::from_bytes_be(&v.to_bin()?))
+    }
+}
+
+#[cfg(feature = "num")]
+impl TryFrom<MPI> for num_bigint::BigUint {
+    type Error = Error;
+
+    fn try_from(v: MPI) -> Result<num_bigint::BigUint> {
+        num_bigint::BigUint::try_from(&v)
+    }
+}
+
+#[cfg(feature = "num")]
+impl TryFrom<&MPI> for num_bigint::BigInt {
+    type Error = Error;
+
+    fn try_from(v: &MPI) -> Result<num_bigint::BigInt> {
+        let sign = if v.is_negative()? {
+            num_bigint::Sign::Minus
+        } else {
+            num_bigint::Sign::Plus
+        };
+        Ok(num_bigint::BigInt::from_bytes_be(sign, &v.to_bin()?))
+    }
+}
+
+#[cfg(feature = "num")]
+impl TryFrom<MPI> for num_bigint::BigInt {
+    type Error = Error;
+
+    fn try_from(v: MPI) -> Result<num_bigint::BigInt> {
+        num_bigint::BigInt::try_from(&v)
+    }
+}
+
+#[cfg(feature = "num")]
+impl num_traits::Zero for MPI {
+    fn zero() -> MPI {
+        MPI::new().expect("MPI::new succeeded")
+    }
+
+    fn is_zero(&self) -> bool {
+        MPI::is_zero(self).expect("MPI::is_zero succeeded")
+    }
+}
+
+#[cfg(feature = "num")]
+impl num_traits::One for MPI {
+    fn one() -> MPI {
+        MPI::new_from_u32(1).expect("MPI::new_from_u32 succeeded")
+    }
+}
+
+#[cfg(feature = "num")]
+/// Note that division rounds such that the remainder is never negative,
+/// which differs from the primitive integer types for negative dividends
+impl num_traits::Num for MPI {
+    type FromStrRadixErr = Error;
+
+    fn from_str_radix(s: &str, radix: u32) -> Result<MPI> {
+        MPI::from_str_radix(s, radix)
+    }
+}
+
+#[cfg(feature = "num")]
+impl num_traits::Pow<u32> for &MPI {
+    type Output = MPI;
+
+    fn pow(self, mut exp: u32) -> MPI {
+        let mut base = self.clone();
+        let mut r = <MPI as num_traits::One>::one();
+        while exp > 0 {
+            if exp & 1 == 1 {
+                r *= &base;
+            }
+            exp >>= 1;
+            if exp > 0 {
+                base = &base * &base;
+            }
+        }
+        r
+    }
+}
+
+#[cfg(feature = "num")]
+impl num_traits::Pow<u32> for MPI {
+    type Output = MPI;
+
+    fn pow(self, exp: u32) -> MPI {
+        num_traits::Pow::pow(&self, exp)
+    }
+}
+
+#[cfg(feature = "num")]
+impl num_traits::CheckedAdd for MPI {
+    fn checked_add(&self, v: &MPI) -> Option<MPI> {
+        self.mp_add(v).ok()
+    }
+}
+
+#[cfg(feature = "num")]
+impl num_traits::CheckedSub for MPI {
+    fn checked_sub(&self, v: &MPI) -> Option<MPI> {
+        self.mp_sub(v).ok()
+    }
+}
+
+#[cfg(feature = "num")]
+impl num_traits::CheckedMul for MPI {
+    fn checked_mul(&self, v: &MPI) -> Option<MPI> {
+        self.mp_mul(v).ok()
+    }
+}
+
+#[cfg(feature = "num")]
+impl num_traits::CheckedDiv for MPI {
+    /// Returns `None` if `v` is zero
+    fn checked_div(&self, v: &MPI) -> Option<MPI> {
+        if MPI::is_zero(v).ok()? {
+            return None;
+        }
+        self.divrem(v).ok().map(|(q, _r)| q)
+    }
+}
+
+#[cfg(feature = "num")]
+impl num_traits::CheckedRem for MPI {
+    /// Returns `None` if `v` is zero
+    fn checked_rem(&self, v: &MPI) -> Option<MPI> {
+        if MPI::is_zero(v).ok()? {
+            return None;
+        }
+        self.divrem(v).ok().map(|(_q, r)| r)
+    }
+}
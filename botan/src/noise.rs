@@ -0,0 +1,1107 @@
+use crate::utils::*;
+
+use crate::{
+    BlockCipherAlgorithm, Cipher, CipherAlgorithm, CipherDirection, HashAlgorithm, HashFunction,
+    KeyAgreement, MacAlgorithm, MsgAuthCode, Privkey, RandomNumberGenerator,
+};
+
+/// The maximum size of any Noise message, including all keys and tags
+pub const NOISE_MAX_MESSAGE_LEN: usize = 65535;
+
+const NOISE_TAG_LEN: usize = 16;
+const NOISE_KEY_LEN: usize = 32;
+const NOISE_PSK_LEN: usize = 32;
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+/// A Diffie-Hellman function usable with Noise
+pub enum NoiseDh {
+    /// X25519, named "25519" in protocol names
+    X25519,
+    /// X448, named "448" in protocol names
+    X448,
+}
+
+impl NoiseDh {
+    fn name(self) -> &'static str {
+        match self {
+            Self::X25519 => "25519",
+            Self::X448 => "448",
+        }
+    }
+
+    /// Return the length of public keys and shared secrets
+    #[must_use]
+    pub fn dh_len(self) -> usize {
+        match self {
+            Self::X25519 => 32,
+            Self::X448 => 56,
+        }
+    }
+
+    fn load(self, private_key: &[u8]) -> Result<Privkey> {
+        if private_key.len() != self.dh_len() {
+            return Err(Error::bad_parameter("Invalid Noise private key length"));
+        }
+        match self {
+            Self::X25519 => Privkey::load_x25519(private_key),
+            Self::X448 => Privkey::load_x448(private_key),
+        }
+    }
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+/// An AEAD cipher usable with Noise
+pub enum NoiseCipher {
+    /// ChaCha20Poly1305, named "ChaChaPoly" in protocol names
+    ChaChaPoly,
+    /// AES-256/GCM, named "AESGCM" in protocol names
+    AesGcm,
+}
+
+impl NoiseCipher {
+    fn name(self) -> &'static str {
+        match self {
+            Self::ChaChaPoly => "ChaChaPoly",
+            Self::AesGcm => "AESGCM",
+        }
+    }
+
+    fn algorithm(self) -> CipherAlgorithm {
+        match self {
+            Self::ChaChaPoly => CipherAlgorithm::ChaCha20Poly1305,
+            Self::AesGcm => CipherAlgorithm::Gcm(BlockCipherAlgorithm::Aes256, None),
+        }
+    }
+
+    fn nonce(self, n: u64) -> [u8; 12] {
+        let mut nonce = [0u8; 12];
+        match self {
+            Self::ChaChaPoly => nonce[4..].copy_from_slice(&n.to_le_bytes()),
+            Self::AesGcm => nonce[4..].copy_from_slice(&n.to_be_bytes()),
+        }
+        nonce
+    }
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+/// A hash function usable with Noise
+pub enum NoiseHash {
+    /// SHA-256
+    Sha256,
+    /// SHA-512
+    Sha512,
+    /// BLAKE2s with 256 bit output
+    Blake2s,
+    /// BLAKE2b with 512 bit output
+    Blake2b,
+}
+
+impl NoiseHash {
+    fn name(self) -> &'static str {
+        match self {
+            Self::Sha256 => "SHA256",
+            Self::Sha512 => "SHA512",
+            Self::Blake2s => "BLAKE2s",
+            Self::Blake2b => "BLAKE2b",
+        }
+    }
+
+    fn algorithm(self) -> HashAlgorithm {
+        match self {
+            Self::Sha256 => HashAlgorithm::Sha256,
+            Self::Sha512 => HashAlgorithm::Sha512,
+            Self::Blake2s => HashAlgorithm::Blake2s(256),
+            Self::Blake2b => HashAlgorithm::Blake2b(512),
+        }
+    }
+
+    /// Return the output length of the hash function
+    #[must_use]
+    pub fn hash_len(self) -> usize {
+        match self {
+            Self::Sha256 | Self::Blake2s => 32,
+            Self::Sha512 | Self::Blake2b => 64,
+        }
+    }
+
+    fn hash(self, parts: &[&[u8]]) -> Result<Vec<u8>> {
+        let mut hash = HashFunction::new(self.algorithm())?;
+        for part in parts {
+            hash.update(part)?;
+        }
+        hash.finish()
+    }
+
+    fn hmac(self, key: &[u8], parts: &[&[u8]]) -> Result<Vec<u8>> {
+        let mut mac = MsgAuthCode::new(MacAlgorithm::Hmac(self.algorithm()))?;
+        mac.set_key(key)?;
+        for part in parts {
+            mac.update(part)?;
+        }
+        mac.finish()
+    }
+
+    /// The HKDF function of the Noise specification, returning `outputs` values
+    fn hkdf(self, chaining_key: &[u8], ikm: &[u8], outputs: usize) -> Result<Vec<Vec<u8>>> {
+        let temp_key = self.hmac(chaining_key, &[ikm])?;
+        let mut result: Vec<Vec<u8>> = Vec::with_capacity(outputs);
+        for i in 1..=outputs {
+            let counter = [i as u8];
+            let prev = result.last().map(|v| v.as_slice()).unwrap_or_default();
+            result.push(self.hmac(&temp_key, &[prev, &counter])?);
+        }
+        Ok(result)
+    }
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+/// One of the fundamental interactive Noise handshake patterns
+pub enum NoisePattern {
+    /// No static keys
+    Nn,
+    /// The responder's static key is known to the initiator in advance
+    Nk,
+    /// Static keys for both parties are transmitted during the handshake
+    Xx,
+    /// The initiator's static key is transmitted immediately; the
+    /// responder's static key is known to the initiator in advance
+    Ik,
+    /// Both static keys are known to the peer in advance
+    Kk,
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+enum Token {
+    E,
+    S,
+    Ee,
+    Es,
+    Se,
+    Ss,
+    Psk,
+}
+
+impl NoisePattern {
+    fn name(self) -> &'static str {
+        match self {
+            Self::Nn => "NN",
+            Self::Nk => "NK",
+            Self::Xx => "XX",
+            Self::Ik => "IK",
+            Self::Kk => "KK",
+        }
+    }
+
+    fn from_name(name: &str) -> Option<Self> {
+        Some(match name {
+            "NN" => Self::Nn,
+            "NK" => Self::Nk,
+            "XX" => Self::Xx,
+            "IK" => Self::Ik,
+            "KK" => Self::Kk,
+            _ => return None,
+        })
+    }
+
+    /// The initiator and responder pre-messages
+    fn premessages(self) -> (&'static [Token], &'static [Token]) {
+        match self {
+            Self::Nn | Self::Xx => (&[], &[]),
+            Self::Nk | Self::Ik => (&[], &[Token::S]),
+            Self::Kk => (&[Token::S], &[Token::S]),
+        }
+    }
+
+    fn messages(self) -> Vec<Vec<Token>> {
+        use Token::*;
+        match self {
+            Self::Nn => vec![vec![E], vec![E, Ee]],
+            Self::Nk => vec![vec![E, Es], vec![E, Ee]],
+            Self::Xx => vec![vec![E], vec![E, Ee, S, Es], vec![S, Se]],
+            Self::Ik => vec![vec![E, Es, S, Ss], vec![E, Ee, Se]],
+            Self::Kk => vec![vec![E, Es, Ss], vec![E, Ee, Se]],
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+/// A complete Noise protocol: a handshake pattern, optionally modified
+/// with pre-shared keys, plus the DH, cipher, and hash functions
+///
+/// # Examples
+///
+/// ```
+/// let params = botan::NoiseParams::from_name("Noise_XXpsk3_25519_ChaChaPoly_BLAKE2s").unwrap();
+/// assert_eq!(params.pattern(), botan::NoisePattern::Xx);
+/// assert_eq!(params.psk_positions(), &[3]);
+/// assert_eq!(params.name(), "Noise_XXpsk3_25519_ChaChaPoly_BLAKE2s");
+/// ```
+pub struct NoiseParams {
+    pattern: NoisePattern,
+    psk_positions: Vec<usize>,
+    dh: NoiseDh,
+    cipher: NoiseCipher,
+    hash: NoiseHash,
+}
+
+impl NoiseParams {
+    /// Create Noise parameters without any pre-shared keys
+    #[must_use]
+    pub fn new(pattern: NoisePattern, dh: NoiseDh, cipher: NoiseCipher, hash: NoiseHash) -> Self {
+        Self {
+            pattern,
+            psk_positions: Vec::new(),
+            dh,
+            cipher,
+            hash,
+        }
+    }
+
+    /// Add a `pskN` modifier to the handshake pattern
+    ///
+    /// `psk0` mixes a pre-shared key at the start of the first message, and
+    /// `pskN` for N >= 1 at the end of the Nth message.
+    pub fn with_psk(mut self, position: usize) -> Result<Self> {
+        if position > self.pattern.messages().len() {
+            return Err(Error::bad_parameter(
+                "PSK position exceeds the number of handshake messages",
+            ));
+        }
+        if self.psk_positions.contains(&position) {
+            return Err(Error::bad_parameter("Duplicate PSK position"));
+        }
+        self.psk_positions.push(position);
+        Ok(self)
+    }
+
+    /// Parse a protocol name such as `Noise_IKpsk2_448_AESGCM_SHA512`
+    pub fn from_name(name: &str) -> Result<Self> {
+        let invalid = || {
+            Error::with_message(
+                ErrorType::BadParameter,
+                format!("Unsupported Noise protocol name '{name}'"),
+            )
+        };
+
+        let parts: Vec<&str> = name.split('_').collect();
+        if parts.len() != 5 || parts[0] != "Noise" {
+            return Err(invalid());
+        }
+
+        let split = parts[1]
+            .find(|c: char| !c.is_ascii_uppercase())
+            .unwrap_or(parts[1].len());
+        let (base, modifiers) = parts[1].split_at(split);
+
+        let pattern = NoisePattern::from_name(base).ok_or_else(invalid)?;
+
+        let dh = match parts[2] {
+            "25519" => NoiseDh::X25519,
+            "448" => NoiseDh::X448,
+            _ => return Err(invalid()),
+        };
+
+        let cipher = match parts[3] {
+            "ChaChaPoly" => NoiseCipher::ChaChaPoly,
+            "AESGCM" => NoiseCipher::AesGcm,
+            _ => return Err(invalid()),
+        };
+
+        let hash = match parts[4] {
+            "SHA256" => NoiseHash::Sha256,
+            "SHA512" => NoiseHash::Sha512,
+            "BLAKE2s" => NoiseHash::Blake2s,
+            "BLAKE2b" => NoiseHash::Blake2b,
+            _ => return Err(invalid()),
+        };
+
+        let mut params = Self::new(pattern, dh, cipher, hash);
+
+        if !modifiers.is_empty() {
+            for modifier in modifiers.split('+') {
+                let position = modifier
+                    .strip_prefix("psk")
+                    .filter(|n| !n.is_empty() && n.bytes().all(|b| b.is_ascii_digit()))
+                    .and_then(|n| n.parse::<usize>().ok())
+                    .ok_or_else(invalid)?;
+                params = params.with_psk(position).map_err(|_| invalid())?;
+            }
+        }
+
+        Ok(params)
+    }
+
+    /// Return the protocol name
+    #[must_use]
+    pub fn name(&self) -> String {
+        let modifiers = self
+            .psk_positions
+            .iter()
+            .map(|p| format!("psk{p}"))
+            .collect::<Vec<_>>()
+            .join("+");
+
+        format!(
+            "Noise_{}{}_{}_{}_{}",
+            self.pattern.name(),
+            modifiers,
+            self.dh.name(),
+            self.cipher.name(),
+            self.hash.name()
+        )
+    }
+
+    /// Return the base handshake pattern
+    #[must_use]
+    pub fn pattern(&self) -> NoisePattern {
+        self.pattern
+    }
+
+    /// Return the positions of the `pskN` modifiers
+    #[must_use]
+    pub fn psk_positions(&self) -> &[usize] {
+        &self.psk_positions
+    }
+
+    /// Return the DH function
+    #[must_use]
+    pub fn dh(&self) -> NoiseDh {
+        self.dh
+    }
+
+    /// Return the cipher
+    #[must_use]
+    pub fn cipher(&self) -> NoiseCipher {
+        self.cipher
+    }
+
+    /// Return the hash function
+    #[must_use]
+    pub fn hash(&self) -> NoiseHash {
+        self.hash
+    }
+
+    /// The message patterns, with the PSK modifiers applied
+    fn messages(&self) -> Vec<Vec<Token>> {
+        let mut messages = self.pattern.messages();
+        for position in &self.psk_positions {
+            match position {
+                0 => messages[0].insert(0, Token::Psk),
+                n => messages[n - 1].push(Token::Psk),
+            }
+        }
+        messages
+    }
+}
+
+/// A Diffie-Hellman key pair for use with Noise
+pub struct NoiseKeypair {
+    dh: NoiseDh,
+    private_key: Vec<u8>,
+    public_key: Vec<u8>,
+    key: Privkey,
+}
+
+impl core::fmt::Debug for NoiseKeypair {
+    fn fmt(&self, f: &mut core::fmt::Formatter) -> core::fmt::Result {
+        f.debug_struct("NoiseKeypair")
+            .field("dh", &self.dh)
+            .field("public_key", &self.public_key)
+            .finish_non_exhaustive()
+    }
+}
+
+impl NoiseKeypair {
+    /// Generate a new random key pair
+    pub fn generate(dh: NoiseDh, rng: &mut RandomNumberGenerator) -> Result<Self> {
+        Self::from_private_key(dh, &rng.read(dh.dh_len())?)
+    }
+
+    /// Load a key pair from the raw private key
+    pub fn from_private_key(dh: NoiseDh, private_key: &[u8]) -> Result<Self> {
+        let key = dh.load(private_key)?;
+        let public_key = key.key_agreement_key()?;
+        Ok(Self {
+            dh,
+            private_key: private_key.to_vec(),
+            public_key,
+            key,
+        })
+    }
+
+    /// Return the raw private key
+    #[must_use]
+    pub fn private_key(&self) -> &[u8] {
+        &self.private_key
+    }
+
+    /// Return the raw public key
+    #[must_use]
+    pub fn public_key(&self) -> &[u8] {
+        &self.public_key
+    }
+
+    fn dh(&self, public_key: &[u8]) -> Result<Vec<u8>> {
+        let mut ka = KeyAgreement::new(&self.key, "Raw")?;
+        ka.agree(0, public_key, &[])
+    }
+}
+
+/// A Noise CipherState: an AEAD key and nonce counter
+///
+/// After a handshake completes, one of these is used for each direction of
+/// the transport.
+pub struct NoiseCipherState {
+    cipher: NoiseCipher,
+    key: Option<Vec<u8>>,
+    nonce: u64,
+    enc: Cipher,
+    dec: Cipher,
+}
+
+impl core::fmt::Debug for NoiseCipherState {
+    fn fmt(&self, f: &mut core::fmt::Formatter) -> core::fmt::Result {
+        f.debug_struct("NoiseCipherState")
+            .field("cipher", &self.cipher)
+            .field("has_key", &self.key.is_some())
+            .field("nonce", &self.nonce)
+            .finish_non_exhaustive()
+    }
+}
+
+impl NoiseCipherState {
+    /// Create a cipher state with no key
+    ///
+    /// Until a key is set, encryption and decryption return the input unchanged.
+    pub fn new(cipher: NoiseCipher) -> Result<Self> {
+        Ok(Self {
+            cipher,
+            key: None,
+            nonce: 0,
+            enc: Cipher::new(cipher.algorithm(), CipherDirection::Encrypt)?,
+            dec: Cipher::new(cipher.algorithm(), CipherDirection::Decrypt)?,
+        })
+    }
+
+    /// Set the key, and reset the nonce to zero
+    pub fn set_key(&mut self, key: &[u8]) -> Result<()> {
+        if key.len() != NOISE_KEY_LEN {
+            return Err(Error::bad_parameter("Noise cipher keys must be 32 bytes"));
+        }
+        self.enc.set_key(key)?;
+        self.dec.set_key(key)?;
+        self.key = Some(key.to_vec());
+        self.nonce = 0;
+        Ok(())
+    }
+
+    /// Return true if a key has been set
+    #[must_use]
+    pub fn has_key(&self) -> bool {
+        self.key.is_some()
+    }
+
+    /// Return the nonce which will be used for the next message
+    #[must_use]
+    pub fn nonce(&self) -> u64 {
+        self.nonce
+    }
+
+    /// Set the nonce, for transports where messages may be lost or reordered
+    pub fn set_nonce(&mut self, nonce: u64) {
+        self.nonce = nonce;
+    }
+
+    fn next_nonce(&self) -> Result<[u8; 12]> {
+        // The maximum nonce is reserved for rekeying
+        if self.nonce == u64::MAX {
+            return Err(Error::with_message(
+                ErrorType::InvalidObjectState,
+                "Noise nonce space exhausted".to_owned(),
+            ));
+        }
+        Ok(self.cipher.nonce(self.nonce))
+    }
+
+    /// Encrypt a message with associated data
+    pub fn encrypt_with_ad(&mut self, ad: &[u8], plaintext: &[u8]) -> Result<Vec<u8>> {
+        if !self.has_key() {
+            return Ok(plaintext.to_vec());
+        }
+        let nonce = self.next_nonce()?;
+        self.enc.set_associated_data(ad)?;
+        let ciphertext = self.enc.process(&nonce, plaintext)?;
+        self.nonce += 1;
+        Ok(ciphertext)
+    }
+
+    /// Decrypt a message with associated data
+    ///
+    /// If authentication fails, an error of type
+    /// [`ErrorType::BadAuthCode`](crate::ErrorType::BadAuthCode) is returned
+    /// and the nonce is not advanced.
+    pub fn decrypt_with_ad(&mut self, ad: &[u8], ciphertext: &[u8]) -> Result<Vec<u8>> {
+        if !self.has_key() {
+            return Ok(ciphertext.to_vec());
+        }
+        let nonce = self.next_nonce()?;
+        if ciphertext.len() < NOISE_TAG_LEN {
+            return Err(Error::with_message(
+                ErrorType::BadAuthCode,
+                "Noise ciphertext too short".to_owned(),
+            ));
+        }
+        self.dec.set_associated_data(ad)?;
+        let plaintext = self.dec.process(&nonce, ciphertext)?;
+        self.nonce += 1;
+        Ok(plaintext)
+    }
+
+    /// Replace the key with one derived from the current key
+    ///
+    /// Both parties must rekey at the same point in the message stream.
+    /// The nonce is not changed.
+    pub fn rekey(&mut self) -> Result<()> {
+        if !self.has_key() {
+            return Err(Error::with_message(
+                ErrorType::KeyNotSet,
+                "Cannot rekey a Noise cipher state without a key".to_owned(),
+            ));
+        }
+        self.enc.set_associated_data(&[])?;
+        let new_key = self
+            .enc
+            .process(&self.cipher.nonce(u64::MAX), &[0u8; NOISE_KEY_LEN])?;
+        let nonce = self.nonce;
+        self.set_key(&new_key[..NOISE_KEY_LEN])?;
+        self.nonce = nonce;
+        Ok(())
+    }
+}
+
+#[derive(Debug)]
+struct NoiseSymmetricState {
+    hash: NoiseHash,
+    ck: Vec<u8>,
+    h: Vec<u8>,
+    cs: NoiseCipherState,
+}
+
+impl NoiseSymmetricState {
+    fn new(params: &NoiseParams) -> Result<Self> {
+        let name = params.name();
+        let hash = params.hash;
+
+        let h = if name.len() <= hash.hash_len() {
+            let mut h = name.into_bytes();
+            h.resize(hash.hash_len(), 0);
+            h
+        } else {
+            hash.hash(&[name.as_bytes()])?
+        };
+
+        Ok(Self {
+            hash,
+            ck: h.clone(),
+            h,
+            cs: NoiseCipherState::new(params.cipher)?,
+        })
+    }
+
+    fn mix_key(&mut self, ikm: &[u8]) -> Result<()> {
+        let mut out = self.hash.hkdf(&self.ck, ikm, 2)?;
+        self.cs.set_key(&out[1][..NOISE_KEY_LEN])?;
+        self.ck = out.swap_remove(0);
+        Ok(())
+    }
+
+    fn mix_hash(&mut self, data: &[u8]) -> Result<()> {
+        self.h = self.hash.hash(&[&self.h, data])?;
+        Ok(())
+    }
+
+    fn mix_key_and_hash(&mut self, ikm: &[u8]) -> Result<()> {
+        let mut out = self.hash.hkdf(&self.ck, ikm, 3)?;
+        self.mix_hash(&out[1])?;
+        self.cs.set_key(&out[2][..NOISE_KEY_LEN])?;
+        self.ck = out.swap_remove(0);
+        Ok(())
+    }
+
+    fn encrypt_and_hash(&mut self, plaintext: &[u8]) -> Result<Vec<u8>> {
+        let ciphertext = self.cs.encrypt_with_ad(&self.h, plaintext)?;
+        self.mix_hash(&ciphertext)?;
+        Ok(ciphertext)
+    }
+
+    fn decrypt_and_hash(&mut self, ciphertext: &[u8]) -> Result<Vec<u8>> {
+        let plaintext = self.cs.decrypt_with_ad(&self.h, ciphertext)?;
+        self.mix_hash(ciphertext)?;
+        Ok(plaintext)
+    }
+
+    fn split(&self, cipher: NoiseCipher) -> Result<(NoiseCipherState, NoiseCipherState)> {
+        let out = self.hash.hkdf(&self.ck, &[], 2)?;
+        let mut c1 = NoiseCipherState::new(cipher)?;
+        c1.set_key(&out[0][..NOISE_KEY_LEN])?;
+        let mut c2 = NoiseCipherState::new(cipher)?;
+        c2.set_key(&out[1][..NOISE_KEY_LEN])?;
+        Ok((c1, c2))
+    }
+}
+
+/// Split `len` bytes off the front of a message being parsed
+fn take(rest: &mut &[u8], len: usize) -> Result<Vec<u8>> {
+    if rest.len() < len {
+        return Err(Error::with_message(
+            ErrorType::InvalidInput,
+            "Noise message truncated".to_owned(),
+        ));
+    }
+    let (head, tail) = rest.split_at(len);
+    *rest = tail;
+    Ok(head.to_vec())
+}
+
+#[derive(Eq, PartialEq, Debug, Copy, Clone)]
+/// The role of a party in a Noise handshake
+pub enum NoiseRole {
+    /// The party sending the first handshake message
+    Initiator,
+    /// The party receiving the first handshake message
+    Responder,
+}
+
+/// A Noise handshake in progress
+///
+/// Create the handshake, supply the keys the pattern requires, and then
+/// alternate calls to [`NoiseHandshake::write_message`] and
+/// [`NoiseHandshake::read_message`] until
+/// [`NoiseHandshake::is_finished`] returns true. Then call
+/// [`NoiseHandshake::into_transport`] to obtain the cipher states used to
+/// protect further messages.
+///
+/// If any call fails, the handshake must be abandoned; all further calls
+/// will return an error.
+///
+/// # Examples
+///
+/// ```
+/// let params = botan::NoiseParams::from_name("Noise_NN_25519_ChaChaPoly_SHA256").unwrap();
+/// let mut rng = botan::RandomNumberGenerator::new_system().unwrap();
+///
+/// let mut alice = botan::NoiseHandshake::new(&params, botan::NoiseRole::Initiator, b"prologue").unwrap();
+/// let mut bob = botan::NoiseHandshake::new(&params, botan::NoiseRole::Responder, b"prologue").unwrap();
+///
+/// let msg = alice.write_message(b"", &mut rng).unwrap();
+/// bob.read_message(&msg).unwrap();
+/// let msg = bob.write_message(b"", &mut rng).unwrap();
+/// alice.read_message(&msg).unwrap();
+///
+/// let (mut alice_send, _) = alice.into_transport().unwrap();
+/// let (_, mut bob_recv) = bob.into_transport().unwrap();
+///
+/// let ctext = alice_send.encrypt_with_ad(&[], b"hello").unwrap();
+/// assert_eq!(bob_recv.decrypt_with_ad(&[], &ctext).unwrap(), b"hello");
+/// ```
+#[derive(Debug)]
+pub struct NoiseHandshake {
+    params: NoiseParams,
+    role: NoiseRole,
+    prologue: Vec<u8>,
+    symmetric: Option<NoiseSymmetricState>,
+    s: Option<NoiseKeypair>,
+    e: Option<NoiseKeypair>,
+    rs: Option<Vec<u8>>,
+    re: Option<Vec<u8>>,
+    psks: Vec<Vec<u8>>,
+    psks_used: usize,
+    messages: Vec<Vec<Token>>,
+    message_index: usize,
+    failed: bool,
+}
+
+impl NoiseHandshake {
+    /// Create a new handshake
+    ///
+    /// Both parties must use the same prologue; it is authenticated by the handshake
+    pub fn new(params: &NoiseParams, role: NoiseRole, prologue: &[u8]) -> Result<Self> {
+        Ok(Self {
+            params: params.clone(),
+            role,
+            prologue: prologue.to_vec(),
+            symmetric: None,
+            s: None,
+            e: None,
+            rs: None,
+            re: None,
+            psks: Vec::new(),
+            psks_used: 0,
+            messages: params.messages(),
+            message_index: 0,
+            failed: false,
+        })
+    }
+
+    fn check_not_started(&self) -> Result<()> {
+        if self.symmetric.is_some() {
+            return Err(Error::with_message(
+                ErrorType::InvalidObjectState,
+                "Noise handshake already started".to_owned(),
+            ));
+        }
+        Ok(())
+    }
+
+    fn check_keypair(&self, keypair: &NoiseKeypair) -> Result<()> {
+        if keypair.dh != self.params.dh {
+            return Err(Error::bad_parameter(
+                "Key pair does not match the Noise DH function",
+            ));
+        }
+        Ok(())
+    }
+
+    /// Set the local static key pair
+    pub fn set_local_static(&mut self, keypair: NoiseKeypair) -> Result<()> {
+        self.check_not_started()?;
+        self.check_keypair(&keypair)?;
+        self.s = Some(keypair);
+        Ok(())
+    }
+
+    /// Set the peer's static public key, for patterns where it is known in advance
+    pub fn set_remote_static(&mut self, public_key: &[u8]) -> Result<()> {
+        self.check_not_started()?;
+        if public_key.len() != self.params.dh.dh_len() {
+            return Err(Error::bad_parameter("Invalid Noise public key length"));
+        }
+        self.rs = Some(public_key.to_vec());
+        Ok(())
+    }
+
+    /// Set the local ephemeral key pair
+    ///
+    /// Normally the ephemeral key is generated randomly when needed; this
+    /// exists for testing against fixed test vectors. Reusing an ephemeral
+    /// key is catastrophic for security.
+    pub fn set_local_ephemeral(&mut self, keypair: NoiseKeypair) -> Result<()> {
+        self.check_not_started()?;
+        self.check_keypair(&keypair)?;
+        self.e = Some(keypair);
+        Ok(())
+    }
+
+    /// Add a 32 byte pre-shared key
+    ///
+    /// One key must be added for each PSK modifier; they are used in order.
+    pub fn add_psk(&mut self, psk: &[u8]) -> Result<()> {
+        self.check_not_started()?;
+        if psk.len() != NOISE_PSK_LEN {
+            return Err(Error::bad_parameter("Noise PSKs must be 32 bytes"));
+        }
+        self.psks.push(psk.to_vec());
+        Ok(())
+    }
+
+    /// Return the role of this party
+    #[must_use]
+    pub fn role(&self) -> NoiseRole {
+        self.role
+    }
+
+    /// Return true if all handshake messages have been processed
+    #[must_use]
+    pub fn is_finished(&self) -> bool {
+        self.message_index == self.messages.len()
+    }
+
+    /// Return true if this party is to write the next handshake message
+    #[must_use]
+    pub fn is_my_turn(&self) -> bool {
+        let initiator_turn = self.message_index % 2 == 0;
+        !self.is_finished() && initiator_turn == (self.role == NoiseRole::Initiator)
+    }
+
+    /// Return the peer's static public key, if known
+    #[must_use]
+    pub fn remote_static(&self) -> Option<&[u8]> {
+        self.rs.as_deref()
+    }
+
+    /// Return the handshake hash, which uniquely identifies the session
+    ///
+    /// This is only meaningful once the handshake has finished
+    pub fn handshake_hash(&self) -> Result<Vec<u8>> {
+        match &self.symmetric {
+            Some(symmetric) => Ok(symmetric.h.clone()),
+            None => Err(Error::with_message(
+                ErrorType::InvalidObjectState,
+                "Noise handshake not yet started".to_owned(),
+            )),
+        }
+    }
+
+    fn missing(what: &str) -> Error {
+        Error::with_message(
+            ErrorType::InvalidObjectState,
+            format!("Noise handshake requires {what}"),
+        )
+    }
+
+    fn uses_token(&self, token: Token, initiator: bool) -> bool {
+        self.messages
+            .iter()
+            .enumerate()
+            .any(|(i, m)| (i % 2 == 0) == initiator && m.contains(&token))
+    }
+
+    /// Check that all required keys are present and process the pre-messages
+    fn initialize(&mut self) -> Result<()> {
+        let initiator = self.role == NoiseRole::Initiator;
+        let (init_pre, resp_pre) = self.params.pattern.premessages();
+        let (local_pre, remote_pre) = if initiator {
+            (init_pre, resp_pre)
+        } else {
+            (resp_pre, init_pre)
+        };
+
+        if (local_pre.contains(&Token::S) || self.uses_token(Token::S, initiator))
+            && self.s.is_none()
+        {
+            return Err(Self::missing("a local static key"));
+        }
+        if remote_pre.contains(&Token::S) && self.rs.is_none() {
+            return Err(Self::missing("the remote static key"));
+        }
+        if self.psks.len() != self.params.psk_positions.len() {
+            return Err(Self::missing("one PSK for each PSK modifier"));
+        }
+
+        let mut symmetric = NoiseSymmetricState::new(&self.params)?;
+        symmetric.mix_hash(&self.prologue)?;
+
+        for (pre, local) in [(init_pre, initiator), (resp_pre, !initiator)] {
+            if pre.contains(&Token::S) {
+                let key = if local {
+                    self.s.as_ref().map(|s| s.public_key.clone())
+                } else {
+                    self.rs.clone()
+                };
+                symmetric.mix_hash(&key.expect("checked above"))?;
+            }
+        }
+
+        self.symmetric = Some(symmetric);
+        Ok(())
+    }
+
+    fn start_message(&mut self, writing: bool) -> Result<Vec<Token>> {
+        if self.failed {
+            return Err(Error::with_message(
+                ErrorType::InvalidObjectState,
+                "Noise handshake previously failed".to_owned(),
+            ));
+        }
+        if self.is_finished() {
+            return Err(Error::with_message(
+                ErrorType::InvalidObjectState,
+                "Noise handshake already finished".to_owned(),
+            ));
+        }
+        if self.is_my_turn() != writing {
+            return Err(Error::with_message(
+                ErrorType::InvalidObjectState,
+                "Noise handshake messages processed out of order".to_owned(),
+            ));
+        }
+        if self.symmetric.is_none() {
+            self.initialize()?;
+        }
+        Ok(self.messages[self.message_index].clone())
+    }
+
+    fn has_psk(&self) -> bool {
+        !self.params.psk_positions.is_empty()
+    }
+
+    fn next_psk(&mut self) -> Vec<u8> {
+        let psk = self.psks[self.psks_used].clone();
+        self.psks_used += 1;
+        psk
+    }
+
+    /// Perform the DH for a token, from this party's point of view
+    fn token_dh(&self, token: Token) -> Result<Vec<u8>> {
+        let initiator = self.role == NoiseRole::Initiator;
+
+        // Tokens name the initiator's key first
+        let (local, remote) = match (token, initiator) {
+            (Token::Ee, _) => (&self.e, &self.re),
+            (Token::Ss, _) => (&self.s, &self.rs),
+            (Token::Es, true) | (Token::Se, false) => (&self.e, &self.rs),
+            (Token::Es, false) | (Token::Se, true) => (&self.s, &self.re),
+            _ => unreachable!("not a DH token"),
+        };
+
+        match (local, remote) {
+            (Some(local), Some(remote)) => local.dh(remote),
+            _ => Err(Self::missing("a key which was not provided")),
+        }
+    }
+
+    /// Write the next handshake message, with an optional payload
+    ///
+    /// The payload is encrypted if the handshake has established a key at
+    /// this point; early payloads may have weak or no confidentiality.
+    pub fn write_message(
+        &mut self,
+        payload: &[u8],
+        rng: &mut RandomNumberGenerator,
+    ) -> Result<Vec<u8>> {
+        let tokens = self.start_message(true)?;
+        let result = self.write_tokens(&tokens, payload, rng);
+        self.finish_message(result)
+    }
+
+    fn write_tokens(
+        &mut self,
+        tokens: &[Token],
+        payload: &[u8],
+        rng: &mut RandomNumberGenerator,
+    ) -> Result<Vec<u8>> {
+        let mut out = Vec::new();
+
+        for token in tokens {
+            match token {
+                Token::E => {
+                    if self.e.is_none() {
+                        self.e = Some(NoiseKeypair::generate(self.params.dh, rng)?);
+                    }
+                    let public = self.e.as_ref().expect("set above").public_key.clone();
+                    let has_psk = self.has_psk();
+                    let symmetric = self.symmetric.as_mut().expect("initialized");
+                    symmetric.mix_hash(&public)?;
+                    if has_psk {
+                        symmetric.mix_key(&public)?;
+                    }
+                    out.extend_from_slice(&public);
+                }
+                Token::S => {
+                    let public = self.s.as_ref().expect("checked").public_key.clone();
+                    let symmetric = self.symmetric.as_mut().expect("initialized");
+                    out.extend_from_slice(&symmetric.encrypt_and_hash(&public)?);
+                }
+                Token::Psk => {
+                    let psk = self.next_psk();
+                    let symmetric = self.symmetric.as_mut().expect("initialized");
+                    symmetric.mix_key_and_hash(&psk)?;
+                }
+                dh => {
+                    let shared = self.token_dh(*dh)?;
+                    let symmetric = self.symmetric.as_mut().expect("initialized");
+                    symmetric.mix_key(&shared)?;
+                }
+            }
+        }
+
+        let symmetric = self.symmetric.as_mut().expect("initialized");
+        out.extend_from_slice(&symmetric.encrypt_and_hash(payload)?);
+
+        if out.len() > NOISE_MAX_MESSAGE_LEN {
+            return Err(Error::bad_parameter("Noise message too long"));
+        }
+
+        Ok(out)
+    }
+
+    /// Read the next handshake message, returning the payload
+    pub fn read_message(&mut self, message: &[u8]) -> Result<Vec<u8>> {
+        let tokens = self.start_message(false)?;
+        let result = self.read_tokens(&tokens, message);
+        self.finish_message(result)
+    }
+
+    fn read_tokens(&mut self, tokens: &[Token], message: &[u8]) -> Result<Vec<u8>> {
+        if message.len() > NOISE_MAX_MESSAGE_LEN {
+            return Err(Error::with_message(
+                ErrorType::InvalidInput,
+                "Noise message too long".to_owned(),
+            ));
+        }
+
+        let dh_len = self.params.dh.dh_len();
+        let mut rest = message;
+
+        for token in tokens {
+            match token {
+                Token::E => {
+                    let re = take(&mut rest, dh_len)?;
+                    let has_psk = self.has_psk();
+                    let symmetric = self.symmetric.as_mut().expect("initialized");
+                    symmetric.mix_hash(&re)?;
+                    if has_psk {
+                        symmetric.mix_key(&re)?;
+                    }
+                    self.re = Some(re);
+                }
+                Token::S => {
+                    let symmetric = self.symmetric.as_mut().expect("initialized");
+                    let len = dh_len
+                        + if symmetric.cs.has_key() {
+                            NOISE_TAG_LEN
+                        } else {
+                            0
+                        };
+                    let encrypted = take(&mut rest, len)?;
+                    self.rs = Some(symmetric.decrypt_and_hash(&encrypted)?);
+                }
+                Token::Psk => {
+                    let psk = self.next_psk();
+                    let symmetric = self.symmetric.as_mut().expect("initialized");
+                    symmetric.mix_key_and_hash(&psk)?;
+                }
+                dh => {
+                    let shared = self.token_dh(*dh)?;
+                    let symmetric = self.symmetric.as_mut().expect("initialized");
+                    symmetric.mix_key(&shared)?;
+                }
+            }
+        }
+
+        let symmetric = self.symmetric.as_mut().expect("initialized");
+        symmetric.decrypt_and_hash(rest)
+    }
+
+    fn finish_message(&mut self, result: Result<Vec<u8>>) -> Result<Vec<u8>> {
+        match result {
+            Ok(v) => {
+                self.message_index += 1;
+                Ok(v)
+            }
+            Err(e) => {
+                self.failed = true;
+                Err(e)
+            }
+        }
+    }
+
+    /// Finish the handshake, returning the cipher states for sending and
+    /// receiving transport messages, in that order
+    pub fn into_transport(self) -> Result<(NoiseCipherState, NoiseCipherState)> {
+        if self.failed || !self.is_finished() {
+            return Err(Error::with_message(
+                ErrorType::InvalidObjectState,
+                "Noise handshake not finished".to_owned(),
+            ));
+        }
+
+        let symmetric = self.symmetric.as_ref().expect("initialized");
+        let (c1, c2) = symmetric.split(self.params.cipher)?;
+
+        match self.role {
+            NoiseRole::Initiator => Ok((c1, c2)),
+            NoiseRole::Responder => Ok((c2, c1)),
+        }
+    }
+}
@@ -0,0 +1,703 @@
+//! Oblivious pseudorandom functions (RFC 9497) in the OPRF, VOPRF and
+//! POPRF modes
+
+use crate::utils::*;
+
+use crate::{EcGroup, EcPoint, EcScalar, HashFunction, RandomNumberGenerator};
+
+#[derive(Eq, PartialEq, Debug, Copy, Clone)]
+/// An OPRF ciphersuite from RFC 9497
+pub enum OprfSuite {
+    /// P-256 with SHA-256
+    P256Sha256,
+    /// P-384 with SHA-384
+    P384Sha384,
+    /// P-521 with SHA-512
+    P521Sha512,
+}
+
+impl OprfSuite {
+    fn identifier(self) -> &'static str {
+        match self {
+            Self::P256Sha256 => "P256-SHA256",
+            Self::P384Sha384 => "P384-SHA384",
+            Self::P521Sha512 => "P521-SHA512",
+        }
+    }
+
+    fn curve(self) -> &'static str {
+        match self {
+            Self::P256Sha256 => "secp256r1",
+            Self::P384Sha384 => "secp384r1",
+            Self::P521Sha512 => "secp521r1",
+        }
+    }
+
+    fn hash(self) -> &'static str {
+        match self {
+            Self::P256Sha256 => "SHA-256",
+            Self::P384Sha384 => "SHA-384",
+            Self::P521Sha512 => "SHA-512",
+        }
+    }
+}
+
+#[derive(Eq, PartialEq, Debug, Copy, Clone)]
+/// An OPRF protocol variant from RFC 9497
+pub enum OprfMode {
+    /// The base OPRF protocol
+    Oprf,
+    /// Verifiable OPRF, where the server proves it used its committed key
+    Voprf,
+    /// Partially oblivious OPRF, which is verifiable and additionally
+    /// binds the output to public info known to both client and server
+    Poprf,
+}
+
+impl OprfMode {
+    fn identifier(self) -> u8 {
+        match self {
+            Self::Oprf => 0x00,
+            Self::Voprf => 0x01,
+            Self::Poprf => 0x02,
+        }
+    }
+}
+
+fn append_length_prefixed(out: &mut Vec<u8>, data: &[u8]) -> Result<()> {
+    let len = u16::try_from(data.len())
+        .map_err(|_| Error::bad_parameter("OPRF input too long to be length prefixed"))?;
+    out.extend_from_slice(&len.to_be_bytes());
+    out.extend_from_slice(data);
+    Ok(())
+}
+
+fn invalid_proof() -> Error {
+    Error::with_message(
+        ErrorType::InvalidVerifier,
+        "OPRF proof is invalid".to_string(),
+    )
+}
+
+/// The group operations and encodings of a ciphersuite in some mode
+#[derive(Debug)]
+struct OprfContext {
+    suite: OprfSuite,
+    mode: OprfMode,
+    group: EcGroup,
+    context_string: Vec<u8>,
+    scalar_len: usize,
+    element_len: usize,
+}
+
+impl OprfContext {
+    fn new(suite: OprfSuite, mode: OprfMode) -> Result<Self> {
+        let group = EcGroup::from_name(suite.curve())?;
+        let scalar_len = group.order()?.byte_count()?;
+        let element_len = 1 + group.p()?.byte_count()?;
+
+        let mut context_string = b"OPRFV1-".to_vec();
+        context_string.push(mode.identifier());
+        context_string.push(b'-');
+        context_string.extend_from_slice(suite.identifier().as_bytes());
+
+        Ok(Self {
+            suite,
+            mode,
+            group,
+            context_string,
+            scalar_len,
+            element_len,
+        })
+    }
+
+    fn dst(&self, prefix: &[u8]) -> Vec<u8> {
+        let mut dst = prefix.to_vec();
+        dst.extend_from_slice(&self.context_string);
+        dst
+    }
+
+    fn hash(&self, input: &[u8]) -> Result<Vec<u8>> {
+        let mut hash = HashFunction::new(self.suite.hash())?;
+        hash.update(input)?;
+        hash.finish()
+    }
+
+    fn hash_to_group(&self, input: &[u8]) -> Result<EcPoint> {
+        let pt = self
+            .group
+            .hash_to_curve(input, &self.dst(b"HashToGroup-"))?;
+        if pt.is_identity()? {
+            return Err(Error::bad_parameter("OPRF input maps to the identity"));
+        }
+        Ok(pt)
+    }
+
+    fn hash_to_scalar(&self, input: &[u8]) -> Result<EcScalar> {
+        self.group
+            .hash_to_scalar(input, &self.dst(b"HashToScalar-"))
+    }
+
+    fn serialize_element(&self, pt: &EcPoint) -> Result<Vec<u8>> {
+        pt.to_compressed()
+    }
+
+    fn deserialize_element(&self, bytes: &[u8]) -> Result<EcPoint> {
+        // Only the compressed encoding is accepted, and never the identity
+        if bytes.len() != self.element_len {
+            return Err(Error::with_message(
+                ErrorType::InvalidInput,
+                format!("Invalid length {} for OPRF element", bytes.len()),
+            ));
+        }
+        let pt = EcPoint::from_bytes(&self.group, bytes)?;
+        if pt.is_identity()? {
+            return Err(Error::with_message(
+                ErrorType::InvalidInput,
+                "OPRF element must not be the identity".to_string(),
+            ));
+        }
+        Ok(pt)
+    }
+
+    fn deserialize_scalar(&self, bytes: &[u8]) -> Result<EcScalar> {
+        EcScalar::from_bytes(&self.group, bytes)
+    }
+
+    /// Compute the POPRF tweak scalar for the public info
+    fn info_scalar(&self, info: &[u8]) -> Result<EcScalar> {
+        let mut framed_info = b"Info".to_vec();
+        append_length_prefixed(&mut framed_info, info)?;
+        self.hash_to_scalar(&framed_info)
+    }
+
+    fn check_info(&self, info: &[u8]) -> Result<()> {
+        if self.mode != OprfMode::Poprf && !info.is_empty() {
+            return Err(Error::bad_parameter(
+                "OPRF info is only supported in POPRF mode",
+            ));
+        }
+        Ok(())
+    }
+
+    fn finalize_hash(&self, input: &[u8], info: &[u8], element: &EcPoint) -> Result<Vec<u8>> {
+        let mut hash_input = Vec::new();
+        append_length_prefixed(&mut hash_input, input)?;
+        if self.mode == OprfMode::Poprf {
+            append_length_prefixed(&mut hash_input, info)?;
+        }
+        append_length_prefixed(&mut hash_input, &self.serialize_element(element)?)?;
+        hash_input.extend_from_slice(b"Finalize");
+        self.hash(&hash_input)
+    }
+
+    /// ComputeComposites and ComputeCompositesFast of RFC 9497 Section 2.2
+    fn compute_composites(
+        &self,
+        k: Option<&EcScalar>,
+        b: &EcPoint,
+        c: &[&EcPoint],
+        d: &[&EcPoint],
+        rng: &mut RandomNumberGenerator,
+    ) -> Result<(EcPoint, EcPoint)> {
+        let mut seed_transcript = Vec::new();
+        append_length_prefixed(&mut seed_transcript, &self.serialize_element(b)?)?;
+        append_length_prefixed(&mut seed_transcript, &self.dst(b"Seed-"))?;
+        let seed = self.hash(&seed_transcript)?;
+
+        let mut m = self.group.identity()?;
+        let mut z = self.group.identity()?;
+
+        for (i, (ci, di)) in c.iter().zip(d.iter()).enumerate() {
+            let mut composite_transcript = Vec::new();
+            append_length_prefixed(&mut composite_transcript, &seed)?;
+            composite_transcript.extend_from_slice(&(i as u16).to_be_bytes());
+            append_length_prefixed(&mut composite_transcript, &self.serialize_element(ci)?)?;
+            append_length_prefixed(&mut composite_transcript, &self.serialize_element(di)?)?;
+            composite_transcript.extend_from_slice(b"Composite");
+
+            let weight = self.hash_to_scalar(&composite_transcript)?;
+            m = m.pt_add(&ci.mul(&weight, rng)?)?;
+            if k.is_none() {
+                z = z.pt_add(&di.mul(&weight, rng)?)?;
+            }
+        }
+
+        if let Some(k) = k {
+            z = m.mul(k, rng)?;
+        }
+
+        Ok((m, z))
+    }
+
+    fn challenge(&self, points: [&EcPoint; 5]) -> Result<EcScalar> {
+        let mut transcript = Vec::new();
+        for pt in points {
+            append_length_prefixed(&mut transcript, &self.serialize_element(pt)?)?;
+        }
+        transcript.extend_from_slice(b"Challenge");
+        self.hash_to_scalar(&transcript)
+    }
+
+    /// Prove that `B = k*A` and `D[i] = k*C[i]`
+    fn generate_proof(
+        &self,
+        k: &EcScalar,
+        a: &EcPoint,
+        b: &EcPoint,
+        c: &[&EcPoint],
+        d: &[&EcPoint],
+        rng: &mut RandomNumberGenerator,
+    ) -> Result<Vec<u8>> {
+        let (m, z) = self.compute_composites(Some(k), b, c, d, rng)?;
+
+        let r = EcScalar::random(&self.group, rng)?;
+        let t2 = a.mul(&r, rng)?;
+        let t3 = m.mul(&r, rng)?;
+
+        let challenge = self.challenge([b, &m, &z, &t2, &t3])?;
+        let response = &r - &(&challenge * k);
+
+        let mut proof = challenge.to_bytes()?;
+        proof.extend_from_slice(&response.to_bytes()?);
+        Ok(proof)
+    }
+
+    fn verify_proof(
+        &self,
+        a: &EcPoint,
+        b: &EcPoint,
+        c: &[&EcPoint],
+        d: &[&EcPoint],
+        proof: &[u8],
+        rng: &mut RandomNumberGenerator,
+    ) -> Result<bool> {
+        if proof.len() != 2 * self.scalar_len {
+            return Ok(false);
+        }
+        let (challenge, response) = proof.split_at(self.scalar_len);
+        let (challenge, response) = match (
+            self.deserialize_scalar(challenge),
+            self.deserialize_scalar(response),
+        ) {
+            (Ok(c), Ok(s)) => (c, s),
+            _ => return Ok(false),
+        };
+
+        let (m, z) = self.compute_composites(None, b, c, d, rng)?;
+        let t2 = a.mul(&response, rng)?.pt_add(&b.mul(&challenge, rng)?)?;
+        let t3 = m.mul(&response, rng)?.pt_add(&z.mul(&challenge, rng)?)?;
+
+        let expected = self.challenge([b, &m, &z, &t2, &t3])?;
+        expected.is_equal(&challenge)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+/// The server's response to a batch of blinded elements
+///
+/// Contains the evaluated elements, in the same order as the blinded
+/// elements, and in the verifiable modes a proof covering the whole batch.
+pub struct OprfEvaluation {
+    elements: Vec<Vec<u8>>,
+    proof: Option<Vec<u8>>,
+}
+
+impl OprfEvaluation {
+    /// Create an evaluation from its serialized elements and proof
+    pub fn new(elements: Vec<Vec<u8>>, proof: Option<Vec<u8>>) -> Self {
+        Self { elements, proof }
+    }
+
+    /// Return the serialized evaluated elements
+    pub fn elements(&self) -> &[Vec<u8>] {
+        &self.elements
+    }
+
+    /// Return the serialized proof, if any
+    pub fn proof(&self) -> Option<&[u8]> {
+        self.proof.as_deref()
+    }
+}
+
+#[derive(Debug)]
+/// The server side of an oblivious pseudorandom function (RFC 9497)
+///
+/// # Examples
+/// ```
+/// let mut rng = botan::RandomNumberGenerator::new().unwrap();
+/// let suite = botan::OprfSuite::P256Sha256;
+/// let mode = botan::OprfMode::Voprf;
+///
+/// let server = botan::OprfServer::generate(suite, mode, &mut rng).unwrap();
+/// let client = botan::OprfClient::new(suite, mode, Some(&server.public_key().unwrap())).unwrap();
+///
+/// let blinded = client.blind(b"password", b"", &mut rng).unwrap();
+/// let evaluation = server
+///     .blind_evaluate(&[blinded.blinded_element()], b"", &mut rng)
+///     .unwrap();
+/// let output = client.finalize(&[blinded], &evaluation, &mut rng).unwrap();
+///
+/// assert_eq!(output[0], server.evaluate(b"password", b"", &mut rng).unwrap());
+/// ```
+pub struct OprfServer {
+    ctx: OprfContext,
+    secret: EcScalar,
+    public: EcPoint,
+}
+
+impl OprfServer {
+    /// Create a server from a serialized private key
+    pub fn new(
+        suite: OprfSuite,
+        mode: OprfMode,
+        secret_key: &[u8],
+        rng: &mut RandomNumberGenerator,
+    ) -> Result<Self> {
+        let ctx = OprfContext::new(suite, mode)?;
+        let secret = ctx.deserialize_scalar(secret_key)?;
+        Self::from_scalar(ctx, secret, rng)
+    }
+
+    /// Create a server with a new random private key
+    pub fn generate(
+        suite: OprfSuite,
+        mode: OprfMode,
+        rng: &mut RandomNumberGenerator,
+    ) -> Result<Self> {
+        let ctx = OprfContext::new(suite, mode)?;
+        let secret = EcScalar::random(&ctx.group, rng)?;
+        Self::from_scalar(ctx, secret, rng)
+    }
+
+    /// Create a server with a private key derived from a seed (`DeriveKeyPair`)
+    ///
+    /// The seed must be uniformly random; `info` allows deriving multiple
+    /// independent keys from the same seed.
+    pub fn derive_key(
+        suite: OprfSuite,
+        mode: OprfMode,
+        seed: &[u8],
+        info: &[u8],
+        rng: &mut RandomNumberGenerator,
+    ) -> Result<Self> {
+        let ctx = OprfContext::new(suite, mode)?;
+
+        let mut derive_input = seed.to_vec();
+        append_length_prefixed(&mut derive_input, info)?;
+        let dst = ctx.dst(b"DeriveKeyPair");
+
+        for counter in 0..=255u8 {
+            let mut input = derive_input.clone();
+            input.push(counter);
+            let secret = ctx.group.hash_to_scalar(&input, &dst)?;
+            if !secret.is_zero()? {
+                return Self::from_scalar(ctx, secret, rng);
+            }
+        }
+
+        Err(Error::with_message(
+            ErrorType::InternalError,
+            "OPRF key derivation failed".to_string(),
+        ))
+    }
+
+    fn from_scalar(
+        ctx: OprfContext,
+        secret: EcScalar,
+        rng: &mut RandomNumberGenerator,
+    ) -> Result<Self> {
+        if secret.is_zero()? {
+            return Err(Error::bad_parameter("Invalid OPRF private key"));
+        }
+        let public = ctx.group.generator()?.mul(&secret, rng)?;
+        Ok(Self {
+            ctx,
+            secret,
+            public,
+        })
+    }
+
+    /// Return the ciphersuite
+    pub fn suite(&self) -> OprfSuite {
+        self.ctx.suite
+    }
+
+    /// Return the protocol mode
+    pub fn mode(&self) -> OprfMode {
+        self.ctx.mode
+    }
+
+    /// Return the serialized private key
+    pub fn secret_key(&self) -> Result<Vec<u8>> {
+        self.secret.to_bytes()
+    }
+
+    /// Return the serialized public key
+    ///
+    /// In the verifiable modes the client needs this to check proofs.
+    pub fn public_key(&self) -> Result<Vec<u8>> {
+        self.ctx.serialize_element(&self.public)
+    }
+
+    /// Evaluate a batch of blinded elements received from a client (`BlindEvaluate`)
+    ///
+    /// In the verifiable modes a single proof is produced for the whole
+    /// batch. The `info` must be empty unless in POPRF mode.
+    pub fn blind_evaluate<B: AsRef<[u8]>>(
+        &self,
+        blinded_elements: &[B],
+        info: &[u8],
+        rng: &mut RandomNumberGenerator,
+    ) -> Result<OprfEvaluation> {
+        let ctx = &self.ctx;
+        ctx.check_info(info)?;
+        if blinded_elements.is_empty() {
+            return Err(Error::bad_parameter("OPRF batch must not be empty"));
+        }
+
+        let blinded = blinded_elements
+            .iter()
+            .map(|b| ctx.deserialize_element(b.as_ref()))
+            .collect::<Result<Vec<_>>>()?;
+
+        let g = ctx.group.generator()?;
+        let blinded = blinded.iter().collect::<Vec<_>>();
+
+        let (evaluated, proof) = match ctx.mode {
+            OprfMode::Oprf => (self.evaluate_elements(&self.secret, &blinded, rng)?, None),
+            OprfMode::Voprf => {
+                let evaluated = self.evaluate_elements(&self.secret, &blinded, rng)?;
+                let evaluated_refs = evaluated.iter().collect::<Vec<_>>();
+                let proof = ctx.generate_proof(
+                    &self.secret,
+                    &g,
+                    &self.public,
+                    &blinded,
+                    &evaluated_refs,
+                    rng,
+                )?;
+                (evaluated, Some(proof))
+            }
+            OprfMode::Poprf => {
+                let t = self.tweaked_secret(info)?;
+                let evaluated = self.evaluate_elements(&t.invert()?, &blinded, rng)?;
+                let evaluated_refs = evaluated.iter().collect::<Vec<_>>();
+                let tweaked_key = g.mul(&t, rng)?;
+                let proof =
+                    ctx.generate_proof(&t, &g, &tweaked_key, &evaluated_refs, &blinded, rng)?;
+                (evaluated, Some(proof))
+            }
+        };
+
+        let elements = evaluated
+            .iter()
+            .map(|e| ctx.serialize_element(e))
+            .collect::<Result<Vec<_>>>()?;
+        Ok(OprfEvaluation::new(elements, proof))
+    }
+
+    fn evaluate_elements(
+        &self,
+        k: &EcScalar,
+        elements: &[&EcPoint],
+        rng: &mut RandomNumberGenerator,
+    ) -> Result<Vec<EcPoint>> {
+        elements.iter().map(|e| e.mul(k, rng)).collect()
+    }
+
+    /// Directly compute the PRF output for an input (`Evaluate`)
+    ///
+    /// This gives the same result the client computes by running the
+    /// protocol, without any blinding.
+    pub fn evaluate(
+        &self,
+        input: &[u8],
+        info: &[u8],
+        rng: &mut RandomNumberGenerator,
+    ) -> Result<Vec<u8>> {
+        let ctx = &self.ctx;
+        ctx.check_info(info)?;
+        let input_element = ctx.hash_to_group(input)?;
+
+        let evaluated = match ctx.mode {
+            OprfMode::Oprf | OprfMode::Voprf => input_element.mul(&self.secret, rng)?,
+            OprfMode::Poprf => input_element.mul(&self.tweaked_secret(info)?.invert()?, rng)?,
+        };
+
+        ctx.finalize_hash(input, info, &evaluated)
+    }
+
+    fn tweaked_secret(&self, info: &[u8]) -> Result<EcScalar> {
+        let t = &self.secret + &self.ctx.info_scalar(info)?;
+        if t.is_zero()? {
+            return Err(Error::bad_parameter("Invalid OPRF info for this key"));
+        }
+        Ok(t)
+    }
+}
+
+#[derive(Debug)]
+/// A client input which has been blinded, awaiting evaluation by the server
+pub struct OprfBlindedInput {
+    input: Vec<u8>,
+    info: Vec<u8>,
+    blind: EcScalar,
+    blinded: EcPoint,
+    blinded_bytes: Vec<u8>,
+}
+
+impl OprfBlindedInput {
+    /// Return the serialized blinded element, which is sent to the server
+    pub fn blinded_element(&self) -> &[u8] {
+        &self.blinded_bytes
+    }
+}
+
+#[derive(Debug)]
+/// The client side of an oblivious pseudorandom function (RFC 9497)
+///
+/// See [`OprfServer`] for an example
+pub struct OprfClient {
+    ctx: OprfContext,
+    server_public: Option<EcPoint>,
+}
+
+impl OprfClient {
+    /// Create a client
+    ///
+    /// The server's public key is required in the verifiable modes (VOPRF
+    /// and POPRF) and ignored in the base OPRF mode.
+    pub fn new(suite: OprfSuite, mode: OprfMode, server_public_key: Option<&[u8]>) -> Result<Self> {
+        let ctx = OprfContext::new(suite, mode)?;
+
+        let server_public = match (mode, server_public_key) {
+            (OprfMode::Oprf, _) => None,
+            (_, Some(key)) => Some(ctx.deserialize_element(key)?),
+            (_, None) => {
+                return Err(Error::bad_parameter(
+                    "Verifiable OPRF modes require the server public key",
+                ));
+            }
+        };
+
+        Ok(Self { ctx, server_public })
+    }
+
+    /// Return the ciphersuite
+    pub fn suite(&self) -> OprfSuite {
+        self.ctx.suite
+    }
+
+    /// Return the protocol mode
+    pub fn mode(&self) -> OprfMode {
+        self.ctx.mode
+    }
+
+    /// Blind an input (`Blind`)
+    ///
+    /// The `info` must be empty unless in POPRF mode.
+    pub fn blind(
+        &self,
+        input: &[u8],
+        info: &[u8],
+        rng: &mut RandomNumberGenerator,
+    ) -> Result<OprfBlindedInput> {
+        let blind = EcScalar::random(&self.ctx.group, rng)?;
+        self.blind_with(input, info, &blind, rng)
+    }
+
+    /// Blind an input using the specified blinding scalar
+    ///
+    /// This is intended for testing against known answers; the blind
+    /// must be a fresh random nonzero scalar for every input, otherwise
+    /// the server can link the blinded elements.
+    pub fn blind_with(
+        &self,
+        input: &[u8],
+        info: &[u8],
+        blind: &EcScalar,
+        rng: &mut RandomNumberGenerator,
+    ) -> Result<OprfBlindedInput> {
+        let ctx = &self.ctx;
+        ctx.check_info(info)?;
+        if blind.is_zero()? {
+            return Err(Error::bad_parameter("OPRF blind must not be zero"));
+        }
+
+        let blinded = ctx.hash_to_group(input)?.mul(blind, rng)?;
+        let blinded_bytes = ctx.serialize_element(&blinded)?;
+
+        Ok(OprfBlindedInput {
+            input: input.to_vec(),
+            info: info.to_vec(),
+            blind: blind.clone(),
+            blinded,
+            blinded_bytes,
+        })
+    }
+
+    /// Compute the PRF outputs from the server's evaluation (`Finalize`)
+    ///
+    /// The blinded inputs must be given in the same order as they were sent
+    /// to the server. In the verifiable modes, if the proof is not valid an
+    /// error of type [`ErrorType::InvalidVerifier`]
+    /// is returned.
+    pub fn finalize(
+        &self,
+        inputs: &[OprfBlindedInput],
+        evaluation: &OprfEvaluation,
+        rng: &mut RandomNumberGenerator,
+    ) -> Result<Vec<Vec<u8>>> {
+        let ctx = &self.ctx;
+
+        if inputs.is_empty() || inputs.len() != evaluation.elements().len() {
+            return Err(Error::bad_parameter(
+                "OPRF evaluation does not match the blinded inputs",
+            ));
+        }
+
+        let info = inputs[0].info.as_slice();
+        if inputs.iter().any(|i| i.info != info) {
+            return Err(Error::bad_parameter(
+                "All inputs of an OPRF batch must use the same info",
+            ));
+        }
+
+        let evaluated = evaluation
+            .elements()
+            .iter()
+            .map(|e| ctx.deserialize_element(e))
+            .collect::<Result<Vec<_>>>()?;
+
+        if let Some(server_public) = &self.server_public {
+            let proof = evaluation.proof().ok_or_else(invalid_proof)?;
+            let blinded = inputs.iter().map(|i| &i.blinded).collect::<Vec<_>>();
+            let evaluated = evaluated.iter().collect::<Vec<_>>();
+            let g = ctx.group.generator()?;
+
+            let valid = if ctx.mode == OprfMode::Poprf {
+                let tweaked_key = g.mul(&ctx.info_scalar(info)?, rng)?.pt_add(server_public)?;
+                if tweaked_key.is_identity()? {
+                    return Err(Error::bad_parameter("Invalid OPRF info for this key"));
+                }
+                ctx.verify_proof(&g, &tweaked_key, &evaluated, &blinded, proof, rng)?
+            } else {
+                ctx.verify_proof(&g, server_public, &blinded, &evaluated, proof, rng)?
+            };
+
+            if !valid {
+                return Err(invalid_proof());
+            }
+        }
+
+        inputs
+            .iter()
+            .zip(evaluated.iter())
+            .map(|(input, element)| {
+                let unblinded = element.mul(&input.blind.invert()?, rng)?;
+                ctx.finalize_hash(&input.input, info, &unblinded)
+            })
+            .collect()
+    }
+}
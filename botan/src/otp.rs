@@ -1,6 +1,11 @@
 use crate::utils::*;
 use botan_sys::*;
 
+use core::fmt;
+use core::str::FromStr;
+
+use crate::{HashAlgorithm, base32_decode, base32_encode};
+
 /// Generate or check HOTP tokens
 #[derive(Debug)]
 #[allow(clippy::upper_case_acronyms)]
@@ -139,3 +144,455 @@ impl TOTP {
         )?)
     }
 }
+
+fn otpauth_error(msg: &str) -> Error {
+    Error::with_message(
+        ErrorType::InvalidInput,
+        format!("Invalid otpauth URI: {msg}"),
+    )
+}
+
+fn percent_encode(s: &str) -> String {
+    let mut out = String::with_capacity(s.len());
+    for b in s.bytes() {
+        if b.is_ascii_alphanumeric() || matches!(b, b'-' | b'.' | b'_' | b'~') {
+            out.push(char::from(b));
+        } else {
+            out.push_str(&format!("%{b:02X}"));
+        }
+    }
+    out
+}
+
+fn percent_decode(s: &str) -> Result<String> {
+    let mut out = Vec::with_capacity(s.len());
+    let mut bytes = s.bytes();
+    while let Some(b) = bytes.next() {
+        if b == b'%' {
+            let hex = [bytes.next(), bytes.next()];
+            let hex = match hex {
+                [Some(h), Some(l)] => [h, l],
+                _ => return Err(otpauth_error("truncated percent encoding")),
+            };
+            let hex =
+                core::str::from_utf8(&hex).map_err(|_| otpauth_error("bad percent encoding"))?;
+            let v =
+                u8::from_str_radix(hex, 16).map_err(|_| otpauth_error("bad percent encoding"))?;
+            out.push(v);
+        } else {
+            out.push(b);
+        }
+    }
+    String::from_utf8(out).map_err(|_| otpauth_error("label or parameter is not UTF-8"))
+}
+
+/// The type of one time password described by an [`OtpAuthUri`]
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum OtpAuthKind {
+    /// Counter based codes (RFC 4226), with the initial counter value
+    Hotp {
+        /// The counter value
+        counter: u64,
+    },
+    /// Time based codes (RFC 6238), with the time step in seconds
+    Totp {
+        /// The time step, in seconds
+        period: u64,
+    },
+}
+
+/// An `otpauth://` key URI, as used to provision authenticator apps
+///
+/// This is the format popularized by Google Authenticator, for example
+/// `otpauth://totp/Example:alice@example.com?secret=JBSWY3DPEHPK3PXP&issuer=Example`.
+/// The secret is encoded in Base32. The supported algorithms are SHA-1,
+/// SHA-256 and SHA-512.
+///
+/// # Examples
+///
+/// ```
+/// let uri = botan::OtpAuthUri::new_totp(b"12345678901234567890", "alice@example.com").with_issuer("Example");
+/// let encoded = uri.to_uri().unwrap();
+/// assert_eq!(encoded, "otpauth://totp/Example:alice%40example.com?secret=GEZDGNBVGY3TQOJQGEZDGNBVGY3TQOJQ&issuer=Example&algorithm=SHA1&digits=6&period=30");
+///
+/// let parsed: botan::OtpAuthUri = encoded.parse().unwrap();
+/// assert!(parsed == uri);
+/// let totp = parsed.totp().unwrap();
+/// ```
+#[derive(Clone, PartialEq, Eq)]
+pub struct OtpAuthUri {
+    /// HOTP or TOTP, along with the counter or period
+    pub kind: OtpAuthKind,
+    /// The shared secret
+    pub secret: Vec<u8>,
+    /// The provider or service the account belongs to
+    pub issuer: Option<String>,
+    /// The account name, typically a username or email address
+    pub account: String,
+    /// The hash function used
+    pub algorithm: HashAlgorithm,
+    /// The number of digits in each code
+    pub digits: usize,
+}
+
+impl OtpAuthUri {
+    /// Describe a TOTP key using the usual defaults of SHA-1, 6 digits, and a 30 second period
+    #[must_use]
+    pub fn new_totp(secret: &[u8], account: &str) -> Self {
+        Self {
+            kind: OtpAuthKind::Totp { period: 30 },
+            secret: secret.to_vec(),
+            issuer: None,
+            account: account.to_owned(),
+            algorithm: HashAlgorithm::Sha1,
+            digits: 6,
+        }
+    }
+
+    /// Describe an HOTP key using SHA-1 and 6 digits
+    #[must_use]
+    pub fn new_hotp(secret: &[u8], account: &str, counter: u64) -> Self {
+        Self {
+            kind: OtpAuthKind::Hotp { counter },
+            ..Self::new_totp(secret, account)
+        }
+    }
+
+    /// Set the issuer
+    #[must_use]
+    pub fn with_issuer(mut self, issuer: &str) -> Self {
+        self.issuer = Some(issuer.to_owned());
+        self
+    }
+
+    fn algorithm_name(&self) -> Result<&'static str> {
+        match self.algorithm {
+            HashAlgorithm::Sha1 => Ok("SHA1"),
+            HashAlgorithm::Sha256 => Ok("SHA256"),
+            HashAlgorithm::Sha512 => Ok("SHA512"),
+            _ => Err(Error::bad_parameter(
+                "Hash algorithm not supported in otpauth URIs",
+            )),
+        }
+    }
+
+    /// Encode the key as an `otpauth://` URI
+    ///
+    /// Fails if the algorithm is not one of SHA-1, SHA-256 or SHA-512.
+    pub fn to_uri(&self) -> Result<String> {
+        let (otp_type, param) = match self.kind {
+            OtpAuthKind::Hotp { counter } => ("hotp", format!("counter={counter}")),
+            OtpAuthKind::Totp { period } => ("totp", format!("period={period}")),
+        };
+        let algorithm = self.algorithm_name()?;
+        let secret = base32_encode(&self.secret);
+
+        let mut uri = format!("otpauth://{otp_type}/");
+        if let Some(issuer) = &self.issuer {
+            uri.push_str(&percent_encode(issuer));
+            uri.push(':');
+        }
+        uri.push_str(&percent_encode(&self.account));
+
+        uri.push_str("?secret=");
+        uri.push_str(secret.trim_end_matches('='));
+        if let Some(issuer) = &self.issuer {
+            uri.push_str("&issuer=");
+            uri.push_str(&percent_encode(issuer));
+        }
+        uri.push_str(&format!(
+            "&algorithm={algorithm}&digits={}&{param}",
+            self.digits
+        ));
+        Ok(uri)
+    }
+
+    /// Create an [`HOTP`] instance for this key
+    pub fn hotp(&self) -> Result<HOTP> {
+        if !matches!(self.kind, OtpAuthKind::Hotp { .. }) {
+            return Err(Error::bad_parameter("Not an HOTP key"));
+        }
+        HOTP::new(&self.secret, self.algorithm.clone(), self.digits)
+    }
+
+    /// Create a [`TOTP`] instance for this key
+    pub fn totp(&self) -> Result<TOTP> {
+        let OtpAuthKind::Totp { period } = self.kind else {
+            return Err(Error::bad_parameter("Not a TOTP key"));
+        };
+        let period = usize::try_from(period).map_err(|_| Error::bad_parameter("Invalid period"))?;
+        TOTP::new(&self.secret, self.algorithm.clone(), self.digits, period)
+    }
+}
+
+impl FromStr for OtpAuthUri {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        let rest = s
+            .get(..10)
+            .filter(|scheme| scheme.eq_ignore_ascii_case("otpauth://"))
+            .map(|_| &s[10..])
+            .ok_or_else(|| otpauth_error("wrong scheme"))?;
+
+        let (otp_type, rest) = rest
+            .split_once('/')
+            .ok_or_else(|| otpauth_error("missing label"))?;
+        let (label, query) = rest.split_once('?').unwrap_or((rest, ""));
+
+        // The issuer prefix is separated by a colon, which may itself be percent encoded
+        let (label_issuer, account) = match label.split_once(':') {
+            Some((issuer, account)) => (Some(percent_decode(issuer)?), percent_decode(account)?),
+            None => match percent_decode(label)?.split_once(':') {
+                Some((issuer, account)) => (Some(issuer.to_owned()), account.to_owned()),
+                None => (None, percent_decode(label)?),
+            },
+        };
+        let account = account.trim_start();
+        if account.is_empty() {
+            return Err(otpauth_error("missing account name"));
+        }
+
+        let mut secret = None;
+        let mut issuer = None;
+        let mut algorithm = HashAlgorithm::Sha1;
+        let mut digits = 6;
+        let mut period = 30;
+        let mut counter = None;
+
+        let parse_int = |v: &str| {
+            v.parse::<u64>()
+                .map_err(|_| otpauth_error("bad integer parameter"))
+        };
+
+        for param in query.split('&').filter(|p| !p.is_empty()) {
+            let (key, value) = param.split_once('=').unwrap_or((param, ""));
+            let value = percent_decode(value)?;
+            match key {
+                "secret" => {
+                    secret = Some(
+                        base32_decode(&value).map_err(|_| otpauth_error("bad secret encoding"))?,
+                    )
+                }
+                "issuer" => issuer = Some(value),
+                "algorithm" => {
+                    algorithm = match value.to_ascii_uppercase().as_str() {
+                        "SHA1" => HashAlgorithm::Sha1,
+                        "SHA256" => HashAlgorithm::Sha256,
+                        "SHA512" => HashAlgorithm::Sha512,
+                        _ => return Err(otpauth_error("unsupported algorithm")),
+                    }
+                }
+                "digits" => {
+                    digits = usize::try_from(parse_int(&value)?)
+                        .map_err(|_| otpauth_error("bad digits"))?
+                }
+                "period" => period = parse_int(&value)?,
+                "counter" => counter = Some(parse_int(&value)?),
+                // Ignore extensions such as image
+                _ => {}
+            }
+        }
+
+        let kind = if otp_type.eq_ignore_ascii_case("totp") {
+            if period == 0 {
+                return Err(otpauth_error("bad period"));
+            }
+            OtpAuthKind::Totp { period }
+        } else if otp_type.eq_ignore_ascii_case("hotp") {
+            OtpAuthKind::Hotp {
+                counter: counter.ok_or_else(|| otpauth_error("missing counter"))?,
+            }
+        } else {
+            return Err(otpauth_error("unknown OTP type"));
+        };
+
+        if let (Some(a), Some(b)) = (&label_issuer, &issuer) {
+            if a != b {
+                return Err(otpauth_error("issuer does not match label"));
+            }
+        }
+
+        let secret = secret
+            .filter(|s| !s.is_empty())
+            .ok_or_else(|| otpauth_error("missing secret"))?;
+
+        Ok(Self {
+            kind,
+            secret,
+            issuer: issuer.or(label_issuer),
+            account: account.to_owned(),
+            algorithm,
+            digits,
+        })
+    }
+}
+
+/// Validate HOTP codes, keeping track of the counter
+///
+/// Codes are accepted if they match any counter value up to `look_ahead`
+/// steps ahead of the current counter, and the counter is advanced past
+/// the accepted value so that no code can be used twice. The counter
+/// should be persisted (see [`HotpValidator::counter`]) after every
+/// successful validation.
+///
+/// # Examples
+///
+/// ```
+/// let key = b"12345678901234567890";
+/// let mut validator = botan::HotpValidator::new(key, "SHA-1", 6, 0, 3).unwrap();
+/// assert!(validator.validate(359152).unwrap()); // counter 2
+/// assert_eq!(validator.counter(), 3);
+/// assert!(!validator.validate(359152).unwrap()); // no replay
+/// ```
+pub struct HotpValidator {
+    hotp: HOTP,
+    counter: u64,
+    look_ahead: usize,
+}
+
+impl HotpValidator {
+    /// Create a new validator with the given (possibly persisted) counter value
+    pub fn new<A: crate::HashAlgorithmIdentifier>(
+        key: &[u8],
+        hash_algo: A,
+        digits: usize,
+        counter: u64,
+        look_ahead: usize,
+    ) -> Result<Self> {
+        Ok(Self {
+            hotp: HOTP::new(key, hash_algo, digits)?,
+            counter,
+            look_ahead,
+        })
+    }
+
+    /// Return the counter value expected next
+    #[must_use]
+    pub fn counter(&self) -> u64 {
+        self.counter
+    }
+
+    /// Check a code, advancing the counter if it is accepted
+    pub fn validate(&mut self, code: u32) -> Result<bool> {
+        let (ok, next) = self
+            .hotp
+            .check_with_resync(code, self.counter, self.look_ahead)?;
+        if ok {
+            self.counter = next;
+        }
+        Ok(ok)
+    }
+
+    /// Resynchronize the counter using two consecutive codes
+    ///
+    /// As described in RFC 4226 section 7.4, this allows a much larger
+    /// window than is acceptable for a single code, for instance when a
+    /// token has been used many times without the server seeing it.
+    pub fn resynchronize(&mut self, code1: u32, code2: u32, max_look_ahead: u64) -> Result<bool> {
+        let mut expected = self.hotp.generate(self.counter)?;
+
+        let overflow = || Error::bad_parameter("HOTP counter overflow");
+
+        for i in 0..=max_look_ahead {
+            let counter = self.counter.checked_add(i).ok_or_else(overflow)?;
+            let next_counter = counter.checked_add(1).ok_or_else(overflow)?;
+            let next = self.hotp.generate(next_counter)?;
+
+            if expected == code1 && next == code2 {
+                self.counter = next_counter.checked_add(1).ok_or_else(overflow)?;
+                return Ok(true);
+            }
+            expected = next;
+        }
+
+        Ok(false)
+    }
+}
+
+/// Validate TOTP codes, rejecting any code which was already used
+///
+/// Codes within `allowed_drift` time steps either side of the current
+/// time are accepted, but only for time steps after the last accepted
+/// one. This prevents a code from being replayed while it is still
+/// within the drift window. A separate validator is used for each user,
+/// and the last accepted time step (see [`TotpValidator::last_step`])
+/// should be persisted after every successful validation.
+///
+/// # Examples
+///
+/// ```
+/// let key = b"12345678901234567890";
+/// let mut validator = botan::TotpValidator::new(key, "SHA-1", 8, 30, 1).unwrap();
+/// assert!(validator.validate(94287082, 59).unwrap());
+/// assert!(!validator.validate(94287082, 60).unwrap()); // replay rejected
+/// ```
+pub struct TotpValidator {
+    hotp: HOTP,
+    time_step: u64,
+    allowed_drift: u64,
+    last_step: Option<u64>,
+}
+
+impl TotpValidator {
+    /// Create a new validator
+    pub fn new<A: crate::HashAlgorithmIdentifier>(
+        key: &[u8],
+        hash_algo: A,
+        digits: usize,
+        time_step: u64,
+        allowed_drift: u64,
+    ) -> Result<Self> {
+        if time_step == 0 {
+            return Err(Error::bad_parameter("Invalid TOTP time step"));
+        }
+
+        // A TOTP code is the HOTP code using the time step number as counter
+        Ok(Self {
+            hotp: HOTP::new(key, hash_algo, digits)?,
+            time_step,
+            allowed_drift,
+            last_step: None,
+        })
+    }
+
+    /// Restore the last accepted time step, as previously persisted
+    #[must_use]
+    pub fn with_last_step(mut self, last_step: Option<u64>) -> Self {
+        self.last_step = last_step;
+        self
+    }
+
+    /// Return the time step of the most recently accepted code
+    #[must_use]
+    pub fn last_step(&self) -> Option<u64> {
+        self.last_step
+    }
+
+    /// Check a code against the given Unix timestamp
+    ///
+    /// If the code is accepted, its time step is recorded and no code from
+    /// that time step or any earlier one will be accepted again.
+    pub fn validate(&mut self, code: u32, timestamp: u64) -> Result<bool> {
+        let step = timestamp / self.time_step;
+        let first = match self.last_step {
+            Some(last) => step
+                .saturating_sub(self.allowed_drift)
+                .max(last.saturating_add(1)),
+            None => step.saturating_sub(self.allowed_drift),
+        };
+        let last = step.saturating_add(self.allowed_drift);
+
+        let mut accepted = None;
+        for s in first..=last {
+            if self.hotp.generate(s)? == code && accepted.is_none() {
+                accepted = Some(s);
+            }
+        }
+
+        if let Some(s) = accepted {
+            self.last_step = Some(s);
+        }
+        Ok(accepted.is_some())
+    }
+}
@@ -0,0 +1,494 @@
+use crate::utils::*;
+
+use crate::{
+    HashAlgorithm, MsgAuthCode, PasswordHashAlgorithm, PasswordHashLimits, PasswordHashParams,
+    RandomNumberGenerator, base64_decode, base64_encode, const_time_compare,
+    derive_key_from_password,
+};
+
+const ARGON2_VERSION: usize = 0x13;
+
+fn phc_error(msg: &str) -> Error {
+    Error::with_message(
+        ErrorType::InvalidInput,
+        format!("Invalid password hash: {msg}"),
+    )
+}
+
+/// Base64 encode without padding
+///
+/// PHC strings use the standard alphabet; passlib's PBKDF2 hashes use
+/// its "adapted" alphabet, which substitutes `.` for `+`.
+fn b64_encode(data: &[u8], adapted: bool) -> Result<String> {
+    if data.is_empty() {
+        return Ok(String::new());
+    }
+    let encoded = base64_encode(data)?;
+    let encoded = encoded.trim_end_matches('=');
+    if adapted {
+        Ok(encoded.replace('+', "."))
+    } else {
+        Ok(encoded.to_owned())
+    }
+}
+
+fn b64_decode(encoded: &str, adapted: bool) -> Result<Vec<u8>> {
+    let valid = |c: u8| {
+        c.is_ascii_alphanumeric() || c == b'/' || if adapted { c == b'.' } else { c == b'+' }
+    };
+    if encoded.is_empty() || !encoded.bytes().all(valid) || encoded.len() % 4 == 1 {
+        return Err(phc_error("bad base64 encoding"));
+    }
+
+    let mut padded = if adapted {
+        encoded.replace('.', "+")
+    } else {
+        encoded.to_owned()
+    };
+    while padded.len() % 4 != 0 {
+        padded.push('=');
+    }
+    base64_decode(&padded)
+}
+
+/// Parse a PHC decimal value, which may not have a sign or leading zeros
+fn parse_decimal(s: &str) -> Result<usize> {
+    if s.is_empty() || !s.bytes().all(|c| c.is_ascii_digit()) || (s.len() > 1 && s.starts_with('0'))
+    {
+        return Err(phc_error("bad decimal value"));
+    }
+    s.parse()
+        .map_err(|_| phc_error("decimal value out of range"))
+}
+
+/// Parse a comma separated list of parameters, which must appear exactly
+/// in the order given by `names`
+fn parse_params<const N: usize>(s: &str, names: [&str; N]) -> Result<[usize; N]> {
+    let mut values = [0; N];
+    let mut fields = s.split(',');
+
+    for (name, value) in names.iter().zip(values.iter_mut()) {
+        let (key, v) = fields
+            .next()
+            .and_then(|f| f.split_once('='))
+            .ok_or_else(|| phc_error("missing parameter"))?;
+        if key != *name {
+            return Err(phc_error("unexpected parameter"));
+        }
+        *value = parse_decimal(v)?;
+    }
+
+    if fields.next().is_some() {
+        return Err(phc_error("unexpected parameter"));
+    }
+    Ok(values)
+}
+
+/// The PHC identifier used for a password hash, if it has one
+fn phc_id(algo: &PasswordHashAlgorithm) -> Option<&'static str> {
+    match algo {
+        PasswordHashAlgorithm::Argon2d => Some("argon2d"),
+        PasswordHashAlgorithm::Argon2i => Some("argon2i"),
+        PasswordHashAlgorithm::Argon2id => Some("argon2id"),
+        PasswordHashAlgorithm::Scrypt => Some("scrypt"),
+        PasswordHashAlgorithm::Pbkdf2(HashAlgorithm::Sha1) => Some("pbkdf2"),
+        PasswordHashAlgorithm::Pbkdf2(HashAlgorithm::Sha256) => Some("pbkdf2-sha256"),
+        PasswordHashAlgorithm::Pbkdf2(HashAlgorithm::Sha512) => Some("pbkdf2-sha512"),
+        _ => None,
+    }
+}
+
+/// Settings used to create new password hashes
+///
+/// The parameters are interpreted as for [`derive_key_from_password`]:
+///
+/// * Argon2: `param1` is the memory in KiB, `param2` the number of
+///   iterations, and `param3` the parallelism
+/// * scrypt: `param1` is N (which must be a power of two), `param2` is r,
+///   and `param3` is p
+/// * PBKDF2: `param1` is the iteration count, the others are unused
+///
+/// The supported algorithms are Argon2d, Argon2i, Argon2id, scrypt, and
+/// PBKDF2 with SHA-1, SHA-256 or SHA-512.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct PasswordHashPolicy {
+    /// The password hashing algorithm
+    pub algorithm: PasswordHashAlgorithm,
+    /// The first algorithm parameter
+    pub param1: usize,
+    /// The second algorithm parameter
+    pub param2: usize,
+    /// The third algorithm parameter
+    pub param3: usize,
+    /// Length of the random salt, in bytes
+    pub salt_len: usize,
+    /// Length of the hash output, in bytes
+    pub output_len: usize,
+}
+
+impl PasswordHashPolicy {
+    /// Return the default policy for the given algorithm
+    ///
+    /// Argon2 uses 64 MiB of memory, 3 iterations and 4 lanes (the second
+    /// recommended option of RFC 9106), scrypt uses N = 32768, r = 8 and
+    /// p = 1, and PBKDF2 uses 600000 iterations.
+    pub fn new(algorithm: PasswordHashAlgorithm) -> Result<Self> {
+        let (param1, param2, param3) = match algorithm {
+            PasswordHashAlgorithm::Argon2d
+            | PasswordHashAlgorithm::Argon2i
+            | PasswordHashAlgorithm::Argon2id => (65536, 3, 4),
+            PasswordHashAlgorithm::Scrypt => (32768, 8, 1),
+            PasswordHashAlgorithm::Pbkdf2(_) if phc_id(&algorithm).is_some() => (600000, 0, 0),
+            _ => {
+                return Err(Error::bad_parameter(
+                    "Password hash algorithm has no PHC string encoding",
+                ));
+            }
+        };
+
+        Ok(Self {
+            algorithm,
+            param1,
+            param2,
+            param3,
+            salt_len: 16,
+            output_len: 32,
+        })
+    }
+}
+
+impl From<PasswordHashParams> for PasswordHashPolicy {
+    /// Use previously chosen parameters, for example from [`PasswordHashParams::calibrate`]
+    fn from(params: PasswordHashParams) -> Self {
+        Self {
+            algorithm: params.algorithm,
+            param1: params.param1,
+            param2: params.param2,
+            param3: params.param3,
+            salt_len: 16,
+            output_len: 32,
+        }
+    }
+}
+
+impl Default for PasswordHashPolicy {
+    fn default() -> Self {
+        Self {
+            algorithm: PasswordHashAlgorithm::Argon2id,
+            param1: 65536,
+            param2: 3,
+            param3: 4,
+            salt_len: 16,
+            output_len: 32,
+        }
+    }
+}
+
+/// A password hash decoded from its PHC string
+struct PhcHash {
+    algorithm: PasswordHashAlgorithm,
+    params: (usize, usize, usize),
+    salt: Vec<u8>,
+    hash: Vec<u8>,
+}
+
+impl PhcHash {
+    fn parse(s: &str) -> Result<Self> {
+        let mut fields = s
+            .strip_prefix('$')
+            .ok_or_else(|| phc_error("missing identifier"))?
+            .split('$');
+
+        let id = fields.next().unwrap_or_default();
+
+        let (algorithm, params, adapted) = match id {
+            "argon2d" | "argon2i" | "argon2id" => {
+                let algorithm = match id {
+                    "argon2d" => PasswordHashAlgorithm::Argon2d,
+                    "argon2i" => PasswordHashAlgorithm::Argon2i,
+                    _ => PasswordHashAlgorithm::Argon2id,
+                };
+
+                // Hashes with no version field use the original version 1.0
+                let mut field = fields.next().unwrap_or_default();
+                let version = match field.strip_prefix("v=") {
+                    Some(v) => {
+                        field = fields.next().unwrap_or_default();
+                        parse_decimal(v)?
+                    }
+                    None => 0x10,
+                };
+                if version != ARGON2_VERSION {
+                    return Err(Error::with_message(
+                        ErrorType::NotImplemented,
+                        format!("Argon2 version {version} is not supported"),
+                    ));
+                }
+
+                let [m, t, p] = parse_params(field, ["m", "t", "p"])?;
+                (algorithm, (m, t, p), false)
+            }
+            "scrypt" => {
+                let [ln, r, p] = parse_params(fields.next().unwrap_or_default(), ["ln", "r", "p"])?;
+                if ln == 0 || ln >= usize::BITS as usize {
+                    return Err(phc_error("bad scrypt cost"));
+                }
+                (PasswordHashAlgorithm::Scrypt, (1 << ln, r, p), false)
+            }
+            "pbkdf2" | "pbkdf2-sha256" | "pbkdf2-sha512" => {
+                let hash = match id {
+                    "pbkdf2" => HashAlgorithm::Sha1,
+                    "pbkdf2-sha256" => HashAlgorithm::Sha256,
+                    _ => HashAlgorithm::Sha512,
+                };
+                let iterations = parse_decimal(fields.next().unwrap_or_default())?;
+                (
+                    PasswordHashAlgorithm::Pbkdf2(hash),
+                    (iterations, 0, 0),
+                    true,
+                )
+            }
+            _ => {
+                return Err(Error::with_message(
+                    ErrorType::NotImplemented,
+                    format!("Unknown password hash identifier '{id}'"),
+                ));
+            }
+        };
+
+        let salt = b64_decode(fields.next().unwrap_or_default(), adapted)?;
+        let hash = b64_decode(fields.next().unwrap_or_default(), adapted)?;
+
+        if fields.next().is_some() {
+            return Err(phc_error("trailing fields"));
+        }
+
+        Ok(Self {
+            algorithm,
+            params,
+            salt,
+            hash,
+        })
+    }
+
+    fn encode(&self) -> Result<String> {
+        let id = phc_id(&self.algorithm).ok_or_else(|| {
+            Error::bad_parameter("Password hash algorithm has no PHC string encoding")
+        })?;
+        let (p1, p2, p3) = self.params;
+
+        let adapted = matches!(self.algorithm, PasswordHashAlgorithm::Pbkdf2(_));
+
+        let params = match self.algorithm {
+            PasswordHashAlgorithm::Scrypt => {
+                if !p1.is_power_of_two() || p1 == 1 {
+                    return Err(Error::bad_parameter("Scrypt N must be a power of two"));
+                }
+                format!("ln={},r={p2},p={p3}", p1.trailing_zeros())
+            }
+            PasswordHashAlgorithm::Pbkdf2(_) => format!("{p1}"),
+            _ => format!("v={ARGON2_VERSION}$m={p1},t={p2},p={p3}"),
+        };
+
+        Ok(format!(
+            "${id}${params}${}${}",
+            b64_encode(&self.salt, adapted)?,
+            b64_encode(&self.hash, adapted)?
+        ))
+    }
+
+    fn check_limits(&self, limits: &PasswordHashLimits) -> Result<()> {
+        if self.hash.len() > limits.max_hash_len {
+            return Err(Error::with_message(
+                ErrorType::InvalidInput,
+                format!(
+                    "Password hash length of {} exceeds the limit of {}",
+                    self.hash.len(),
+                    limits.max_hash_len
+                ),
+            ));
+        }
+
+        let (p1, p2, p3) = self.params;
+        limits.check(&PasswordHashParams::new(self.algorithm.clone(), p1, p2, p3))
+    }
+
+    fn compute(&self, password: &str, output_len: usize) -> Result<Vec<u8>> {
+        let (p1, p2, p3) = self.params;
+        derive_key_from_password(
+            self.algorithm.clone(),
+            output_len,
+            password,
+            &self.salt,
+            p1,
+            p2,
+            p3,
+        )
+    }
+}
+
+/// Create and verify password hashes encoded as PHC strings
+///
+/// This is the format used by the Argon2 reference implementation and
+/// by passlib, for example
+/// `$argon2id$v=19$m=65536,t=3,p=4$<salt>$<hash>`.
+///
+/// Optionally a pepper, a secret key stored separately from the password
+/// hashes, can be set. The password is then first processed with
+/// HMAC-SHA-256 keyed by the pepper, and the base64 encoding of the HMAC
+/// output is hashed in its place. Peppered hashes can only be verified
+/// using the same pepper, and are not compatible with other
+/// implementations.
+///
+/// The cost parameters of a hash being verified are read from the hash
+/// string itself, so are checked against [`PasswordHashLimits`] (by default
+/// [`PasswordHashLimits::default`]) before any hashing is done.
+///
+/// # Examples
+///
+/// ```
+/// let mut rng = botan::RandomNumberGenerator::new_system().unwrap();
+/// let hasher = botan::PasswordHasher::new(botan::PasswordHashPolicy::default()).with_pepper(b"secret pepper");
+/// let hash = hasher.hash("hunter2", &mut rng).unwrap();
+/// assert!(hasher.verify("hunter2", &hash).unwrap());
+/// assert!(!hasher.verify("hunter3", &hash).unwrap());
+/// assert!(!botan::password_verify("hunter2", &hash).unwrap());
+/// ```
+#[derive(Clone, Default)]
+pub struct PasswordHasher {
+    policy: PasswordHashPolicy,
+    limits: PasswordHashLimits,
+    pepper: Option<Vec<u8>>,
+}
+
+impl PasswordHasher {
+    /// Create a new password hasher using the given policy
+    #[must_use]
+    pub fn new(policy: PasswordHashPolicy) -> Self {
+        Self {
+            policy,
+            limits: PasswordHashLimits::default(),
+            pepper: None,
+        }
+    }
+
+    /// Set the limits on the cost parameters of hashes being verified
+    #[must_use]
+    pub fn with_limits(mut self, limits: PasswordHashLimits) -> Self {
+        self.limits = limits;
+        self
+    }
+
+    /// Set the pepper applied to every password
+    #[must_use]
+    pub fn with_pepper(mut self, pepper: &[u8]) -> Self {
+        self.pepper = Some(pepper.to_vec());
+        self
+    }
+
+    /// Return the policy used for new hashes
+    #[must_use]
+    pub fn policy(&self) -> &PasswordHashPolicy {
+        &self.policy
+    }
+
+    fn peppered(&self, password: &str) -> Result<String> {
+        match &self.pepper {
+            None => Ok(password.to_owned()),
+            Some(pepper) => {
+                let mut hmac = MsgAuthCode::new("HMAC(SHA-256)")?;
+                hmac.set_key(pepper)?;
+                hmac.update(password.as_bytes())?;
+                base64_encode(&hmac.finish()?)
+            }
+        }
+    }
+
+    /// Hash a password using a fresh random salt
+    pub fn hash(&self, password: &str, rng: &mut RandomNumberGenerator) -> Result<String> {
+        let policy = &self.policy;
+        if policy.salt_len == 0 || policy.output_len == 0 {
+            return Err(Error::bad_parameter("Invalid salt or output length"));
+        }
+
+        let mut phc = PhcHash {
+            algorithm: policy.algorithm.clone(),
+            params: (policy.param1, policy.param2, policy.param3),
+            salt: rng.read(policy.salt_len)?,
+            hash: Vec::new(),
+        };
+        // Check the encoding is possible before doing any expensive work
+        phc.encode()?;
+        phc.hash = phc.compute(&self.peppered(password)?, policy.output_len)?;
+        phc.encode()
+    }
+
+    /// Verify a password against a PHC string
+    ///
+    /// Returns `Ok(false)` if the password is incorrect, and an error if
+    /// the hash string is malformed, uses an unsupported algorithm, or has
+    /// cost parameters exceeding the limits.
+    pub fn verify(&self, password: &str, hash: &str) -> Result<bool> {
+        let phc = PhcHash::parse(hash)?;
+        phc.check_limits(&self.limits)?;
+        let computed = phc.compute(&self.peppered(password)?, phc.hash.len())?;
+        Ok(const_time_compare(&computed, &phc.hash))
+    }
+
+    /// Check if a hash was created with settings other than the current policy
+    ///
+    /// This should be called after a successful verification, and if it
+    /// returns true the password rehashed and the stored hash replaced.
+    pub fn needs_rehash(&self, hash: &str) -> Result<bool> {
+        needs_rehash(hash, &self.policy)
+    }
+}
+
+/// Hash a password using the default policy for the given algorithm
+///
+/// See [`PasswordHasher`] for details of the format.
+///
+/// # Examples
+///
+/// ```
+/// let mut rng = botan::RandomNumberGenerator::new_system().unwrap();
+/// let hash = botan::password_hash(botan::PasswordHashAlgorithm::Argon2id, "hunter2", &mut rng).unwrap();
+/// assert!(hash.starts_with("$argon2id$v=19$m=65536,t=3,p=4$"));
+/// assert!(botan::password_verify("hunter2", &hash).unwrap());
+/// ```
+pub fn password_hash(
+    algorithm: PasswordHashAlgorithm,
+    password: &str,
+    rng: &mut RandomNumberGenerator,
+) -> Result<String> {
+    PasswordHasher::new(PasswordHashPolicy::new(algorithm)?).hash(password, rng)
+}
+
+/// Verify a password against a PHC string, in constant time
+///
+/// The hash must be within the default [`PasswordHashLimits`]; use
+/// [`PasswordHasher::with_limits`] to verify more expensive hashes.
+///
+/// # Examples
+///
+/// ```
+/// let hash = "$argon2i$v=19$m=65536,t=2,p=4$c29tZXNhbHQ$RdescudvJCsgt3ub+b+dWRWJTmaaJObG";
+/// assert!(botan::password_verify("password", hash).unwrap());
+/// assert!(!botan::password_verify("wrong", hash).unwrap());
+/// ```
+pub fn password_verify(password: &str, hash: &str) -> Result<bool> {
+    PasswordHasher::default().verify(password, hash)
+}
+
+/// Check if a PHC string was created with settings other than the given policy
+///
+/// A rehash is needed if the algorithm or any cost parameter differs from
+/// the policy, or if the salt or output are shorter than it requires.
+pub fn needs_rehash(hash: &str, policy: &PasswordHashPolicy) -> Result<bool> {
+    let phc = PhcHash::parse(hash)?;
+
+    Ok(phc.algorithm != policy.algorithm
+        || phc.params != (policy.param1, policy.param2, policy.param3)
+        || phc.salt.len() < policy.salt_len
+        || phc.hash.len() < policy.output_len)
+}
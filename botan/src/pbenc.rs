@@ -0,0 +1,534 @@
+use crate::utils::*;
+
+use crate::{
+    BlockCipherAlgorithm, Cipher, CipherAlgorithm, CipherDirection, PasswordHashAlgorithm,
+    PasswordHashLimits, PasswordHashParams, RandomNumberGenerator, derive_key_from_password,
+};
+
+use crate::segmented::{SEGMENT_NONCE_SUFFIX_LEN, SegmentedAead};
+
+/// The current version of the password based encryption format
+pub const PBENC_VERSION: u8 = 1;
+
+const PBENC_MAGIC: &[u8; 4] = b"BPBE";
+
+// magic, version, and header length
+const PBENC_FIXED_PREFIX_LEN: usize = 4 + 1 + 2;
+
+fn header_error(msg: &str) -> Error {
+    Error::with_message(
+        ErrorType::InvalidInput,
+        format!("Invalid pbenc header: {msg}"),
+    )
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+/// Parameters for password based encryption
+///
+/// The three password hash parameters are interpreted as for
+/// [`derive_key_from_password`]; for Argon2 they are the memory in KiB,
+/// the number of iterations, and the parallelism.
+pub struct PbencParams {
+    /// The password hash used to derive the encryption key
+    pub password_hash: PasswordHashAlgorithm,
+    /// The first password hash parameter
+    pub param1: usize,
+    /// The second password hash parameter
+    pub param2: usize,
+    /// The third password hash parameter
+    pub param3: usize,
+    /// The length of the random salt
+    pub salt_len: usize,
+    /// The AEAD used to encrypt each chunk
+    pub cipher: CipherAlgorithm,
+    /// The plaintext length of each chunk
+    pub chunk_size: usize,
+}
+
+impl Default for PbencParams {
+    /// Argon2id with 64 MiB of memory, 3 passes and 4 lanes (RFC 9106),
+    /// AES-256/GCM, and 64 KiB chunks
+    fn default() -> Self {
+        Self {
+            password_hash: PasswordHashAlgorithm::Argon2id,
+            param1: 64 * 1024,
+            param2: 3,
+            param3: 4,
+            salt_len: 16,
+            cipher: CipherAlgorithm::Gcm(BlockCipherAlgorithm::Aes256, None),
+            chunk_size: 64 * 1024,
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+/// Limits on the header of a container being decrypted
+///
+/// The header of a container records the password hash parameters and chunk
+/// size, and comes from untrusted input. Headers exceeding these limits are
+/// rejected before the key is derived or any chunk is buffered. The default
+/// accepts the default [`PasswordHashLimits`] and chunks of up to 16 MiB.
+pub struct PbencLimits {
+    /// Bounds on the password hash parameters
+    pub password_hash: PasswordHashLimits,
+    /// The largest chunk size accepted
+    pub max_chunk_size: usize,
+}
+
+impl Default for PbencLimits {
+    fn default() -> Self {
+        Self {
+            password_hash: PasswordHashLimits::default(),
+            max_chunk_size: 16 * 1024 * 1024,
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+/// The header of a password encrypted container
+///
+/// The header is self-describing; it records everything needed to
+/// decrypt the container other than the password. The Botan names of the
+/// algorithms it records are parsed into [`PasswordHashAlgorithm`] and
+/// [`CipherAlgorithm`], falling back to their `Arbitrary` variants for
+/// names not modeled by those types.
+pub struct PbencHeader {
+    version: u8,
+    params: PbencParams,
+    salt: Vec<u8>,
+    nonce_prefix: Vec<u8>,
+    encoding: Vec<u8>,
+}
+
+fn put_bytes(out: &mut Vec<u8>, bytes: &[u8]) -> Result<()> {
+    let len = u8::try_from(bytes.len()).map_err(|_| Error::bad_parameter("Field too long"))?;
+    out.push(len);
+    out.extend_from_slice(bytes);
+    Ok(())
+}
+
+fn put_u32(out: &mut Vec<u8>, v: usize) -> Result<()> {
+    let v = u32::try_from(v).map_err(|_| Error::bad_parameter("Parameter too large"))?;
+    out.extend_from_slice(&v.to_be_bytes());
+    Ok(())
+}
+
+struct HeaderReader<'a> {
+    data: &'a [u8],
+}
+
+impl<'a> HeaderReader<'a> {
+    fn take(&mut self, len: usize) -> Result<&'a [u8]> {
+        if self.data.len() < len {
+            return Err(header_error("truncated"));
+        }
+        let (head, tail) = self.data.split_at(len);
+        self.data = tail;
+        Ok(head)
+    }
+
+    fn bytes(&mut self) -> Result<&'a [u8]> {
+        let len = self.take(1)?[0];
+        self.take(len as usize)
+    }
+
+    fn string(&mut self) -> Result<String> {
+        String::from_utf8(self.bytes()?.to_vec()).map_err(|_| header_error("invalid name"))
+    }
+
+    fn u32(&mut self) -> Result<usize> {
+        let bytes: [u8; 4] = self.take(4)?.try_into().expect("correct length");
+        Ok(u32::from_be_bytes(bytes) as usize)
+    }
+}
+
+impl PbencHeader {
+    fn new(params: &PbencParams, salt: Vec<u8>, nonce_prefix: Vec<u8>) -> Result<Self> {
+        let mut body = Vec::new();
+        put_bytes(&mut body, params.password_hash.botan_name().as_bytes())?;
+        put_u32(&mut body, params.param1)?;
+        put_u32(&mut body, params.param2)?;
+        put_u32(&mut body, params.param3)?;
+        put_bytes(&mut body, &salt)?;
+        put_bytes(&mut body, params.cipher.botan_name().as_bytes())?;
+        put_u32(&mut body, params.chunk_size)?;
+        put_bytes(&mut body, &nonce_prefix)?;
+
+        let total_len = u16::try_from(PBENC_FIXED_PREFIX_LEN + body.len())
+            .map_err(|_| Error::bad_parameter("pbenc header too long"))?;
+
+        let mut encoding = PBENC_MAGIC.to_vec();
+        encoding.push(PBENC_VERSION);
+        encoding.extend_from_slice(&total_len.to_be_bytes());
+        encoding.extend_from_slice(&body);
+
+        Ok(Self {
+            version: PBENC_VERSION,
+            params: params.clone(),
+            salt,
+            nonce_prefix,
+            encoding,
+        })
+    }
+
+    /// Return the length of the header, if `data` begins with enough of
+    /// the header to determine it
+    fn encoded_len(data: &[u8]) -> Result<Option<usize>> {
+        let magic_len = data.len().min(PBENC_MAGIC.len());
+        if data[..magic_len] != PBENC_MAGIC[..magic_len] {
+            return Err(header_error("bad magic"));
+        }
+        if data.len() < PBENC_FIXED_PREFIX_LEN {
+            return Ok(None);
+        }
+        Ok(Some(u16::from_be_bytes([data[5], data[6]]) as usize))
+    }
+
+    /// Parse the header at the start of an encrypted container
+    ///
+    /// Any data following the header is ignored.
+    pub fn parse(data: &[u8]) -> Result<Self> {
+        let len = Self::encoded_len(data)?.ok_or_else(|| header_error("truncated"))?;
+        if len < PBENC_FIXED_PREFIX_LEN || data.len() < len {
+            return Err(header_error("truncated"));
+        }
+
+        let version = data[4];
+        if version != PBENC_VERSION {
+            return Err(header_error("unsupported version"));
+        }
+
+        let mut reader = HeaderReader {
+            data: &data[PBENC_FIXED_PREFIX_LEN..len],
+        };
+
+        let password_hash = reader.string()?.parse()?;
+        let param1 = reader.u32()?;
+        let param2 = reader.u32()?;
+        let param3 = reader.u32()?;
+        let salt = reader.bytes()?.to_vec();
+        let cipher = reader.string()?.parse()?;
+        let chunk_size = reader.u32()?;
+        let nonce_prefix = reader.bytes()?.to_vec();
+
+        if !reader.data.is_empty() {
+            return Err(header_error("trailing data"));
+        }
+        if chunk_size == 0 {
+            return Err(header_error("invalid chunk size"));
+        }
+
+        Ok(Self {
+            version,
+            params: PbencParams {
+                password_hash,
+                param1,
+                param2,
+                param3,
+                salt_len: salt.len(),
+                cipher,
+                chunk_size,
+            },
+            salt,
+            nonce_prefix,
+            encoding: data[..len].to_vec(),
+        })
+    }
+
+    /// Return the format version
+    #[must_use]
+    pub fn version(&self) -> u8 {
+        self.version
+    }
+
+    /// Return the parameters used to encrypt the container
+    #[must_use]
+    pub fn params(&self) -> &PbencParams {
+        &self.params
+    }
+
+    /// Return the password hash salt
+    #[must_use]
+    pub fn salt(&self) -> &[u8] {
+        &self.salt
+    }
+
+    /// Return the encoded header
+    #[must_use]
+    pub fn as_bytes(&self) -> &[u8] {
+        &self.encoding
+    }
+
+    /// Check that the header is within the given limits
+    ///
+    /// Returns an error of type [`ErrorType::InvalidInput`] if the password
+    /// hash parameters or chunk size exceed the limits.
+    pub fn check_limits(&self, limits: &PbencLimits) -> Result<()> {
+        let params = &self.params;
+
+        if params.chunk_size > limits.max_chunk_size {
+            return Err(header_error(&format!(
+                "chunk size {} exceeds the limit of {}",
+                params.chunk_size, limits.max_chunk_size
+            )));
+        }
+
+        limits.password_hash.check(&PasswordHashParams::new(
+            params.password_hash.clone(),
+            params.param1,
+            params.param2,
+            params.param3,
+        ))
+    }
+
+    /// Check if the container should be re-encrypted with the default parameters
+    ///
+    /// See [`PbencHeader::needs_upgrade_to`]
+    #[must_use]
+    pub fn needs_upgrade(&self) -> bool {
+        self.needs_upgrade_to(&PbencParams::default())
+    }
+
+    /// Check if the container is weaker than the given policy
+    ///
+    /// This returns true if the container uses an older format version, a
+    /// different password hash or cipher than the policy, password hash
+    /// parameters smaller than the policy's, or a shorter salt.
+    #[must_use]
+    pub fn needs_upgrade_to(&self, policy: &PbencParams) -> bool {
+        let params = &self.params;
+
+        self.version < PBENC_VERSION
+            || params.password_hash.botan_name() != policy.password_hash.botan_name()
+            || params.param1 < policy.param1
+            || params.param2 < policy.param2
+            || params.param3 < policy.param3
+            || self.salt.len() < policy.salt_len
+            || params.cipher.botan_name() != policy.cipher.botan_name()
+    }
+}
+
+/// Derive the key from the password, and set up the chunked AEAD
+///
+/// The header is authenticated along with every chunk.
+fn pbenc_chunks(
+    password: &str,
+    header: &PbencHeader,
+    direction: CipherDirection,
+) -> Result<SegmentedAead> {
+    let params = &header.params;
+    let cipher = Cipher::new(params.cipher.clone(), direction)?;
+
+    if cipher.tag_length() == 0 {
+        return Err(Error::bad_parameter("pbenc requires an AEAD cipher"));
+    }
+    if header.nonce_prefix.len() + SEGMENT_NONCE_SUFFIX_LEN != cipher.default_nonce_length() {
+        return Err(header_error("nonce length does not match cipher"));
+    }
+
+    let mut chunks = SegmentedAead::new(
+        cipher,
+        &header.nonce_prefix,
+        &header.encoding,
+        params.chunk_size,
+    )?;
+
+    let key = derive_key_from_password(
+        params.password_hash.clone(),
+        chunks.maximum_keylength()?,
+        password,
+        &header.salt,
+        params.param1,
+        params.param2,
+        params.param3,
+    )?;
+    chunks.set_key(&key)?;
+    Ok(chunks)
+}
+
+/// Incremental password based encryption
+///
+/// The output begins with a [`PbencHeader`], followed by the input
+/// split into chunks which are each encrypted and authenticated
+/// separately, so the container can be decrypted with bounded memory.
+pub struct PbencEncryptor {
+    header: PbencHeader,
+    chunks: SegmentedAead,
+    header_written: bool,
+}
+
+impl PbencEncryptor {
+    /// Derive a key from the password with a fresh salt and start encrypting
+    pub fn new(
+        password: &str,
+        params: &PbencParams,
+        rng: &mut RandomNumberGenerator,
+    ) -> Result<Self> {
+        if params.chunk_size == 0 {
+            return Err(Error::bad_parameter("Invalid pbenc chunk size"));
+        }
+        let nonce_len =
+            Cipher::new(params.cipher.clone(), CipherDirection::Encrypt)?.default_nonce_length();
+        if nonce_len < SEGMENT_NONCE_SUFFIX_LEN {
+            return Err(Error::bad_parameter("pbenc cipher nonce too short"));
+        }
+
+        let salt = rng.read(params.salt_len)?;
+        let nonce_prefix = rng.read(nonce_len - SEGMENT_NONCE_SUFFIX_LEN)?;
+        let header = PbencHeader::new(params, salt, nonce_prefix)?;
+        let chunks = pbenc_chunks(password, &header, CipherDirection::Encrypt)?;
+
+        Ok(Self {
+            header,
+            chunks,
+            header_written: false,
+        })
+    }
+
+    /// Return the header of the container being written
+    #[must_use]
+    pub fn header(&self) -> &PbencHeader {
+        &self.header
+    }
+
+    fn take_header(&mut self) -> Vec<u8> {
+        if self.header_written {
+            return Vec::new();
+        }
+        self.header_written = true;
+        self.header.encoding.clone()
+    }
+
+    /// Encrypt more input, returning whatever output is ready
+    ///
+    /// A chunk is only output once it is known not to be the final chunk.
+    pub fn update(&mut self, input: &[u8]) -> Result<Vec<u8>> {
+        let mut out = self.take_header();
+        out.extend_from_slice(&self.chunks.update(input)?);
+        Ok(out)
+    }
+
+    /// Encrypt the final chunk and finish the container
+    pub fn finish(mut self) -> Result<Vec<u8>> {
+        let mut out = self.take_header();
+        out.extend_from_slice(&self.chunks.finish()?);
+        Ok(out)
+    }
+}
+
+/// Incremental password based decryption
+///
+/// Plaintext is only returned after the chunk containing it has been
+/// authenticated. Truncation, reordering, or modification of the
+/// chunks, or modification of the header, is detected.
+pub struct PbencDecryptor {
+    password: String,
+    limits: PbencLimits,
+    header: Option<PbencHeader>,
+    chunks: Option<SegmentedAead>,
+    buffer: Vec<u8>,
+}
+
+impl PbencDecryptor {
+    /// Start decrypting with the given password
+    ///
+    /// The key is derived once the complete header has been provided to
+    /// [`PbencDecryptor::update`]. The header must be within the default
+    /// [`PbencLimits`].
+    #[must_use]
+    pub fn new(password: &str) -> Self {
+        Self {
+            password: password.to_owned(),
+            limits: PbencLimits::default(),
+            header: None,
+            chunks: None,
+            buffer: Vec::new(),
+        }
+    }
+
+    /// Set the limits the header must be within
+    #[must_use]
+    pub fn with_limits(mut self, limits: PbencLimits) -> Self {
+        self.limits = limits;
+        self
+    }
+
+    /// Return the header, once it has been read
+    #[must_use]
+    pub fn header(&self) -> Option<&PbencHeader> {
+        self.header.as_ref()
+    }
+
+    /// Decrypt more input, returning any plaintext which has been authenticated
+    pub fn update(&mut self, input: &[u8]) -> Result<Vec<u8>> {
+        if let Some(chunks) = self.chunks.as_mut() {
+            return chunks.update(input);
+        }
+
+        // Until the header is complete, the input is buffered here
+        self.buffer.extend_from_slice(input);
+        let len = match PbencHeader::encoded_len(&self.buffer)? {
+            Some(len) if self.buffer.len() >= len => len,
+            _ => return Ok(Vec::new()),
+        };
+
+        let header = PbencHeader::parse(&self.buffer)?;
+        header.check_limits(&self.limits)?;
+        let mut chunks = pbenc_chunks(&self.password, &header, CipherDirection::Decrypt)?;
+        let out = chunks.update(&self.buffer[len..])?;
+
+        self.buffer = Vec::new();
+        self.header = Some(header);
+        self.chunks = Some(chunks);
+        Ok(out)
+    }
+
+    /// Decrypt the final chunk, verifying that the container is complete
+    pub fn finish(mut self) -> Result<Vec<u8>> {
+        let chunks = self
+            .chunks
+            .take()
+            .ok_or_else(|| header_error("truncated"))?;
+        chunks.finish()
+    }
+}
+
+/// Encrypt a message with a password
+///
+/// # Examples
+///
+/// ```
+/// let mut rng = botan::RandomNumberGenerator::new_system().unwrap();
+/// let params = botan::PbencParams {
+///     param1: 1024, // Argon2 memory in KiB, reduced to keep the example fast
+///     ..Default::default()
+/// };
+/// let ctext = botan::pbenc_encrypt("correct horse", b"secret", &params, &mut rng).unwrap();
+/// let header = botan::PbencHeader::parse(&ctext).unwrap();
+/// assert!(header.needs_upgrade());
+/// assert_eq!(botan::pbenc_decrypt("correct horse", &ctext).unwrap(), b"secret");
+/// ```
+pub fn pbenc_encrypt(
+    password: &str,
+    plaintext: &[u8],
+    params: &PbencParams,
+    rng: &mut RandomNumberGenerator,
+) -> Result<Vec<u8>> {
+    let mut enc = PbencEncryptor::new(password, params, rng)?;
+    let mut out = enc.update(plaintext)?;
+    out.extend_from_slice(&enc.finish()?);
+    Ok(out)
+}
+
+/// Decrypt a message encrypted with [`pbenc_encrypt`] or [`PbencEncryptor`]
+///
+/// If the password is wrong or the container has been modified, an error of
+/// type [`ErrorType::BadAuthCode`](crate::ErrorType::BadAuthCode) is returned.
+/// A header exceeding the default [`PbencLimits`] is rejected; use
+/// [`PbencDecryptor::with_limits`] to accept more expensive containers.
+pub fn pbenc_decrypt(password: &str, ciphertext: &[u8]) -> Result<Vec<u8>> {
+    let mut dec = PbencDecryptor::new(password);
+    let mut out = dec.update(ciphertext)?;
+    out.extend_from_slice(&dec.finish()?);
+    Ok(out)
+}
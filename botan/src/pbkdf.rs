@@ -1,6 +1,11 @@
 use crate::utils::*;
 use botan_sys::*;
 
+use core::fmt;
+use core::str::FromStr;
+
+use crate::{HashAlgorithm, PasswordHashAlgorithm};
+
 /// Password based key derivation function
 ///
 /// # Examples
@@ -143,3 +148,307 @@ pub fn scrypt(
         p,
     )
 }
+
+/// A password hash algorithm along with its parameters
+///
+/// This is typically produced by [`PasswordHashParams::calibrate`] and
+/// then stored in configuration; it can be converted to and from a string
+/// of the form `Argon2id:65536:3:4`.
+///
+/// The parameters are interpreted as for [`derive_key_from_password`]:
+/// for Argon2 they are the memory in KiB, the number of iterations, and
+/// the parallelism; for scrypt N, r and p; and for PBKDF2 the iteration
+/// count.
+///
+/// # Examples
+/// ```
+/// let params = botan::PasswordHashParams::calibrate(botan::PasswordHashAlgorithm::Argon2id, 20, Some(64)).unwrap();
+/// assert!(params.param1 <= 64 * 1024);
+/// let stored = params.to_string();
+///
+/// let params: botan::PasswordHashParams = stored.parse().unwrap();
+/// let key = botan::derive_key_from_password_with_params(&params, 32, "passphrase", &[0; 16]).unwrap();
+/// assert_eq!(key.len(), 32);
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct PasswordHashParams {
+    /// The password hashing algorithm
+    pub algorithm: PasswordHashAlgorithm,
+    /// The first algorithm parameter
+    pub param1: usize,
+    /// The second algorithm parameter
+    pub param2: usize,
+    /// The third algorithm parameter
+    pub param3: usize,
+}
+
+impl PasswordHashParams {
+    /// Create a new set of parameters
+    #[must_use]
+    pub fn new(
+        algorithm: PasswordHashAlgorithm,
+        param1: usize,
+        param2: usize,
+        param3: usize,
+    ) -> Self {
+        Self {
+            algorithm,
+            param1,
+            param2,
+            param3,
+        }
+    }
+
+    /// Choose parameters which take approximately `msec` milliseconds on this host
+    ///
+    /// If `max_memory_mib` is set, the memory-hard algorithms are adjusted
+    /// to use at most that many MiB, trading memory for additional passes
+    /// (Argon2) or parallelism (scrypt) to keep the running time similar.
+    /// The scrypt parallelism is kept within [`PasswordHashLimits::default`],
+    /// so a small enough limit also shortens the running time.
+    pub fn calibrate(
+        algorithm: PasswordHashAlgorithm,
+        msec: u32,
+        max_memory_mib: Option<usize>,
+    ) -> Result<Self> {
+        let (_, iterations, parallelism, memory) =
+            derive_key_from_password_timed(algorithm.clone(), 32, "calibration", &[0u8; 16], msec)?;
+
+        // The timed interface reports iterations, parallelism and memory,
+        // which for the memory hard algorithms is not the order used to
+        // specify them (for scrypt, "iterations" is r and "memory" is N)
+        let mut params = match algorithm {
+            PasswordHashAlgorithm::Argon2d
+            | PasswordHashAlgorithm::Argon2i
+            | PasswordHashAlgorithm::Argon2id
+            | PasswordHashAlgorithm::Scrypt => {
+                Self::new(algorithm, memory, iterations, parallelism)
+            }
+            _ => Self::new(algorithm, iterations, parallelism, memory),
+        };
+        if let Some(max_memory_mib) = max_memory_mib {
+            params.limit_memory(max_memory_mib.saturating_mul(1024 * 1024))?;
+        }
+        Ok(params)
+    }
+
+    /// Return the approximate memory required, in bytes, if the algorithm is memory hard
+    #[must_use]
+    pub fn memory_usage(&self) -> Option<usize> {
+        match self.algorithm {
+            PasswordHashAlgorithm::Argon2d
+            | PasswordHashAlgorithm::Argon2i
+            | PasswordHashAlgorithm::Argon2id => Some(self.param1.saturating_mul(1024)),
+            PasswordHashAlgorithm::Scrypt => {
+                Some(self.param1.saturating_mul(self.param2).saturating_mul(128))
+            }
+            _ => None,
+        }
+    }
+
+    fn limit_memory(&mut self, max_memory: usize) -> Result<()> {
+        let Some(usage) = self.memory_usage() else {
+            return Ok(());
+        };
+        if usage <= max_memory {
+            return Ok(());
+        }
+
+        match self.algorithm {
+            PasswordHashAlgorithm::Scrypt => {
+                // Time is linear in N * p, memory only in N. p stays within
+                // the default limits, so that the hash can still be verified
+                let max_p = PasswordHashLimits::default().max_parallelism;
+                while self.memory_usage().unwrap_or(0) > max_memory && self.param1 > 2 {
+                    self.param1 /= 2;
+                    if self.param3.saturating_mul(2) <= max_p {
+                        self.param3 *= 2;
+                    }
+                }
+            }
+            _ => {
+                // Argon2 requires at least 8 KiB per lane, and time is linear in memory * passes
+                let max_kib = max_memory / 1024;
+                if max_kib >= 8 * self.param3 {
+                    self.param2 = self.param2.saturating_mul(self.param1).div_ceil(max_kib);
+                    self.param1 = max_kib;
+                }
+            }
+        }
+
+        if self.memory_usage().unwrap_or(0) > max_memory {
+            return Err(Error::bad_parameter(
+                "Memory limit too small for password hash",
+            ));
+        }
+        Ok(())
+    }
+}
+
+impl fmt::Display for PasswordHashParams {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "{}:{}:{}:{}",
+            self.algorithm.botan_name(),
+            self.param1,
+            self.param2,
+            self.param3
+        )
+    }
+}
+
+/// Serialized as a string of the form `Argon2id:65536:3:4`
+#[cfg(feature = "serde")]
+impl serde::Serialize for PasswordHashParams {
+    fn serialize<S: serde::Serializer>(
+        &self,
+        serializer: S,
+    ) -> core::result::Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de> serde::Deserialize<'de> for PasswordHashParams {
+    fn deserialize<D: serde::Deserializer<'de>>(
+        deserializer: D,
+    ) -> core::result::Result<Self, D::Error> {
+        let s = <String as serde::Deserialize>::deserialize(deserializer)?;
+        s.parse().map_err(serde::de::Error::custom)
+    }
+}
+
+impl FromStr for PasswordHashParams {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        let invalid = || {
+            Error::with_message(
+                ErrorType::InvalidInput,
+                format!("Invalid password hash parameters '{s}'"),
+            )
+        };
+
+        let mut fields = s.rsplitn(4, ':');
+        let mut param = || -> Result<usize> {
+            fields
+                .next()
+                .and_then(|p| p.parse().ok())
+                .ok_or_else(invalid)
+        };
+        let param3 = param()?;
+        let param2 = param()?;
+        let param1 = param()?;
+
+        let name = fields
+            .next()
+            .filter(|n| !n.is_empty())
+            .ok_or_else(invalid)?;
+
+        Ok(Self::new(name.parse()?, param1, param2, param3))
+    }
+}
+
+/// Password based key derivation using previously chosen parameters
+///
+/// See [`PasswordHashParams`] for an example.
+pub fn derive_key_from_password_with_params(
+    params: &PasswordHashParams,
+    out_len: usize,
+    passphrase: &str,
+    salt: &[u8],
+) -> Result<Vec<u8>> {
+    derive_key_from_password(
+        params.algorithm.clone(),
+        out_len,
+        passphrase,
+        salt,
+        params.param1,
+        params.param2,
+        params.param3,
+    )
+}
+
+/// Upper bounds on the cost of a password hash whose parameters come from
+/// untrusted input
+///
+/// Verifying a stored password hash, or decrypting a password encrypted
+/// container, uses the parameters recorded along with it; without bounds a
+/// hostile input can demand an arbitrary amount of memory or time. The
+/// defaults accept up to 1 GiB of memory, 64 Argon2 passes or bcrypt-PBKDF
+/// rounds, a parallelism of 16, 10 million PBKDF2 iterations, and a 64 byte
+/// hash, which is well beyond any recommended setting.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct PasswordHashLimits {
+    /// The most memory, in KiB, which Argon2 or scrypt may use
+    pub max_memory_kib: usize,
+    /// The most passes of Argon2, or rounds of bcrypt-PBKDF
+    pub max_passes: usize,
+    /// The most lanes of Argon2, or the largest scrypt p
+    pub max_parallelism: usize,
+    /// The most iterations of PBKDF2, the PKCS #12 KDF, or OpenPGP S2K
+    pub max_iterations: usize,
+    /// The longest hash, in bytes, of a stored password hash being verified
+    pub max_hash_len: usize,
+}
+
+impl Default for PasswordHashLimits {
+    fn default() -> Self {
+        Self {
+            max_memory_kib: 1024 * 1024,
+            max_passes: 64,
+            max_parallelism: 16,
+            max_iterations: 10_000_000,
+            max_hash_len: 64,
+        }
+    }
+}
+
+impl PasswordHashLimits {
+    /// Check that the parameters are within these limits
+    ///
+    /// Returns an error of type [`ErrorType::InvalidInput`] if they are not,
+    /// or if the algorithm is not one whose cost is understood (an
+    /// [`PasswordHashAlgorithm::Arbitrary`] name).
+    pub fn check(&self, params: &PasswordHashParams) -> Result<()> {
+        let limit = |what: &str, value: usize, max: usize| {
+            if value > max {
+                Err(Error::with_message(
+                    ErrorType::InvalidInput,
+                    format!(
+                        "{} {what} of {value} exceeds the limit of {max}",
+                        params.algorithm.botan_name()
+                    ),
+                ))
+            } else {
+                Ok(())
+            }
+        };
+
+        match &params.algorithm {
+            PasswordHashAlgorithm::Argon2d
+            | PasswordHashAlgorithm::Argon2i
+            | PasswordHashAlgorithm::Argon2id
+            | PasswordHashAlgorithm::Scrypt => {
+                let memory_kib = params.memory_usage().unwrap_or(usize::MAX) / 1024;
+                limit("memory (KiB)", memory_kib, self.max_memory_kib)?;
+                if params.algorithm != PasswordHashAlgorithm::Scrypt {
+                    limit("passes", params.param2, self.max_passes)?;
+                }
+                limit("parallelism", params.param3, self.max_parallelism)
+            }
+            PasswordHashAlgorithm::BcryptPbkdf => limit("rounds", params.param1, self.max_passes),
+            PasswordHashAlgorithm::OpenPgpS2k(_)
+            | PasswordHashAlgorithm::Pbkdf2(_)
+            | PasswordHashAlgorithm::Pbkdf2Mac(_)
+            | PasswordHashAlgorithm::Pkcs12Kdf(_, _) => {
+                limit("iterations", params.param1, self.max_iterations)
+            }
+            PasswordHashAlgorithm::Arbitrary(name) => Err(Error::with_message(
+                ErrorType::InvalidInput,
+                format!("Unable to bound the cost of password hash '{name}'"),
+            )),
+        }
+    }
+}
@@ -2,6 +2,7 @@ use crate::utils::*;
 use botan_sys::*;
 
 use crate::EcGroup;
+use crate::OID;
 
 use crate::{EcPoint, EcScalar};
 
@@ -597,8 +598,17 @@ impl Privkey {
     /// This requires Botan 3.12 or later; with older versions an error of type
     /// [`ErrorType::NotImplemented`](crate::ErrorType::NotImplemented) is returned
     pub fn get_private_value(&self) -> Result<EcScalar> {
+        let group = self.get_group()?;
         let obj = botan_init_at!(botan_ec_privkey_get_private_key, self.obj;)?;
-        Ok(EcScalar::from_handle(obj))
+        Ok(EcScalar::from_handle(obj, group))
+    }
+
+    /// Return the object identifier of this key's algorithm
+    ///
+    /// This requires Botan 3.8 or later; with older versions an error of type
+    /// [`ErrorType::NotImplemented`](crate::ErrorType::NotImplemented) is returned
+    pub fn oid(&self) -> Result<OID> {
+        OID::from_handle(botan_init!(botan_privkey_oid, self.obj)?)
     }
 
     /// Return the group associated with this key
@@ -609,7 +619,7 @@ impl Privkey {
     /// [`ErrorType::NotImplemented`](crate::ErrorType::NotImplemented) is returned
     pub fn get_group(&self) -> Result<EcGroup> {
         let obj = botan_init_at!(botan_ec_privkey_get_group, self.obj;)?;
-        Ok(EcGroup::from_handle(obj))
+        EcGroup::from_handle(obj)
     }
 
     /// Get the raw bytes associated with this key
@@ -895,6 +905,14 @@ impl Pubkey {
         Ok(r)
     }
 
+    /// Return the object identifier of this key's algorithm
+    ///
+    /// This requires Botan 3.8 or later; with older versions an error of type
+    /// [`ErrorType::NotImplemented`](crate::ErrorType::NotImplemented) is returned
+    pub fn oid(&self) -> Result<OID> {
+        OID::from_handle(botan_init!(botan_pubkey_oid, self.obj)?)
+    }
+
     /// Return the group associated with this key
     ///
     /// Only valid for EC based keys
@@ -903,7 +921,7 @@ impl Pubkey {
     /// [`ErrorType::NotImplemented`](crate::ErrorType::NotImplemented) is returned
     pub fn get_group(&self) -> Result<EcGroup> {
         let obj = botan_init_at!(botan_ec_pubkey_get_group, self.obj; )?;
-        Ok(EcGroup::from_handle(obj))
+        EcGroup::from_handle(obj)
     }
 
     /// Return the raw byte encoding of this key
@@ -958,6 +976,58 @@ impl Pubkey {
     }
 }
 
+/// Serialized as a PEM string; base64 encoded DER is also accepted when
+/// deserializing.
+#[cfg(feature = "serde")]
+impl serde::Serialize for Pubkey {
+    fn serialize<S: serde::Serializer>(
+        &self,
+        serializer: S,
+    ) -> core::result::Result<S::Ok, S::Error> {
+        let pem = self.pem_encode().map_err(serde::ser::Error::custom)?;
+        serializer.serialize_str(&pem)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de> serde::Deserialize<'de> for Pubkey {
+    fn deserialize<D: serde::Deserializer<'de>>(
+        deserializer: D,
+    ) -> core::result::Result<Self, D::Error> {
+        crate::memutils::deserialize_pem_or_der(deserializer, Pubkey::load_pem, Pubkey::load_der)
+    }
+}
+
+/// Opt-in serde support for private keys
+///
+/// `Privkey` deliberately does not implement `Serialize` or `Deserialize`,
+/// so that a private key cannot be written out as part of a larger structure
+/// by accident. A field holding a private key can instead opt in with
+/// `#[serde(with = "botan::serde_privkey")]`, which stores the key as an
+/// unencrypted PKCS #8 PEM string. When deserializing, base64 encoded PKCS #8
+/// DER is also accepted.
+#[cfg(feature = "serde")]
+pub mod serde_privkey {
+    use super::Privkey;
+
+    /// Serialize a private key as an unencrypted PKCS #8 PEM string
+    pub fn serialize<S: serde::Serializer>(
+        key: &Privkey,
+        serializer: S,
+    ) -> core::result::Result<S::Ok, S::Error> {
+        let pem = key.pem_encode().map_err(serde::ser::Error::custom)?;
+        serializer.serialize_str(&pem)
+    }
+
+    /// Deserialize a private key from unencrypted PKCS #8, either as PEM or as
+    /// base64 encoded DER
+    pub fn deserialize<'de, D: serde::Deserializer<'de>>(
+        deserializer: D,
+    ) -> core::result::Result<Privkey, D::Error> {
+        crate::memutils::deserialize_pem_or_der(deserializer, Privkey::load_pem, Privkey::load_der)
+    }
+}
+
 /// Return the identifier used for PKCS1 v1.5 signatures for the specified hash
 pub fn pkcs_hash_id<A: crate::HashAlgorithmIdentifier>(hash_algo: A) -> Result<Vec<u8>> {
     let hash_algo = hash_algo.botan_name();
@@ -0,0 +1,520 @@
+//! Shamir secret sharing over GF(2^8), and Feldman verifiable secret
+//! sharing over an elliptic curve group
+
+use crate::utils::*;
+
+use core::fmt;
+
+use crate::{EcGroup, EcPoint, EcScalar, HashFunction, MPI, RandomNumberGenerator, hex_encode};
+
+const SHAMIR_ID_LEN: usize = 16;
+const SHAMIR_HEADER_LEN: usize = SHAMIR_ID_LEN + 4;
+
+// Multiplication in GF(2^8) modulo x^8 + x^4 + x^3 + x + 1, without tables
+fn gf256_mul(mut a: u8, mut b: u8) -> u8 {
+    let mut r = 0u8;
+    for _ in 0..8 {
+        r ^= a & 0u8.wrapping_sub(b & 1);
+        a = (a << 1) ^ (0x1b & 0u8.wrapping_sub(a >> 7));
+        b >>= 1;
+    }
+    r
+}
+
+fn gf256_inv(a: u8) -> u8 {
+    // a^254 = a^-1
+    let mut r = 1u8;
+    let mut base = a;
+    let mut e = 254u8;
+    while e > 0 {
+        if e & 1 == 1 {
+            r = gf256_mul(r, base);
+        }
+        base = gf256_mul(base, base);
+        e >>= 1;
+    }
+    r
+}
+
+fn shamir_checksum_hash(hash_id: u8) -> Result<Option<&'static str>> {
+    match hash_id {
+        0 => Ok(None),
+        1 => Ok(Some("SHA-1")),
+        2 => Ok(Some("SHA-256")),
+        _ => Err(Error::with_message(
+            ErrorType::InvalidInput,
+            format!("Unknown secret share checksum type {hash_id}"),
+        )),
+    }
+}
+
+fn shamir_checksum(hash: &str, secret: &[u8]) -> Result<Vec<u8>> {
+    let mut hash = HashFunction::new(hash)?;
+    hash.update(secret)?;
+    hash.finish()
+}
+
+/// One share of a secret split using [`shamir_split`]
+///
+/// The encoding is the RTSS format also used by Botan's C++ `RTSS_Share`:
+/// a 16 byte identifier common to all shares of a secret, the checksum
+/// algorithm, the threshold, and the share length, followed by the share
+/// index and the share data. The data includes a checksum of the secret,
+/// which is verified when the shares are combined.
+#[derive(Clone, PartialEq, Eq)]
+pub struct ShamirShare {
+    contents: Vec<u8>,
+}
+
+impl fmt::Debug for ShamirShare {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("ShamirShare")
+            .field("id", &hex_encode(self.id()).map_err(|_| fmt::Error)?)
+            .field("threshold", &self.threshold())
+            .field("index", &self.index())
+            .finish_non_exhaustive()
+    }
+}
+
+impl ShamirShare {
+    /// Parse an encoded share
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self> {
+        if bytes.len() <= SHAMIR_HEADER_LEN + 1 {
+            return Err(Error::with_message(
+                ErrorType::InvalidInput,
+                "Secret share is too short".to_string(),
+            ));
+        }
+
+        let share = Self {
+            contents: bytes.to_vec(),
+        };
+
+        shamir_checksum_hash(share.contents[SHAMIR_ID_LEN])?;
+        if share.share_len() != bytes.len() - SHAMIR_HEADER_LEN
+            || share.threshold() == 0
+            || share.index() == 0
+        {
+            return Err(Error::with_message(
+                ErrorType::InvalidInput,
+                "Invalid secret share".to_string(),
+            ));
+        }
+
+        Ok(share)
+    }
+
+    /// Return the encoded share
+    #[must_use]
+    pub fn to_bytes(&self) -> Vec<u8> {
+        self.contents.clone()
+    }
+
+    /// Return the identifier shared by all shares of the same secret
+    #[must_use]
+    pub fn id(&self) -> &[u8] {
+        &self.contents[..SHAMIR_ID_LEN]
+    }
+
+    /// Return the number of shares required to recover the secret
+    #[must_use]
+    pub fn threshold(&self) -> u8 {
+        self.contents[SHAMIR_ID_LEN + 1]
+    }
+
+    /// Return the index of this share, starting from 1
+    #[must_use]
+    pub fn index(&self) -> u8 {
+        self.contents[SHAMIR_HEADER_LEN]
+    }
+
+    fn share_len(&self) -> usize {
+        u16::from_be_bytes([
+            self.contents[SHAMIR_ID_LEN + 2],
+            self.contents[SHAMIR_ID_LEN + 3],
+        ]) as usize
+    }
+
+    fn data(&self) -> &[u8] {
+        &self.contents[SHAMIR_HEADER_LEN + 1..]
+    }
+}
+
+/// Split a secret into `count` shares, any `threshold` of which recover it
+///
+/// Each byte of the secret (and of a SHA-256 checksum of it) is shared
+/// using an independent random polynomial over GF(2^8), so fewer than
+/// `threshold` shares reveal nothing about the secret. All shares are
+/// tagged with a random 16 byte identifier.
+///
+/// # Examples
+/// ```
+/// let mut rng = botan::RandomNumberGenerator::new().unwrap();
+/// let secret = b"master key material";
+/// let shares = botan::shamir_split(3, 5, secret, &mut rng).unwrap();
+/// assert_eq!(shares.len(), 5);
+///
+/// let some = [shares[4].clone(), shares[0].clone(), shares[2].clone()];
+/// assert_eq!(botan::shamir_combine(&some).unwrap(), secret);
+/// ```
+pub fn shamir_split(
+    threshold: u8,
+    count: u8,
+    secret: &[u8],
+    rng: &mut RandomNumberGenerator,
+) -> Result<Vec<ShamirShare>> {
+    if threshold < 2 || count < threshold || count == u8::MAX {
+        return Err(Error::bad_parameter("Invalid secret sharing parameters"));
+    }
+
+    let mut padded = secret.to_vec();
+    padded.extend_from_slice(&shamir_checksum("SHA-256", secret)?);
+
+    let share_len = u16::try_from(padded.len() + 1)
+        .map_err(|_| Error::bad_parameter("Secret is too large to be shared"))?;
+
+    let mut header = rng.read(SHAMIR_ID_LEN)?;
+    header.push(2); // SHA-256
+    header.push(threshold);
+    header.extend_from_slice(&share_len.to_be_bytes());
+
+    let mut shares: Vec<Vec<u8>> = (1..=count)
+        .map(|index| {
+            let mut contents = Vec::with_capacity(SHAMIR_HEADER_LEN + share_len as usize);
+            contents.extend_from_slice(&header);
+            contents.push(index);
+            contents
+        })
+        .collect();
+
+    let mut coefficients = vec![0u8; threshold as usize];
+
+    for &byte in &padded {
+        coefficients[0] = byte;
+        rng.fill(&mut coefficients[1..])?;
+
+        for (share, x) in shares.iter_mut().zip(1..=count) {
+            // Horner's rule, from the highest degree coefficient
+            let y = coefficients
+                .iter()
+                .rev()
+                .fold(0u8, |acc, &c| gf256_mul(acc, x) ^ c);
+            share.push(y);
+        }
+    }
+
+    crate::scrub_mem(&mut coefficients);
+    crate::scrub_mem(&mut padded);
+
+    Ok(shares
+        .into_iter()
+        .map(|contents| ShamirShare { contents })
+        .collect())
+}
+
+/// Recover a secret from shares created by [`shamir_split`]
+///
+/// At least as many shares with distinct indexes as the threshold must be
+/// provided, all with the same identifier. A share repeating the index of
+/// an earlier one is ignored, and the first threshold distinct shares are
+/// used. Fails if the recovered checksum does not match, which indicates a
+/// corrupted share or shares of different secrets.
+pub fn shamir_combine(shares: &[ShamirShare]) -> Result<Vec<u8>> {
+    let invalid = |msg: &str| Error::with_message(ErrorType::InvalidInput, msg.to_string());
+
+    let first = shares
+        .first()
+        .ok_or_else(|| invalid("No secret shares provided"))?;
+
+    for share in shares {
+        if share.contents.len() != first.contents.len()
+            || share.contents[..SHAMIR_HEADER_LEN] != first.contents[..SHAMIR_HEADER_LEN]
+        {
+            return Err(invalid("Secret shares do not belong to the same secret"));
+        }
+    }
+
+    let threshold = first.threshold() as usize;
+
+    let mut distinct: Vec<&ShamirShare> = Vec::with_capacity(threshold);
+    for share in shares {
+        if distinct.len() == threshold {
+            break;
+        }
+        if distinct.iter().all(|s| s.index() != share.index()) {
+            distinct.push(share);
+        }
+    }
+
+    if distinct.len() < threshold {
+        return Err(invalid("Insufficient secret shares to recover the secret"));
+    }
+
+    let shares = distinct;
+    let xs: Vec<u8> = shares.iter().map(|s| s.index()).collect();
+
+    // Lagrange basis polynomials evaluated at zero
+    let basis: Vec<u8> = xs
+        .iter()
+        .enumerate()
+        .map(|(i, &xi)| {
+            xs.iter()
+                .enumerate()
+                .filter(|&(j, _)| j != i)
+                .fold(1u8, |acc, (_, &xj)| {
+                    gf256_mul(acc, gf256_mul(xj, gf256_inv(xj ^ xi)))
+                })
+        })
+        .collect();
+
+    let mut recovered: Vec<u8> = (0..first.data().len())
+        .map(|pos| {
+            shares.iter().zip(&basis).fold(0u8, |acc, (share, &l)| {
+                acc ^ gf256_mul(share.data()[pos], l)
+            })
+        })
+        .collect();
+
+    if let Some(hash) = shamir_checksum_hash(first.contents[SHAMIR_ID_LEN])? {
+        let checksum_len = shamir_checksum(hash, &[])?.len();
+        if recovered.len() < checksum_len {
+            return Err(invalid("Invalid secret share"));
+        }
+        let secret_len = recovered.len() - checksum_len;
+        let expected = shamir_checksum(hash, &recovered[..secret_len])?;
+        if !crate::const_time_compare(&expected, &recovered[secret_len..]) {
+            crate::scrub_mem(&mut recovered);
+            return Err(invalid("Secret share checksum mismatch"));
+        }
+        recovered.truncate(secret_len);
+    }
+
+    Ok(recovered)
+}
+
+/// One share of a secret scalar split using [`feldman_split`]
+#[derive(Debug)]
+pub struct FeldmanShare {
+    index: u32,
+    value: EcScalar,
+}
+
+impl FeldmanShare {
+    /// Create a share from its index and value
+    #[must_use]
+    pub fn new(index: u32, value: EcScalar) -> Self {
+        Self { index, value }
+    }
+
+    /// Return the index of this share, starting from 1
+    #[must_use]
+    pub fn index(&self) -> u32 {
+        self.index
+    }
+
+    /// Return the value of this share
+    #[must_use]
+    pub fn value(&self) -> &EcScalar {
+        &self.value
+    }
+}
+
+/// The public commitments to the polynomial used by [`feldman_split`]
+///
+/// These are published to all shareholders, who can use
+/// [`FeldmanCommitments::verify`] to check that their share is consistent
+/// with the others without learning anything about the secret.
+#[derive(Debug)]
+pub struct FeldmanCommitments {
+    points: Vec<EcPoint>,
+}
+
+impl FeldmanCommitments {
+    /// Create commitments from the points `a_i*G`, constant term first
+    pub fn new(points: Vec<EcPoint>) -> Result<Self> {
+        if points.is_empty() {
+            return Err(Error::bad_parameter("Feldman commitments cannot be empty"));
+        }
+        Ok(Self { points })
+    }
+
+    /// Return the commitment points, constant term first
+    #[must_use]
+    pub fn points(&self) -> &[EcPoint] {
+        &self.points
+    }
+
+    /// Return the number of shares required to recover the secret
+    #[must_use]
+    pub fn threshold(&self) -> usize {
+        self.points.len()
+    }
+
+    /// Return the commitment to the secret itself, `secret*G`
+    #[must_use]
+    pub fn public_value(&self) -> &EcPoint {
+        &self.points[0]
+    }
+
+    /// Check that a share is consistent with these commitments
+    pub fn verify(
+        &self,
+        group: &EcGroup,
+        share: &FeldmanShare,
+        rng: &mut RandomNumberGenerator,
+    ) -> Result<bool> {
+        if share.index == 0 {
+            return Ok(false);
+        }
+
+        let x = EcScalar::from_mpi(group, &MPI::new_from_u32(share.index)?)?;
+
+        // Evaluate the committed polynomial at x in the exponent
+        let mut expected = EcPoint::identity(group)?;
+        for point in self.points.iter().rev() {
+            expected = expected.mul(&x, rng)?.pt_add(point)?;
+        }
+
+        let actual = EcPoint::generator(group)?.mul(&share.value, rng)?;
+        actual.is_equal(&expected)
+    }
+}
+
+// Lagrange interpolation of the shares requires the scalars to form a field
+fn check_prime_order(group: &EcGroup, rng: &mut RandomNumberGenerator) -> Result<()> {
+    if group.order_ref().is_prime(rng, 128)? {
+        Ok(())
+    } else {
+        Err(Error::bad_parameter(
+            "Feldman secret sharing requires a group of prime order",
+        ))
+    }
+}
+
+/// Split a secret scalar into `count` verifiable shares
+///
+/// Any `threshold` of the shares recover the secret. The returned
+/// commitments allow each shareholder to verify their share. The group
+/// must have prime order, otherwise an error of type
+/// [`ErrorType::BadParameter`] is returned.
+///
+/// # Examples
+/// ```
+/// let group = botan::EcGroup::from_name("secp256r1").unwrap();
+/// let mut rng = botan::RandomNumberGenerator::new().unwrap();
+/// let secret = botan::EcScalar::random(&group, &mut rng).unwrap();
+///
+/// let (shares, commitments) = botan::feldman_split(&group, 2, 3, &secret, &mut rng).unwrap();
+/// for share in &shares {
+///     assert!(commitments.verify(&group, share, &mut rng).unwrap());
+/// }
+///
+/// let recovered = botan::feldman_combine(&group, &commitments, &shares[1..], &mut rng).unwrap();
+/// assert_eq!(recovered.to_mpi().unwrap(), secret.to_mpi().unwrap());
+/// ```
+pub fn feldman_split(
+    group: &EcGroup,
+    threshold: usize,
+    count: usize,
+    secret: &EcScalar,
+    rng: &mut RandomNumberGenerator,
+) -> Result<(Vec<FeldmanShare>, FeldmanCommitments)> {
+    if threshold < 2 || count < threshold || u32::try_from(count).is_err() {
+        return Err(Error::bad_parameter("Invalid secret sharing parameters"));
+    }
+    check_prime_order(group, rng)?;
+
+    let order = group.order()?;
+    let generator = EcPoint::generator(group)?;
+
+    let mut coefficients = Vec::with_capacity(threshold);
+    coefficients.push(secret.to_mpi()?);
+    for _ in 1..threshold {
+        coefficients.push(EcScalar::random(group, rng)?.to_mpi()?);
+    }
+
+    let points = coefficients
+        .iter()
+        .map(|c| generator.mul(&EcScalar::from_mpi(group, c)?, rng))
+        .collect::<Result<Vec<_>>>()?;
+
+    let mut shares = Vec::with_capacity(count);
+    for index in 1..=count as u32 {
+        let x = MPI::new_from_u32(index)?;
+        let mut y = MPI::new()?;
+        for c in coefficients.iter().rev() {
+            y = y.mp_mul(&x)?.mp_add(c)?.divrem(&order)?.1;
+        }
+        shares.push(FeldmanShare::new(index, EcScalar::from_mpi(group, &y)?));
+    }
+
+    Ok((shares, FeldmanCommitments { points }))
+}
+
+/// Recover a secret scalar from shares created by [`feldman_split`]
+///
+/// Each share is first verified against the commitments; shares which
+/// fail verification, or repeat an index already seen, are ignored.
+/// Fails unless at least the threshold number of valid shares remain. As
+/// with [`feldman_split`], the group must have prime order.
+pub fn feldman_combine(
+    group: &EcGroup,
+    commitments: &FeldmanCommitments,
+    shares: &[FeldmanShare],
+    rng: &mut RandomNumberGenerator,
+) -> Result<EcScalar> {
+    check_prime_order(group, rng)?;
+
+    let threshold = commitments.threshold();
+
+    let mut valid: Vec<&FeldmanShare> = Vec::with_capacity(threshold);
+    for share in shares {
+        if valid.len() == threshold {
+            break;
+        }
+        if valid.iter().all(|s| s.index != share.index) && commitments.verify(group, share, rng)? {
+            valid.push(share);
+        }
+    }
+
+    if valid.len() < threshold {
+        return Err(Error::with_message(
+            ErrorType::InvalidInput,
+            format!(
+                "Recovering the secret requires {} valid shares but only {} found",
+                threshold,
+                valid.len()
+            ),
+        ));
+    }
+
+    let order = group.order()?;
+    let xs = valid
+        .iter()
+        .map(|s| MPI::new_from_u32(s.index))
+        .collect::<Result<Vec<_>>>()?;
+
+    let mut secret = MPI::new()?;
+    for (i, share) in valid.iter().enumerate() {
+        let mut num = MPI::new_from_u32(1)?;
+        let mut den = MPI::new_from_u32(1)?;
+        for (j, xj) in xs.iter().enumerate() {
+            if i != j {
+                num = num.mp_mul(xj)?.divrem(&order)?.1;
+                // xj - xi, kept non-negative since both are less than the order
+                let diff = xj.mp_add(&order)?.mp_sub(&xs[i])?;
+                den = den.mp_mul(&diff)?.divrem(&order)?.1;
+            }
+        }
+        let basis = num
+            .mp_mul(&MPI::modular_inverse(&den, &order)?)?
+            .divrem(&order)?
+            .1;
+        secret = secret
+            .mp_add(&share.value.to_mpi()?.mp_mul(&basis)?)?
+            .divrem(&order)?
+            .1;
+    }
+
+    EcScalar::from_mpi(group, &secret)
+}
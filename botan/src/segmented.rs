@@ -0,0 +1,169 @@
+use crate::utils::*;
+
+use crate::{Cipher, CipherDirection};
+
+/// The length of the suffix appended to the nonce prefix of each segment:
+/// the segment number, and a flag marking the final segment
+pub(crate) const SEGMENT_NONCE_SUFFIX_LEN: usize = 5;
+
+/// The per-segment AEAD operation shared by encryption and decryption
+struct SegmentCipher {
+    cipher: Cipher,
+    direction: CipherDirection,
+    nonce_prefix: Vec<u8>,
+    ad: Vec<u8>,
+    counter: u32,
+    finished: bool,
+    failed: bool,
+}
+
+impl SegmentCipher {
+    fn process(&mut self, segment: &[u8], last: bool) -> Result<Vec<u8>> {
+        if self.failed {
+            return Err(Error::with_message(
+                ErrorType::InvalidObjectState,
+                "Stream failed to process an earlier segment".to_owned(),
+            ));
+        }
+        if self.finished {
+            return Err(Error::with_message(
+                ErrorType::InvalidObjectState,
+                "Stream already finished".to_owned(),
+            ));
+        }
+
+        let next = self.counter.checked_add(1).ok_or_else(|| {
+            Error::with_message(
+                ErrorType::InvalidObjectState,
+                "Too many segments in stream".to_owned(),
+            )
+        })?;
+
+        let mut nonce = self.nonce_prefix.clone();
+        nonce.extend_from_slice(&self.counter.to_be_bytes());
+        nonce.push(u8::from(last));
+
+        // A segment that fails to authenticate ends the stream, so that
+        // later segments can't be processed as though it were never seen
+        let output = self
+            .cipher
+            .set_associated_data(&self.ad)
+            .and_then(|()| self.cipher.process(&nonce, segment))
+            .inspect_err(|_| self.failed = true)?;
+
+        self.counter = next;
+        self.finished = last;
+        Ok(output)
+    }
+}
+
+/// An AEAD applied to a stream split into segments of a fixed size
+///
+/// Each segment is encrypted separately under a nonce made of a fixed
+/// prefix, the segment number, and a flag marking the final segment, so
+/// that reordering and truncation of the segments are detected. Input is
+/// buffered until a complete segment is available, and a segment is only
+/// processed once it is known not to be the final one.
+pub(crate) struct SegmentedAead {
+    segments: SegmentCipher,
+    segment_size: usize,
+    buffer: Vec<u8>,
+}
+
+impl SegmentedAead {
+    /// Set up segmented processing with an unkeyed AEAD
+    ///
+    /// The associated data is authenticated with every segment.
+    pub(crate) fn new(
+        cipher: Cipher,
+        nonce_prefix: &[u8],
+        ad: &[u8],
+        segment_size: usize,
+    ) -> Result<Self> {
+        if cipher.tag_length() == 0 {
+            return Err(Error::bad_parameter(
+                "Segmented encryption requires an AEAD cipher",
+            ));
+        }
+        if segment_size == 0 {
+            return Err(Error::bad_parameter("Invalid segment size"));
+        }
+        if nonce_prefix.len() + SEGMENT_NONCE_SUFFIX_LEN != cipher.default_nonce_length() {
+            return Err(Error::bad_parameter("Invalid nonce length"));
+        }
+
+        let direction = cipher.direction()?;
+
+        Ok(Self {
+            segments: SegmentCipher {
+                cipher,
+                direction,
+                nonce_prefix: nonce_prefix.to_vec(),
+                ad: ad.to_vec(),
+                counter: 0,
+                finished: false,
+                failed: false,
+            },
+            segment_size,
+            buffer: Vec::new(),
+        })
+    }
+
+    /// Return the maximum key length of the cipher
+    pub(crate) fn maximum_keylength(&self) -> Result<usize> {
+        Ok(self.segments.cipher.key_spec()?.maximum_keylength())
+    }
+
+    /// Set the key
+    pub(crate) fn set_key(&mut self, key: &[u8]) -> Result<()> {
+        self.segments.cipher.set_key(key)
+    }
+
+    /// Return the length of each complete segment of input: the segment
+    /// size when encrypting, or the segment size plus the tag when decrypting
+    pub(crate) fn input_segment_len(&self) -> usize {
+        match self.segments.direction {
+            CipherDirection::Encrypt => self.segment_size,
+            CipherDirection::Decrypt => self.segment_size + self.segments.cipher.tag_length(),
+        }
+    }
+
+    /// Process more input, returning the output of any complete segments
+    pub(crate) fn update(&mut self, input: &[u8]) -> Result<Vec<u8>> {
+        self.buffer.extend_from_slice(input);
+
+        let segment_len = self.input_segment_len();
+        let mut out = Vec::new();
+        let mut consumed = 0;
+
+        // The last segment is held back until finish, as it is processed differently
+        while self.buffer.len() - consumed > segment_len {
+            let segment = &self.buffer[consumed..consumed + segment_len];
+            out.extend_from_slice(&self.segments.process(segment, false)?);
+            consumed += segment_len;
+        }
+        self.buffer.drain(..consumed);
+
+        Ok(out)
+    }
+
+    /// Process the final segment
+    ///
+    /// When decrypting, this verifies that the stream is complete.
+    pub(crate) fn finish(mut self) -> Result<Vec<u8>> {
+        if self.segments.direction == CipherDirection::Decrypt {
+            // Only an entirely empty stream has an empty final segment
+            let min_len =
+                self.segments.cipher.tag_length() + usize::from(self.segments.counter > 0);
+            if self.buffer.len() < min_len {
+                return Err(Error::with_message(
+                    ErrorType::InvalidInput,
+                    "Encrypted stream truncated".to_owned(),
+                ));
+            }
+        }
+
+        let last = mem::take(&mut self.buffer);
+        self.segments.process(&last, true)
+    }
+}
@@ -623,3 +623,29 @@ impl Certificate {
         }
     }
 }
+
+/// Serialized as a PEM string; base64 encoded DER is also accepted when
+/// deserializing.
+#[cfg(feature = "serde")]
+impl serde::Serialize for Certificate {
+    fn serialize<S: serde::Serializer>(
+        &self,
+        serializer: S,
+    ) -> core::result::Result<S::Ok, S::Error> {
+        let pem = self.pem_encode().map_err(serde::ser::Error::custom)?;
+        serializer.serialize_str(&pem)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de> serde::Deserialize<'de> for Certificate {
+    fn deserialize<D: serde::Deserializer<'de>>(
+        deserializer: D,
+    ) -> core::result::Result<Self, D::Error> {
+        crate::memutils::deserialize_pem_or_der(
+            deserializer,
+            |pem: &str| Certificate::load(pem.as_bytes()),
+            Certificate::load,
+        )
+    }
+}
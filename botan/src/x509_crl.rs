@@ -179,6 +179,32 @@ impl CRL {
     }
 }
 
+/// Serialized as a PEM string; base64 encoded DER is also accepted when
+/// deserializing.
+#[cfg(feature = "serde")]
+impl serde::Serialize for CRL {
+    fn serialize<S: serde::Serializer>(
+        &self,
+        serializer: S,
+    ) -> core::result::Result<S::Ok, S::Error> {
+        let pem = self.pem_encode().map_err(serde::ser::Error::custom)?;
+        serializer.serialize_str(&pem)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de> serde::Deserialize<'de> for CRL {
+    fn deserialize<D: serde::Deserializer<'de>>(
+        deserializer: D,
+    ) -> core::result::Result<Self, D::Error> {
+        crate::memutils::deserialize_pem_or_der(
+            deserializer,
+            |pem: &str| CRL::load(pem.as_bytes()),
+            CRL::load,
+        )
+    }
+}
+
 /// Reason a certificate was revoked for
 ///
 /// This requires Botan 3.11 or later; with older versions an error of type
@@ -1,6 +1,11 @@
 use crate::utils::*;
 use botan_sys::*;
 
+#[cfg(feature = "std")]
+use crate::HashFunction;
+#[cfg(feature = "std")]
+use std::io;
+
 /// Forward Error Correction Encoding
 ///
 /// This requires Botan 3.0 or later; with older versions an error of type
@@ -81,3 +86,384 @@ pub fn zfec_decode(
 
     Ok(output_buf)
 }
+
+/// The number of bytes each share receives per stripe in share files
+///
+/// Inputs are encoded in stripes of `k * ZFEC_STRIPE_SIZE` bytes, the
+/// same as the `zfec` command line tools.
+pub const ZFEC_STRIPE_SIZE: usize = 4096;
+
+/// The header at the start of each zfec share file
+///
+/// This is the compact header used by the Tahoe-LAFS `zfec` tools,
+/// which packs the parameters into between 2 and 4 bytes.
+///
+/// # Examples
+/// ```
+/// let header = botan::ZfecHeader::new(3, 10, 2, 7).unwrap();
+/// let bytes = header.to_bytes();
+/// assert_eq!(bytes, [0x09, 0x29, 0xc0]);
+/// assert_eq!(botan::ZfecHeader::parse(&bytes).unwrap(), (header, 3));
+/// ```
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct ZfecHeader {
+    /// The number of shares required to reconstruct the input
+    pub k: usize,
+    /// The total number of shares
+    pub m: usize,
+    /// The number of padding bytes appended to the input
+    pub pad_len: usize,
+    /// Which share this is, from 0 to m-1
+    pub share_number: usize,
+}
+
+// Number of bits needed to represent the values 0..n
+fn bits_for(n: usize) -> usize {
+    (usize::BITS - n.saturating_sub(1).leading_zeros()) as usize
+}
+
+impl ZfecHeader {
+    /// Create a new header, checking the parameters are consistent
+    pub fn new(k: usize, m: usize, pad_len: usize, share_number: usize) -> Result<Self> {
+        if k == 0 || k > m || m > 256 || pad_len >= k || share_number >= m {
+            return Err(Error::bad_parameter("Invalid ZFEC parameters"));
+        }
+        Ok(Self {
+            k,
+            m,
+            pad_len,
+            share_number,
+        })
+    }
+
+    fn total_bits(k: usize, m: usize) -> usize {
+        8 + 2 * bits_for(m) + bits_for(k)
+    }
+
+    fn len_for(k: usize, m: usize) -> usize {
+        Self::total_bits(k, m).div_ceil(8).max(2)
+    }
+
+    /// Return the length of the encoded header, between 2 and 4 bytes
+    #[must_use]
+    pub fn encoded_len(&self) -> usize {
+        Self::len_for(self.k, self.m)
+    }
+
+    /// Encode the header
+    #[must_use]
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut val = (self.m - 1) as u32;
+        val = (val << bits_for(self.m)) | (self.k - 1) as u32;
+        val = (val << bits_for(self.k)) | self.pad_len as u32;
+        val = (val << bits_for(self.m)) | self.share_number as u32;
+
+        let len = self.encoded_len();
+        let val = val << (8 * len - Self::total_bits(self.k, self.m));
+        val.to_be_bytes()[4 - len..].to_vec()
+    }
+
+    /// Parse a header from the start of `data`
+    ///
+    /// Returns the header along with its encoded length, which is where
+    /// the share data begins.
+    pub fn parse(data: &[u8]) -> Result<(Self, usize)> {
+        let invalid =
+            || Error::with_message(ErrorType::InvalidInput, "Invalid ZFEC header".to_string());
+
+        if data.len() < 2 {
+            return Err(invalid());
+        }
+        let m = data[0] as usize + 1;
+        let k = (data[1] as usize >> (8 - bits_for(m))) + 1;
+        let len = Self::len_for(k, m);
+        if k > m || data.len() < len {
+            return Err(invalid());
+        }
+
+        let mut val = [0u8; 4];
+        val[..len].copy_from_slice(&data[..len]);
+        let val = u32::from_be_bytes(val) >> (32 - Self::total_bits(k, m));
+
+        let mask = |bits: usize| (1u32 << bits) - 1;
+        let share_number = (val & mask(bits_for(m))) as usize;
+        let pad_len = ((val >> bits_for(m)) & mask(bits_for(k))) as usize;
+
+        let header = Self::new(k, m, pad_len, share_number).map_err(|_| invalid())?;
+        Ok((header, len))
+    }
+}
+
+#[cfg(feature = "std")]
+fn io_error(e: io::Error) -> Error {
+    Error::with_message(ErrorType::SystemError, e.to_string())
+}
+
+#[cfg(feature = "std")]
+fn read_fully<R: io::Read>(reader: &mut R, buf: &mut [u8]) -> Result<usize> {
+    let mut filled = 0;
+    while filled < buf.len() {
+        match reader.read(&mut buf[filled..]) {
+            Ok(0) => break,
+            Ok(n) => filled += n,
+            Err(e) if e.kind() == io::ErrorKind::Interrupted => {}
+            Err(e) => return Err(io_error(e)),
+        }
+    }
+    Ok(filled)
+}
+
+#[cfg(feature = "std")]
+/// Erasure code a stream into `m` share files
+///
+/// Reads exactly `input_len` bytes from `input` and writes one share,
+/// each starting with a [`ZfecHeader`], to every writer in `shares`.
+/// The input is processed in stripes so it never needs to be held in
+/// memory at once.
+///
+/// Returns the SHA-256 checksum of each complete share (header included),
+/// indexed by share number. These should be stored alongside the shares
+/// and passed to [`zfec_decode_stream`] so that corrupted shares are
+/// detected and excluded.
+///
+/// # Examples
+/// ```
+/// let input = vec![0x42u8; 10000];
+/// let mut shares = vec![Vec::new(); 5];
+/// let checksums = botan::zfec_encode_stream(3, &mut input.as_slice(), input.len() as u64, &mut shares).unwrap();
+///
+/// // Any 3 shares are sufficient
+/// let mut available = vec![std::io::Cursor::new(&shares[4]), std::io::Cursor::new(&shares[1]), std::io::Cursor::new(&shares[2])];
+/// let mut output = Vec::new();
+/// botan::zfec_decode_stream(&mut available, Some(&checksums), &mut output).unwrap();
+/// assert_eq!(output, input);
+/// ```
+pub fn zfec_encode_stream<R: io::Read, W: io::Write>(
+    k: usize,
+    input: &mut R,
+    input_len: u64,
+    shares: &mut [W],
+) -> Result<Vec<Vec<u8>>> {
+    let m = shares.len();
+    if k == 0 || k > m || m > 256 {
+        return Err(Error::bad_parameter("Invalid ZFEC parameters"));
+    }
+    let pad_len = ((k as u64 - input_len % k as u64) % k as u64) as usize;
+
+    let mut hashes = Vec::with_capacity(m);
+    for (share_number, share) in shares.iter_mut().enumerate() {
+        let header = ZfecHeader::new(k, m, pad_len, share_number)?.to_bytes();
+        share.write_all(&header).map_err(io_error)?;
+        let mut hash = HashFunction::new("SHA-256")?;
+        hash.update(&header)?;
+        hashes.push(hash);
+    }
+
+    let mut stripe = vec![0u8; k * ZFEC_STRIPE_SIZE];
+    let mut remaining = input_len;
+
+    while remaining > 0 {
+        let want = core::cmp::min(remaining, stripe.len() as u64) as usize;
+        if read_fully(input, &mut stripe[..want])? != want {
+            return Err(Error::with_message(
+                ErrorType::InvalidInput,
+                "ZFEC input was shorter than the expected length".to_string(),
+            ));
+        }
+        remaining -= want as u64;
+
+        let padded = want.next_multiple_of(k);
+        stripe[want..padded].fill(0);
+
+        let blocks = zfec_encode(k, m, &stripe[..padded])?;
+        for ((share, hash), block) in shares.iter_mut().zip(hashes.iter_mut()).zip(&blocks) {
+            share.write_all(block).map_err(io_error)?;
+            hash.update(block)?;
+        }
+    }
+
+    hashes.iter_mut().map(|h| h.finish()).collect()
+}
+
+#[cfg(feature = "std")]
+struct ZfecShareInput<'a, R> {
+    reader: &'a mut R,
+    header: ZfecHeader,
+    data_len: u64,
+}
+
+#[cfg(feature = "std")]
+impl<R> ZfecShareInput<'_, R> {
+    /// The encoding parameters, which must agree between the shares decoded together
+    fn params(&self) -> (usize, usize, usize, u64) {
+        let h = &self.header;
+        (h.k, h.m, h.pad_len, self.data_len)
+    }
+}
+
+#[cfg(feature = "std")]
+fn open_zfec_share<'a, R: io::Read + io::Seek>(
+    reader: &'a mut R,
+    checksums: Option<&[Vec<u8>]>,
+) -> Result<ZfecShareInput<'a, R>> {
+    reader.rewind().map_err(io_error)?;
+
+    let mut prefix = [0u8; 4];
+    let got = read_fully(reader, &mut prefix)?;
+    let (header, header_len) = ZfecHeader::parse(&prefix[..got])?;
+
+    if let Some(checksums) = checksums {
+        let expected = checksums.get(header.share_number).ok_or_else(|| {
+            Error::with_message(ErrorType::InvalidInput, "Missing ZFEC checksum".to_string())
+        })?;
+
+        reader.rewind().map_err(io_error)?;
+        let mut hash = HashFunction::new("SHA-256")?;
+        let mut buf = vec![0u8; ZFEC_STRIPE_SIZE];
+        loop {
+            let n = read_fully(reader, &mut buf)?;
+            if n == 0 {
+                break;
+            }
+            hash.update(&buf[..n])?;
+        }
+        if !crate::const_time_compare(&hash.finish()?, expected) {
+            return Err(Error::with_message(
+                ErrorType::InvalidInput,
+                "ZFEC share checksum mismatch".to_string(),
+            ));
+        }
+    }
+
+    let total_len = reader.seek(io::SeekFrom::End(0)).map_err(io_error)?;
+    reader
+        .seek(io::SeekFrom::Start(header_len as u64))
+        .map_err(io_error)?;
+
+    Ok(ZfecShareInput {
+        reader,
+        header,
+        data_len: total_len - header_len as u64,
+    })
+}
+
+#[cfg(feature = "std")]
+/// Rebuild the original input from share files
+///
+/// Each of `shares` should be a share produced by [`zfec_encode_stream`],
+/// in any order. If `checksums` is provided, shares whose SHA-256 does
+/// not match are excluded, as are shares with an invalid header. The
+/// encoding parameters are those agreed on by the most shares; shares
+/// with other parameters, or duplicating a share number already seen, are
+/// also excluded. Decoding succeeds as long as at least `k` shares remain.
+///
+/// An error reading any of the shares is returned rather than excluding
+/// the share.
+///
+/// Returns the number of bytes written to `output`.
+pub fn zfec_decode_stream<R: io::Read + io::Seek, W: io::Write>(
+    shares: &mut [R],
+    checksums: Option<&[Vec<u8>]>,
+    output: &mut W,
+) -> Result<u64> {
+    let mut candidates: Vec<ZfecShareInput<'_, R>> = Vec::new();
+
+    for share in shares.iter_mut() {
+        match open_zfec_share(share, checksums) {
+            Ok(input) => {
+                if candidates.iter().all(|c| {
+                    c.params() != input.params()
+                        || c.header.share_number != input.header.share_number
+                }) {
+                    candidates.push(input);
+                }
+            }
+            Err(e) if e.error_type() == ErrorType::InvalidInput => {}
+            Err(e) => return Err(e),
+        }
+    }
+
+    // Choose the parameters agreed on by the most distinct shares
+    let mut best = None;
+    let mut tied = false;
+    for candidate in &candidates {
+        let params = candidate.params();
+        let count = candidates.iter().filter(|c| c.params() == params).count();
+        match best {
+            Some((p, n)) if p == params || count < n => {}
+            Some((_, n)) if count == n => tied = true,
+            _ => {
+                best = Some((params, count));
+                tied = false;
+            }
+        }
+    }
+
+    let Some((params, _)) = best else {
+        return Err(Error::with_message(
+            ErrorType::InvalidInput,
+            "No valid ZFEC shares".to_string(),
+        ));
+    };
+    if tied {
+        return Err(Error::with_message(
+            ErrorType::InvalidInput,
+            "ZFEC shares disagree on the encoding parameters".to_string(),
+        ));
+    }
+    let (k, m, pad_len, data_len) = params;
+
+    let mut inputs: Vec<ZfecShareInput<'_, R>> = candidates
+        .into_iter()
+        .filter(|c| c.params() == params)
+        .take(k)
+        .collect();
+
+    if inputs.len() < k {
+        return Err(Error::with_message(
+            ErrorType::InvalidInput,
+            format!(
+                "ZFEC decoding requires {} valid shares but only {} found",
+                k,
+                inputs.len()
+            ),
+        ));
+    }
+
+    let output_len = (data_len * k as u64)
+        .checked_sub(pad_len as u64)
+        .ok_or_else(|| {
+            Error::with_message(ErrorType::InvalidInput, "Invalid ZFEC padding".to_string())
+        })?;
+
+    let mut blocks = vec![vec![0u8; ZFEC_STRIPE_SIZE]; k];
+    let mut written = 0;
+
+    while written < output_len {
+        let mut block_len = None;
+        for (input, block) in inputs.iter_mut().zip(blocks.iter_mut()) {
+            let n = read_fully(input.reader, block)?;
+            if n == 0 || block_len.is_some_and(|l| l != n) {
+                return Err(Error::with_message(
+                    ErrorType::InvalidInput,
+                    "ZFEC share was truncated while reading".to_string(),
+                ));
+            }
+            block_len = Some(n);
+        }
+        let block_len = block_len.unwrap_or(0);
+
+        let indexed: Vec<(usize, &[u8])> = inputs
+            .iter()
+            .zip(&blocks)
+            .map(|(i, b)| (i.header.share_number, &b[..block_len]))
+            .collect();
+        let decoded = zfec_decode(k, m, &indexed, block_len)?;
+
+        let take = core::cmp::min(decoded.len() as u64, output_len - written) as usize;
+        output.write_all(&decoded[..take]).map_err(io_error)?;
+        written += take as u64;
+    }
+
+    Ok(written)
+}
@@ -0,0 +1,500 @@
+//! Zero knowledge proofs over elliptic curve groups: Schnorr proofs of
+//! knowledge, Chaum-Pedersen DLEQ proofs, and Pedersen commitments
+
+use crate::utils::*;
+
+use core::ops::Add;
+
+use crate::{EcGroup, EcPoint, EcScalar, HashFunction, RandomNumberGenerator, expand_message_xmd};
+
+#[derive(Debug, Clone)]
+/// A Fiat-Shamir transcript for non-interactive zero knowledge proofs
+///
+/// The transcript absorbs a domain separation label followed by every
+/// public value of the protocol, each tagged with a label and length
+/// prefixed, and derives challenges from everything absorbed so far.
+/// The prover and verifier must append the same values in the same order,
+/// including any context (such as user identities) the proof should be
+/// bound to.
+///
+/// # Examples
+/// ```
+/// let group = botan::EcGroup::from_name("secp256r1").unwrap();
+/// let mut t1 = botan::ZkpTranscript::new("SHA-256", b"my-protocol").unwrap();
+/// let mut t2 = t1.clone();
+/// t1.append_message(b"user", b"alice").unwrap();
+/// t2.append_message(b"user", b"bob").unwrap();
+///
+/// let c1 = t1.challenge_scalar(b"c", &group).unwrap();
+/// let c2 = t2.challenge_scalar(b"c", &group).unwrap();
+/// assert_ne!(c1, c2);
+/// ```
+pub struct ZkpTranscript {
+    hash: HashFunction,
+    hash_name: String,
+}
+
+impl ZkpTranscript {
+    /// Create a new transcript using the specified hash function
+    ///
+    /// The domain should uniquely identify the application protocol.
+    pub fn new<A: crate::HashAlgorithmIdentifier>(hash: A, domain: &[u8]) -> Result<Self> {
+        let hash_name = hash.botan_name();
+        let mut transcript = Self {
+            hash: HashFunction::new(hash_name.as_str())?,
+            hash_name,
+        };
+        transcript.append_message(b"botan-zkp-transcript", domain)?;
+        Ok(transcript)
+    }
+
+    /// Absorb a labeled message into the transcript
+    pub fn append_message(&mut self, label: &[u8], message: &[u8]) -> Result<()> {
+        self.hash.update(&(label.len() as u64).to_be_bytes())?;
+        self.hash.update(label)?;
+        self.hash.update(&(message.len() as u64).to_be_bytes())?;
+        self.hash.update(message)
+    }
+
+    /// Absorb a labeled elliptic curve point (in compressed form) into the transcript
+    pub fn append_point(&mut self, label: &[u8], point: &EcPoint) -> Result<()> {
+        let encoded = if point.is_identity()? {
+            vec![0x00]
+        } else {
+            point.to_compressed()?
+        };
+        self.append_message(label, &encoded)
+    }
+
+    /// Absorb a labeled scalar into the transcript
+    pub fn append_scalar(&mut self, label: &[u8], scalar: &EcScalar) -> Result<()> {
+        self.append_message(label, &scalar.to_bytes()?)
+    }
+
+    /// Derive a challenge scalar from the transcript
+    ///
+    /// The challenge depends on everything absorbed so far, and is itself
+    /// absorbed, so successive challenges are distinct.
+    pub fn challenge_scalar(&mut self, label: &[u8], group: &EcGroup) -> Result<EcScalar> {
+        self.append_message(b"challenge", label)?;
+        let state = self.hash.duplicate()?.finish()?;
+        self.hash.update(&state)?;
+
+        let wide_len = group.order()?.byte_count()? + 16;
+        let wide = expand_message_xmd(
+            self.hash_name.as_str(),
+            &state,
+            b"botan-zkp-challenge",
+            wide_len,
+        )?;
+        EcScalar::from_wide_bytes(group, &wide)
+    }
+}
+
+fn decode_point_and_scalar(group: &EcGroup, bytes: &[u8]) -> Result<(EcPoint, EcScalar)> {
+    let scalar_len = group.order()?.byte_count()?;
+    if bytes.len() <= scalar_len {
+        return Err(Error::with_message(
+            ErrorType::InvalidInput,
+            format!("Invalid length {} for proof", bytes.len()),
+        ));
+    }
+    let (point, scalar) = bytes.split_at(bytes.len() - scalar_len);
+    Ok((
+        EcPoint::from_bytes(group, point)?,
+        EcScalar::from_bytes(group, scalar)?,
+    ))
+}
+
+#[derive(Debug)]
+/// A non-interactive Schnorr proof of knowledge of a discrete logarithm (RFC 8235)
+///
+/// Proves knowledge of `x` such that `A = x*G`, where `G` is the group
+/// generator, without revealing `x`. The challenge is derived from a
+/// [`ZkpTranscript`] instead of a plain hash, so the proof is bound to
+/// whatever context the caller has appended to it.
+///
+/// # Examples
+/// ```
+/// let group = botan::EcGroup::from_name("secp256r1").unwrap();
+/// let mut rng = botan::RandomNumberGenerator::new().unwrap();
+/// let secret = botan::EcScalar::random(&group, &mut rng).unwrap();
+/// let public = group.generator().unwrap().mul(&secret, &mut rng).unwrap();
+///
+/// let mut transcript = botan::ZkpTranscript::new("SHA-256", b"example").unwrap();
+/// let proof = botan::SchnorrProof::prove(&group, &secret, &mut transcript.clone(), &mut rng).unwrap();
+/// assert!(proof.verify(&group, &public, &mut transcript, &mut rng).unwrap());
+/// ```
+pub struct SchnorrProof {
+    commitment: EcPoint,
+    response: EcScalar,
+}
+
+impl SchnorrProof {
+    fn challenge(
+        group: &EcGroup,
+        public: &EcPoint,
+        commitment: &EcPoint,
+        transcript: &mut ZkpTranscript,
+    ) -> Result<EcScalar> {
+        transcript.append_message(b"proof", b"schnorr")?;
+        transcript.append_point(b"G", &group.generator()?)?;
+        transcript.append_point(b"V", commitment)?;
+        transcript.append_point(b"A", public)?;
+        transcript.challenge_scalar(b"c", group)
+    }
+
+    /// Prove knowledge of the secret `x` of the public value `x*G`
+    pub fn prove(
+        group: &EcGroup,
+        secret: &EcScalar,
+        transcript: &mut ZkpTranscript,
+        rng: &mut RandomNumberGenerator,
+    ) -> Result<Self> {
+        let generator = group.generator()?;
+        let public = generator.mul(secret, rng)?;
+
+        let v = EcScalar::random(group, rng)?;
+        let commitment = generator.mul(&v, rng)?;
+        let c = Self::challenge(group, &public, &commitment, transcript)?;
+        let response = v.scalar_sub(&c.scalar_mul(secret)?)?;
+
+        Ok(Self {
+            commitment,
+            response,
+        })
+    }
+
+    /// Verify the proof for a public value
+    ///
+    /// Returns false if the proof is invalid, or if the public value is the identity
+    pub fn verify(
+        &self,
+        group: &EcGroup,
+        public: &EcPoint,
+        transcript: &mut ZkpTranscript,
+        rng: &mut RandomNumberGenerator,
+    ) -> Result<bool> {
+        if public.is_identity()? {
+            return Ok(false);
+        }
+
+        let c = Self::challenge(group, public, &self.commitment, transcript)?;
+        let expected = group
+            .generator()?
+            .mul(&self.response, rng)?
+            .pt_add(&public.mul(&c, rng)?)?;
+        expected.is_equal(&self.commitment)
+    }
+
+    /// Return the encoding of the proof, the compressed commitment followed by the response
+    pub fn to_bytes(&self) -> Result<Vec<u8>> {
+        let mut out = self.commitment.to_compressed()?;
+        out.extend_from_slice(&self.response.to_bytes()?);
+        Ok(out)
+    }
+
+    /// Decode a proof produced by [`SchnorrProof::to_bytes`]
+    pub fn from_bytes(group: &EcGroup, bytes: &[u8]) -> Result<Self> {
+        let (commitment, response) = decode_point_and_scalar(group, bytes)?;
+        Ok(Self {
+            commitment,
+            response,
+        })
+    }
+}
+
+#[derive(Debug)]
+/// A non-interactive Chaum-Pedersen proof of discrete logarithm equality
+///
+/// Proves knowledge of `x` such that `A = x*G` and `B = x*H` for two
+/// bases `G` and `H`, without revealing `x`.
+///
+/// # Examples
+/// ```
+/// let group = botan::EcGroup::from_name("secp256r1").unwrap();
+/// let mut rng = botan::RandomNumberGenerator::new().unwrap();
+/// let g = group.generator().unwrap();
+/// let h = group.hash_to_curve(b"second base", b"example").unwrap();
+///
+/// let secret = botan::EcScalar::random(&group, &mut rng).unwrap();
+/// let a = g.mul(&secret, &mut rng).unwrap();
+/// let b = h.mul(&secret, &mut rng).unwrap();
+///
+/// let mut transcript = botan::ZkpTranscript::new("SHA-256", b"example").unwrap();
+/// let proof = botan::ChaumPedersenProof::prove(
+///     &group, &secret, &g, &h, &mut transcript.clone(), &mut rng).unwrap();
+/// assert!(proof.verify(&group, &g, &a, &h, &b, &mut transcript, &mut rng).unwrap());
+/// ```
+pub struct ChaumPedersenProof {
+    challenge: EcScalar,
+    response: EcScalar,
+}
+
+impl ChaumPedersenProof {
+    #[allow(clippy::too_many_arguments)]
+    fn challenge(
+        group: &EcGroup,
+        g: &EcPoint,
+        a: &EcPoint,
+        h: &EcPoint,
+        b: &EcPoint,
+        r1: &EcPoint,
+        r2: &EcPoint,
+        transcript: &mut ZkpTranscript,
+    ) -> Result<EcScalar> {
+        transcript.append_message(b"proof", b"chaum-pedersen")?;
+        transcript.append_point(b"G", g)?;
+        transcript.append_point(b"A", a)?;
+        transcript.append_point(b"H", h)?;
+        transcript.append_point(b"B", b)?;
+        transcript.append_point(b"R1", r1)?;
+        transcript.append_point(b"R2", r2)?;
+        transcript.challenge_scalar(b"c", group)
+    }
+
+    /// Prove that `x*G` and `x*H` have the same discrete logarithm `x`
+    pub fn prove(
+        group: &EcGroup,
+        secret: &EcScalar,
+        g: &EcPoint,
+        h: &EcPoint,
+        transcript: &mut ZkpTranscript,
+        rng: &mut RandomNumberGenerator,
+    ) -> Result<Self> {
+        let a = g.mul(secret, rng)?;
+        let b = h.mul(secret, rng)?;
+
+        let k = EcScalar::random(group, rng)?;
+        let r1 = g.mul(&k, rng)?;
+        let r2 = h.mul(&k, rng)?;
+
+        let challenge = Self::challenge(group, g, &a, h, &b, &r1, &r2, transcript)?;
+        let response = k.scalar_sub(&challenge.scalar_mul(secret)?)?;
+
+        Ok(Self {
+            challenge,
+            response,
+        })
+    }
+
+    /// Verify the proof that `A` and `B` have the same discrete logarithm
+    /// with respect to the bases `G` and `H`
+    #[allow(clippy::too_many_arguments)]
+    pub fn verify(
+        &self,
+        group: &EcGroup,
+        g: &EcPoint,
+        a: &EcPoint,
+        h: &EcPoint,
+        b: &EcPoint,
+        transcript: &mut ZkpTranscript,
+        rng: &mut RandomNumberGenerator,
+    ) -> Result<bool> {
+        let c = &self.challenge;
+        let r1 = g.mul(&self.response, rng)?.pt_add(&a.mul(c, rng)?)?;
+        let r2 = h.mul(&self.response, rng)?.pt_add(&b.mul(c, rng)?)?;
+
+        let expected = Self::challenge(group, g, a, h, b, &r1, &r2, transcript)?;
+        expected.is_equal(c)
+    }
+
+    /// Return the encoding of the proof, the challenge followed by the response
+    pub fn to_bytes(&self) -> Result<Vec<u8>> {
+        let mut out = self.challenge.to_bytes()?;
+        out.extend_from_slice(&self.response.to_bytes()?);
+        Ok(out)
+    }
+
+    /// Decode a proof produced by [`ChaumPedersenProof::to_bytes`]
+    pub fn from_bytes(group: &EcGroup, bytes: &[u8]) -> Result<Self> {
+        let scalar_len = group.order()?.byte_count()?;
+        if bytes.len() != 2 * scalar_len {
+            return Err(Error::with_message(
+                ErrorType::InvalidInput,
+                format!("Invalid length {} for proof", bytes.len()),
+            ));
+        }
+        let (challenge, response) = bytes.split_at(scalar_len);
+        Ok(Self {
+            challenge: EcScalar::from_bytes(group, challenge)?,
+            response: EcScalar::from_bytes(group, response)?,
+        })
+    }
+}
+
+#[derive(Debug)]
+/// Parameters for Pedersen commitments
+///
+/// A commitment to `v` with blinding factor `r` is `v*G + r*H`. It hides
+/// `v` perfectly, and binds the committer to `v` as long as nobody knows
+/// the discrete logarithm of `H` with respect to `G`.
+///
+/// # Examples
+/// ```
+/// let group = botan::EcGroup::from_name("secp256r1").unwrap();
+/// let mut rng = botan::RandomNumberGenerator::new().unwrap();
+/// let params = botan::PedersenParams::new(&group, b"example").unwrap();
+///
+/// let v1 = botan::EcScalar::random(&group, &mut rng).unwrap();
+/// let v2 = botan::EcScalar::random(&group, &mut rng).unwrap();
+/// let (c1, r1) = params.commit(&v1, &mut rng).unwrap();
+/// let (c2, r2) = params.commit(&v2, &mut rng).unwrap();
+///
+/// // Commitments are additively homomorphic
+/// let sum = &c1 + &c2;
+/// assert!(params.open(&sum, &(&v1 + &v2), &(&r1 + &r2), &mut rng).unwrap());
+/// ```
+pub struct PedersenParams {
+    group: EcGroup,
+    g: EcPoint,
+    h: EcPoint,
+}
+
+impl PedersenParams {
+    /// Create parameters using the group generator as `G` and deriving
+    /// `H` from the label using [`EcGroup::hash_to_curve`]
+    ///
+    /// This ensures nobody knows the discrete logarithm of `H`, but is
+    /// only possible for groups which support hash to curve.
+    pub fn new(group: &EcGroup, label: &[u8]) -> Result<Self> {
+        let g = group.generator()?;
+        let h = group.hash_to_curve(label, b"botan-pedersen-generator")?;
+        Self::from_generators(group, g, h)
+    }
+
+    /// Create parameters from two explicit generators
+    ///
+    /// The caller must ensure the discrete logarithm of `h` relative to
+    /// `g` is unknown; otherwise commitments are not binding.
+    pub fn from_generators(group: &EcGroup, g: EcPoint, h: EcPoint) -> Result<Self> {
+        if g.is_identity()? || h.is_identity()? || g.is_equal(&h)? {
+            return Err(Error::bad_parameter("Invalid Pedersen generators"));
+        }
+        Ok(Self {
+            group: group.clone(),
+            g,
+            h,
+        })
+    }
+
+    /// Return the generator `G` used for the committed value
+    pub fn g(&self) -> &EcPoint {
+        &self.g
+    }
+
+    /// Return the generator `H` used for the blinding factor
+    pub fn h(&self) -> &EcPoint {
+        &self.h
+    }
+
+    /// Commit to a value, returning the commitment and the random blinding factor
+    pub fn commit(
+        &self,
+        value: &EcScalar,
+        rng: &mut RandomNumberGenerator,
+    ) -> Result<(PedersenCommitment, EcScalar)> {
+        let blinding = EcScalar::random(&self.group, rng)?;
+        let commitment = self.commit_with_blinding(value, &blinding, rng)?;
+        Ok((commitment, blinding))
+    }
+
+    /// Commit to a value using the specified blinding factor
+    pub fn commit_with_blinding(
+        &self,
+        value: &EcScalar,
+        blinding: &EcScalar,
+        rng: &mut RandomNumberGenerator,
+    ) -> Result<PedersenCommitment> {
+        let point = self
+            .g
+            .mul(value, rng)?
+            .pt_add(&self.h.mul(blinding, rng)?)?;
+        Ok(PedersenCommitment { point })
+    }
+
+    /// Check that a commitment opens to the specified value and blinding factor
+    pub fn open(
+        &self,
+        commitment: &PedersenCommitment,
+        value: &EcScalar,
+        blinding: &EcScalar,
+        rng: &mut RandomNumberGenerator,
+    ) -> Result<bool> {
+        let expected = self.commit_with_blinding(value, blinding, rng)?;
+        expected.point.is_equal(&commitment.point)
+    }
+}
+
+#[derive(Debug, PartialEq, Eq)]
+/// A Pedersen commitment
+///
+/// Adding two commitments produces a commitment to the sum of the
+/// values, with the sum of the blinding factors.
+pub struct PedersenCommitment {
+    point: EcPoint,
+}
+
+impl PedersenCommitment {
+    /// Create a commitment from its point
+    pub fn from_point(point: EcPoint) -> Self {
+        Self { point }
+    }
+
+    /// Return the commitment point
+    pub fn point(&self) -> &EcPoint {
+        &self.point
+    }
+
+    /// Add two commitments, creating a commitment to the sum of their values
+    pub fn combine(&self, other: &PedersenCommitment) -> Result<PedersenCommitment> {
+        Ok(Self {
+            point: self.point.pt_add(&other.point)?,
+        })
+    }
+
+    /// Return the compressed encoding of the commitment
+    pub fn to_bytes(&self) -> Result<Vec<u8>> {
+        self.point.to_compressed()
+    }
+
+    /// Decode a commitment from a SEC1 encoded point
+    pub fn from_bytes(group: &EcGroup, bytes: &[u8]) -> Result<Self> {
+        Ok(Self {
+            point: EcPoint::from_bytes(group, bytes)?,
+        })
+    }
+}
+
+impl<'b> Add<&'b PedersenCommitment> for &PedersenCommitment {
+    type Output = PedersenCommitment;
+
+    fn add(self, other: &'b PedersenCommitment) -> PedersenCommitment {
+        self.combine(other)
+            .expect("botan_ec_point_add should succeed")
+    }
+}
+
+impl Add<&PedersenCommitment> for PedersenCommitment {
+    type Output = PedersenCommitment;
+
+    fn add(self, other: &PedersenCommitment) -> PedersenCommitment {
+        (&self).add(other)
+    }
+}
+
+impl Add<PedersenCommitment> for &PedersenCommitment {
+    type Output = PedersenCommitment;
+
+    fn add(self, other: PedersenCommitment) -> PedersenCommitment {
+        self.add(&other)
+    }
+}
+
+impl Add<PedersenCommitment> for PedersenCommitment {
+    type Output = PedersenCommitment;
+
+    fn add(self, other: PedersenCommitment) -> PedersenCommitment {
+        (&self).add(&other)
+    }
+}
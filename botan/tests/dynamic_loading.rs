@@ -33,5 +33,61 @@ fn test_dynamic_loading() -> Result<(), botan::Error> {
     assert_eq!(digest[0], 0xBA);
     assert_eq!(digest[31], 0xAD);
 
+    check_ffi_symbol_report()
+}
+
+// Called once the library is loaded, as a separate test could race with
+// the checks of the initial state above
+fn check_ffi_symbol_report() -> Result<(), botan::Error> {
+    let version = botan::Version::current()?;
+    let report = botan::ffi_symbol_report()?;
+
+    assert_eq!(report.api_version, version.ffi_api);
+    assert_eq!(Some(report.library.as_str()), botan::loaded_library_name());
+    assert!(!report.levels.is_empty());
+    assert!(report.levels.windows(2).all(|w| w[0].level < w[1].level));
+    assert_eq!(report.levels[0].botan_release(), Some("2.13"));
+    assert!(
+        report.levels[0]
+            .available
+            .contains(&"botan_ffi_api_version")
+    );
+
+    for level in &report.levels {
+        for name in &level.missing {
+            assert!(report.missing().any(|m| m == *name));
+        }
+    }
+
+    match report.complete_level() {
+        Some(complete) => {
+            botan::require_ffi_level(complete)?;
+            for level in report.levels.iter().filter(|l| l.level <= complete) {
+                assert!(level.missing.is_empty());
+            }
+        }
+        None => assert!(!report.levels[0].missing.is_empty()),
+    }
+
+    // A library which is too old is reported as such
+    let err = botan::require_ffi_level(version.ffi_api + 1).unwrap_err();
+    assert_eq!(err.error_type(), botan::ErrorType::NotImplemented);
+    let msg = err.error_message().expect("has a message");
+    assert!(msg.contains(&report.library), "unexpected message {msg}");
+    assert!(msg.contains("is required"), "unexpected message {msg}");
+
+    // While one which is recent enough but lacks functions is not
+    let incomplete = report
+        .levels
+        .iter()
+        .find(|l| l.level <= version.ffi_api && !l.missing.is_empty());
+    if let Some(level) = incomplete {
+        let err = botan::require_ffi_level(level.level).unwrap_err();
+        assert_eq!(err.error_type(), botan::ErrorType::NotImplemented);
+        let msg = err.error_message().expect("has a message");
+        assert!(!msg.contains("is required"), "unexpected message {msg}");
+        assert!(msg.contains(level.missing[0]), "unexpected message {msg}");
+    }
+
     Ok(())
 }
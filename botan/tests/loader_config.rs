@@ -0,0 +1,160 @@
+#![cfg(feature = "dynamic-loading")]
+
+// This test file runs in its own process, so that it controls which library
+// is loaded, and how
+
+use botan::{LoadError, LoaderConfig};
+
+#[test]
+fn test_loader_config() -> Result<(), botan::Error> {
+    assert!(botan::loaded_library_name().is_none());
+
+    // Nothing is acceptable, but every default candidate is reported
+    let err = LoaderConfig::new()
+        .with_max_version(0, 0, 0)
+        .load()
+        .unwrap_err();
+    let LoadError::NoUsableCandidate(rejected) = err else {
+        panic!("unexpected error {err:?}");
+    };
+    assert!(!rejected.is_empty());
+    assert!(botan::loaded_library_name().is_none());
+
+    // Which is not bypassed by the default search when the library is used
+    let err = botan::Version::current().unwrap_err();
+    assert_eq!(err.error_type(), botan::ErrorType::LibraryNotLoaded);
+    let msg = err.error_message().expect("has a message");
+    assert!(
+        msg.contains(&rejected[0].candidate),
+        "unexpected message {msg}"
+    );
+    assert!(botan::loaded_library_name().is_none());
+
+    // The default search would have found this one
+    let (name, version) = rejected
+        .iter()
+        .find_map(|r| match &r.reason {
+            LoadError::VersionNotAllowed { library, version } => {
+                assert_eq!(*library, r.candidate);
+                Some((library.clone(), *version))
+            }
+            _ => None,
+        })
+        .expect("a library was found");
+    let (major, minor, patch) = version;
+    assert!(major >= 2);
+
+    let err = LoaderConfig::new()
+        .with_candidate("/nonexistent/path/libbotan-3.so")
+        .with_candidate(&name)
+        .with_min_version(major, minor, patch + 1)
+        .load()
+        .unwrap_err();
+    let LoadError::NoUsableCandidate(rejected) = &err else {
+        panic!("unexpected error {err:?}");
+    };
+    assert_eq!(rejected.len(), 2);
+    assert_eq!(rejected[0].candidate, "/nonexistent/path/libbotan-3.so");
+    assert!(matches!(rejected[0].reason, LoadError::LoadFailed(_)));
+    assert_eq!(
+        rejected[1].reason,
+        LoadError::VersionNotAllowed {
+            library: name.clone(),
+            version
+        }
+    );
+    assert!(err.to_string().contains(&name));
+
+    // A pin requires a path, since dlopen decides which file a name refers to
+    let err = LoaderConfig::new()
+        .with_candidate("libbotan-3.so")
+        .with_sha256_pin([0; 32])
+        .load()
+        .unwrap_err();
+    let LoadError::NoUsableCandidate(rejected) = err else {
+        panic!("unexpected error {err:?}");
+    };
+    assert!(matches!(rejected[0].reason, LoadError::LoadFailed(_)));
+
+    // Find the file the default search used, so that it can be pinned
+    let path = find_library(&name);
+
+    if let Some(path) = &path {
+        let err = LoaderConfig::new()
+            .with_candidate(path)
+            .with_sha256_pin([0; 32])
+            .load()
+            .unwrap_err();
+        let LoadError::NoUsableCandidate(rejected) = err else {
+            panic!("unexpected error {err:?}");
+        };
+        let LoadError::DigestMismatch { library, digest } = &rejected[0].reason else {
+            panic!("unexpected error {:?}", rejected[0].reason);
+        };
+        assert_eq!(*library, path.to_string_lossy());
+        let digest = *digest;
+
+        let report = LoaderConfig::new()
+            .with_candidate("/nonexistent/path/libbotan-3.so")
+            .with_candidate(path)
+            .with_sha256_pin(digest)
+            .with_min_version(major, minor, patch)
+            .with_max_version(major, minor, patch)
+            .load()
+            .expect("loads");
+        assert_eq!(report.library, path.to_string_lossy());
+        assert_eq!(report.version, version);
+        assert_eq!(report.rejected.len(), 1);
+        assert_eq!(botan::loaded_library_name(), Some(report.library.as_str()));
+
+        // Now the digest can be checked with the library itself
+        let mut hash = botan::HashFunction::new("SHA-256")?;
+        hash.update(&std::fs::read(path).expect("readable"))?;
+        assert_eq!(hash.finish()?, digest);
+    } else {
+        println!("Could not locate {name}, skipping the SHA-256 pin checks");
+        let report = botan::load_library_with_config(
+            &LoaderConfig::new()
+                .with_candidate(&name)
+                .with_min_version(major, minor, patch),
+        )?;
+        assert_eq!(report.library, name);
+        assert!(report.rejected.is_empty());
+    }
+
+    let v = botan::Version::current()?;
+    assert_eq!((v.major, v.minor, v.patch), version);
+
+    // Once loaded the library cannot be replaced
+    let err = botan::load_library_with_config(&LoaderConfig::new()).unwrap_err();
+    assert_eq!(err.error_type(), botan::ErrorType::InvalidObjectState);
+
+    Ok(())
+}
+
+/// Search the usual library directories for a library loaded by name
+fn find_library(name: &str) -> Option<std::path::PathBuf> {
+    let name = std::path::Path::new(name);
+    if name.is_absolute() {
+        return Some(name.to_path_buf());
+    }
+
+    let mut dirs: Vec<std::path::PathBuf> = Vec::new();
+    for var in ["LD_LIBRARY_PATH", "DYLD_LIBRARY_PATH", "PATH"] {
+        if let Some(value) = std::env::var_os(var) {
+            dirs.extend(std::env::split_paths(&value));
+        }
+    }
+    for dir in [
+        "/usr/local/lib",
+        "/usr/local/lib64",
+        "/usr/lib",
+        "/usr/lib64",
+        "/usr/lib/x86_64-linux-gnu",
+        "/usr/lib/aarch64-linux-gnu",
+    ] {
+        dirs.push(dir.into());
+    }
+
+    dirs.into_iter().map(|d| d.join(name)).find(|p| p.is_file())
+}